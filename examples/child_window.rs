@@ -1,4 +1,4 @@
-#[cfg(any(x11_platform, macos_platform, windows_platform))]
+#[cfg(any(x11_platform, wayland_platform, macos_platform, windows_platform))]
 #[allow(deprecated)]
 fn main() -> Result<(), impl std::error::Error> {
     use std::collections::HashMap;
@@ -16,6 +16,9 @@ fn main() -> Result<(), impl std::error::Error> {
     #[derive(Default)]
     struct Application {
         parent_window_id: Option<WindowId>,
+        /// The child window shown above the others.
+        top_child_id: Option<WindowId>,
+        child_colors: HashMap<WindowId, u32>,
         windows: HashMap<WindowId, Box<dyn Window>>,
     }
 
@@ -47,9 +50,8 @@ fn main() -> Result<(), impl std::error::Error> {
                 WindowEvent::PointerEntered { device_id: _, .. } => {
                     // On x11, println when the cursor entered in a window even if the child window
                     // is created by some key inputs.
-                    // the child windows are always placed at (0, 0) with size (200, 200) in the
-                    // parent window, so we also can see this log when we move
-                    // the cursor around (200, 200) in parent window.
+                    // the child windows are placed diagonally with size (200, 200) in the parent
+                    // window, so we also can see this log when we move the cursor across them.
                     println!("cursor entered in the window {window_id:?}");
                 },
                 WindowEvent::KeyboardInput {
@@ -57,14 +59,38 @@ fn main() -> Result<(), impl std::error::Error> {
                     ..
                 } => {
                     let parent_window = self.windows.get(&self.parent_window_id.unwrap()).unwrap();
-                    let child_window = spawn_child_window(parent_window.as_ref(), event_loop);
+                    // Tell the overlapping child windows apart by their color.
+                    const CHILD_COLORS: [u32; 3] = [0xff803030, 0xff308030, 0xff303080];
+                    let index = self.windows.len() - 1;
+                    let offset = 40.0 * index as f64;
+                    let child_window =
+                        spawn_child_window(parent_window.as_ref(), event_loop, offset);
                     let child_id = child_window.id();
                     println!("Child window created with id: {child_id:?}");
                     self.windows.insert(child_id, child_window);
+                    self.top_child_id = Some(child_id);
+                    self.child_colors.insert(child_id, CHILD_COLORS[index % CHILD_COLORS.len()]);
+                },
+                // Raise the pressed child window above the others.
+                #[cfg(wayland_platform)]
+                WindowEvent::PointerButton { state: ElementState::Pressed, .. }
+                    if Some(window_id) != self.parent_window_id
+                        && Some(window_id) != self.top_child_id =>
+                {
+                    use winit::platform::wayland::WindowExtWayland;
+
+                    let window = self.windows.get(&window_id).unwrap();
+                    let top_child = self.windows.get(&self.top_child_id.unwrap()).unwrap();
+                    if window.place_subsurface_above(top_child.as_ref()).is_ok() {
+                        self.top_child_id = Some(window_id);
+                    }
                 },
                 WindowEvent::RedrawRequested => {
                     if let Some(window) = self.windows.get(&window_id) {
-                        fill::fill_window(window.as_ref());
+                        match self.child_colors.get(&window_id) {
+                            Some(&color) => fill::fill_window_with_color(window.as_ref(), color),
+                            None => fill::fill_window(window.as_ref()),
+                        }
                     }
                 },
                 _ => (),
@@ -75,27 +101,37 @@ fn main() -> Result<(), impl std::error::Error> {
     fn spawn_child_window(
         parent: &dyn Window,
         event_loop: &dyn ActiveEventLoop,
+        offset: f64,
     ) -> Box<dyn Window> {
         let parent = parent.raw_window_handle().unwrap();
         let mut window_attributes = WindowAttributes::default()
             .with_title("child window")
             .with_surface_size(LogicalSize::new(200.0f32, 200.0f32))
-            .with_position(Position::Logical(LogicalPosition::new(0.0, 0.0)))
+            .with_position(Position::Logical(LogicalPosition::new(offset, offset)))
             .with_visible(true);
         // `with_parent_window` is unsafe. Parent window must be a valid window.
         window_attributes = unsafe { window_attributes.with_parent_window(Some(parent)) };
 
-        event_loop.create_window(window_attributes).unwrap()
+        let window = event_loop.create_window(window_attributes).unwrap();
+
+        // Let the child window present on its own, rather than along with its parent.
+        #[cfg(wayland_platform)]
+        {
+            use winit::platform::wayland::WindowExtWayland;
+            let _ = window.set_subsurface_sync(false);
+        }
+
+        window
     }
 
     let event_loop = EventLoop::new().unwrap();
     event_loop.run_app(Application::default())
 }
 
-#[cfg(not(any(x11_platform, macos_platform, windows_platform)))]
+#[cfg(not(any(x11_platform, wayland_platform, macos_platform, windows_platform)))]
 fn main() {
     panic!(
-        "This example is supported only on x11, macOS, and Windows, with the `rwh_06` feature \
-         enabled."
+        "This example is supported only on x11, Wayland, macOS, and Windows, with the `rwh_06` \
+         feature enabled."
     );
 }
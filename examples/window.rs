@@ -16,6 +16,8 @@ use rwh_06::{DisplayHandle, HasDisplayHandle};
 #[cfg(not(android_platform))]
 use softbuffer::{Context, Surface};
 use winit::application::ApplicationHandler;
+use winit::clipboard::ClipboardData;
+use winit::dnd::{DragActions, DragData};
 use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize};
 use winit::error::RequestError;
 use winit::event::{DeviceEvent, DeviceId, Ime, MouseButton, MouseScrollDelta, WindowEvent};
@@ -313,6 +315,31 @@ impl Application {
                 self.sender.send(Action::Message).unwrap();
                 event_loop.create_proxy().wake_up();
             },
+            Action::CopyToClipboard => {
+                let text = format!("Hello from {window_id:?}");
+                if let Err(err) = window.window.set_clipboard(vec![ClipboardData::text(text)]) {
+                    error!("Error setting the clipboard: {err}");
+                }
+            },
+            Action::PasteFromClipboard => {
+                if let Err(err) = window.window.request_clipboard(&[ClipboardData::TEXT_MIME_TYPE])
+                {
+                    error!("Error requesting the clipboard: {err}");
+                }
+            },
+            Action::PastePrimarySelection => {
+                let mime_types = [ClipboardData::TEXT_MIME_TYPE];
+                if let Err(err) = window.window.request_primary_selection(&mime_types) {
+                    error!("Error requesting the primary selection: {err}");
+                }
+            },
+            Action::DragText => {
+                let text = format!("Dragged from {window_id:?}");
+                let data = DragData::new(vec![ClipboardData::text(text)]);
+                if let Err(err) = window.window.start_drag(data, None) {
+                    error!("Error starting the drag: {err}");
+                }
+            },
         }
     }
 
@@ -452,6 +479,9 @@ impl ApplicationHandler for Application {
                 window.modifiers = modifiers.state();
                 info!("Modifiers changed to {:?}", window.modifiers);
             },
+            WindowEvent::KeyboardLayoutChanged(layout) => {
+                info!("Keyboard layout changed to {layout:?}");
+            },
             WindowEvent::MouseWheel { delta, .. } => match delta {
                 MouseScrollDelta::LineDelta(x, y) => {
                     info!("Mouse wheel Line Delta: ({x},{y})");
@@ -540,13 +570,41 @@ impl ApplicationHandler for Application {
             WindowEvent::DoubleTapGesture { .. } => {
                 info!("Smart zoom");
             },
+            WindowEvent::ClipboardDone { data, .. } => match data {
+                Ok(data) => info!("Clipboard contents: {:?}", data.as_text().unwrap_or_default()),
+                Err(err) => info!("Failed to read the clipboard: {err}"),
+            },
+            WindowEvent::DragSource { event, .. } => {
+                info!("Drag source: {event:?}");
+            },
+            WindowEvent::DragEntered { mime_types, actions, position } => {
+                info!("Drag entered at {position:?} with {mime_types:?}, allowing {actions:?}");
+                let text = [ClipboardData::TEXT_MIME_TYPE, "text/plain"];
+                if let Some(mime_type) = mime_types.iter().find(|m| text.contains(&m.as_str())) {
+                    if let Err(err) = window.window.accept_drop(mime_type, DragActions::COPY) {
+                        error!("Error accepting the drop: {err}");
+                    }
+                }
+            },
+            WindowEvent::DragDropped { position } => {
+                info!("Dropped at {position:?}");
+                let mime_types = [ClipboardData::TEXT_MIME_TYPE, "text/plain"];
+                if let Err(err) = window.window.request_drop_data(&mime_types) {
+                    error!("Error requesting the dropped data: {err}");
+                }
+            },
+            WindowEvent::DragLeft => {
+                info!("Drag left");
+            },
             WindowEvent::TouchpadPressure { .. }
             | WindowEvent::HoveredFileCancelled
             | WindowEvent::KeyboardInput { .. }
             | WindowEvent::PointerEntered { .. }
             | WindowEvent::DroppedFile(_)
             | WindowEvent::HoveredFile(_)
+            | WindowEvent::DragMoved { .. }
             | WindowEvent::Destroyed
+            | WindowEvent::PopupDismissed
             | WindowEvent::Moved(_) => (),
         }
     }
@@ -1036,6 +1094,10 @@ enum Action {
     RequestResize,
     DumpMonitors,
     Message,
+    CopyToClipboard,
+    PasteFromClipboard,
+    PastePrimarySelection,
+    DragText,
 }
 
 impl Action {
@@ -1080,6 +1142,10 @@ impl Action {
                  information"
             },
             Action::Message => "Prints a message through a user wake up",
+            Action::CopyToClipboard => "Copy a message to the clipboard",
+            Action::PasteFromClipboard => "Print the text on the clipboard",
+            Action::PastePrimarySelection => "Print the text of the primary selection",
+            Action::DragText => "Drag a message out of the window",
         }
     }
 }
@@ -1231,10 +1297,15 @@ const KEY_BINDINGS: &[Binding<&'static str>] = &[
     #[cfg(macos_platform)]
     Binding::new("O", ModifiersState::CONTROL, Action::CycleOptionAsAlt),
     Binding::new("S", ModifiersState::CONTROL, Action::Message),
+    // V.
+    Binding::new("V", ModifiersState::ALT, Action::CopyToClipboard),
+    Binding::new("V", ModifiersState::CONTROL, Action::PasteFromClipboard),
 ];
 
 const MOUSE_BINDINGS: &[Binding<MouseButton>] = &[
     Binding::new(MouseButton::Left, ModifiersState::ALT, Action::DragResizeWindow),
     Binding::new(MouseButton::Left, ModifiersState::CONTROL, Action::DragWindow),
     Binding::new(MouseButton::Right, ModifiersState::CONTROL, Action::ShowWindowMenu),
+    Binding::new(MouseButton::Middle, ModifiersState::empty(), Action::PastePrimarySelection),
+    Binding::new(MouseButton::Left, ModifiersState::SHIFT, Action::DragText),
 ];
@@ -0,0 +1,137 @@
+//! Types used to exchange data through the system clipboard.
+//!
+//! The clipboard is accessed through a [`Window`], see [`Window::set_clipboard`] and
+//! [`Window::request_clipboard`]. Reading is asynchronous, the contents are delivered back with
+//! the [`WindowEvent::ClipboardDone`] event, so large transfers never block the event loop.
+//!
+//! [`Window`]: crate::window::Window
+//! [`Window::set_clipboard`]: crate::window::Window::set_clipboard
+//! [`Window::request_clipboard`]: crate::window::Window::request_clipboard
+//! [`WindowEvent::ClipboardDone`]: crate::event::WindowEvent::ClipboardDone
+
+use std::error::Error;
+use std::fmt;
+
+/// A single representation of clipboard contents.
+///
+/// Applications usually offer the same content in several representations, e.g. an image as
+/// `image/png` and `image/bmp`, so the receiving application can pick the one it understands
+/// best.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClipboardData {
+    /// The MIME type of [`data`][Self::data], e.g. `text/plain;charset=utf-8`.
+    pub mime_type: String,
+    /// The raw bytes of the content.
+    pub data: Vec<u8>,
+}
+
+impl ClipboardData {
+    /// The MIME type used for UTF-8 encoded plain text.
+    pub const TEXT_MIME_TYPE: &'static str = "text/plain;charset=utf-8";
+
+    /// Creates clipboard data with the given MIME type.
+    pub fn new(mime_type: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self { mime_type: mime_type.into(), data: data.into() }
+    }
+
+    /// Creates clipboard data holding UTF-8 text.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::new(Self::TEXT_MIME_TYPE, text.into().into_bytes())
+    }
+
+    /// Returns the content as a string, if the MIME type is textual and the data is valid UTF-8.
+    pub fn as_text(&self) -> Option<&str> {
+        if is_text_mime_type(&self.mime_type) {
+            std::str::from_utf8(&self.data).ok()
+        } else {
+            None
+        }
+    }
+}
+
+/// An error that occurred while reading the clipboard.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ClipboardError {
+    /// Nothing is stored on the clipboard.
+    Empty,
+    /// The clipboard contents are not available in any of the requested MIME types.
+    NoMatchingMimeType,
+    /// The transfer from the application owning the clipboard failed.
+    TransferFailed,
+}
+
+impl fmt::Display for ClipboardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("the clipboard is empty"),
+            Self::NoMatchingMimeType => {
+                f.write_str("the clipboard contents are not available in any requested MIME type")
+            },
+            Self::TransferFailed => f.write_str("failed to transfer the clipboard contents"),
+        }
+    }
+}
+
+impl Error for ClipboardError {}
+
+/// Whether the MIME type describes text.
+///
+/// Besides the MIME types, this also covers the X11 target names used for text, which are
+/// offered on Wayland as well for compatibility with Xwayland clients.
+pub(crate) fn is_text_mime_type(mime_type: &str) -> bool {
+    matches!(
+        mime_type,
+        "text/plain" | ClipboardData::TEXT_MIME_TYPE | "UTF8_STRING" | "STRING" | "TEXT"
+    )
+}
+
+/// Encodes UTF-8 text as Latin-1, which is used by the X11 `STRING` target.
+pub(crate) fn utf8_to_latin1(data: &[u8]) -> Vec<u8> {
+    String::from_utf8_lossy(data).chars().map(|c| u8::try_from(c).unwrap_or(b'?')).collect()
+}
+
+/// Decodes Latin-1 text to UTF-8.
+#[cfg_attr(not(x11_platform), allow(dead_code))]
+pub(crate) fn latin1_to_utf8(data: &[u8]) -> Vec<u8> {
+    data.iter().map(|&byte| char::from(byte)).collect::<String>().into_bytes()
+}
+
+/// Finds the entry that should be handed out for `mime_type`.
+///
+/// Text is interchangeable between all the text MIME types, so any text entry is used when there
+/// is no exact match.
+pub(crate) fn find_data<'a>(
+    contents: &'a [ClipboardData],
+    mime_type: &str,
+) -> Option<&'a ClipboardData> {
+    contents.iter().find(|entry| entry.mime_type == mime_type).or_else(|| {
+        if is_text_mime_type(mime_type) {
+            contents.iter().find(|entry| is_text_mime_type(&entry.mime_type))
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_is_interchangeable() {
+        let contents = [ClipboardData::new("image/png", [0x89]), ClipboardData::text("foo")];
+
+        assert_eq!(find_data(&contents, "image/png"), Some(&contents[0]));
+        assert_eq!(find_data(&contents, "UTF8_STRING"), Some(&contents[1]));
+        assert_eq!(find_data(&contents, "text/plain"), Some(&contents[1]));
+        assert_eq!(find_data(&contents, "image/bmp"), None);
+        assert_eq!(find_data(&contents[..1], "text/plain"), None);
+    }
+
+    #[test]
+    fn latin1_round_trip() {
+        assert_eq!(utf8_to_latin1("héllo €".as_bytes()), b"h\xe9llo ?");
+        assert_eq!(latin1_to_utf8(b"h\xe9llo"), "héllo".as_bytes());
+    }
+}
@@ -0,0 +1,70 @@
+//! Types used for drag and drop.
+//!
+//! A drag is started from a [`Window`] with [`Window::start_drag`], the progress of the drag is
+//! reported back with [`WindowEvent::DragSource`].
+//!
+//! [`Window`]: crate::window::Window
+//! [`Window::start_drag`]: crate::window::Window::start_drag
+//! [`WindowEvent::DragSource`]: crate::event::WindowEvent::DragSource
+
+use bitflags::bitflags;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::clipboard::ClipboardData;
+
+bitflags! {
+    /// The actions that can be performed with the dragged data when it's dropped.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct DragActions: u8 {
+        /// The data is copied to the target.
+        const COPY = 0b001;
+        /// The data is moved to the target, the source should remove it afterwards.
+        const MOVE = 0b010;
+        /// The target creates a link to the data.
+        const LINK = 0b100;
+    }
+}
+
+/// The data offered by a drag.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DragData {
+    /// The representations of the dragged data, see [`ClipboardData`].
+    pub contents: Vec<ClipboardData>,
+    /// The actions the target may choose from.
+    pub actions: DragActions,
+}
+
+impl DragData {
+    /// Creates drag data that can be copied.
+    pub fn new(contents: Vec<ClipboardData>) -> Self {
+        Self { contents, actions: DragActions::COPY }
+    }
+
+    /// Sets the actions the target may choose from.
+    pub fn with_actions(mut self, actions: DragActions) -> Self {
+        self.actions = actions;
+        self
+    }
+}
+
+/// The progress of a drag started from one of our windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DragSourceEvent {
+    /// The target under the pointer changed the action it would perform on drop.
+    ///
+    /// Contains at most one action, it is empty when the target doesn't accept the data.
+    Action(DragActions),
+    /// The data was dropped on a target which accepted it.
+    ///
+    /// The target now transfers the data, which is finished with [`DragSourceEvent::Finished`].
+    Dropped,
+    /// The target is done with the data.
+    ///
+    /// Contains the action that was performed, if the target reports it. When it is
+    /// [`DragActions::MOVE`], the source should remove the data.
+    Finished(DragActions),
+    /// The drag ended without a drop, or the target rejected the drop.
+    Cancelled,
+}
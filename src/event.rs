@@ -45,10 +45,14 @@ use smol_str::SmolStr;
 #[cfg(web_platform)]
 use web_time::Instant;
 
+use crate::clipboard::{ClipboardData, ClipboardError};
+use crate::dnd::{DragActions, DragSourceEvent};
 use crate::dpi::{PhysicalPosition, PhysicalSize};
 use crate::error::RequestError;
 use crate::event_loop::AsyncRequestSerial;
-use crate::keyboard::{self, ModifiersKeyState, ModifiersKeys, ModifiersState};
+use crate::keyboard::{
+    self, KeyRepeatInfo, LockKeys, ModifiersKeyState, ModifiersKeys, ModifiersState,
+};
 use crate::platform_impl;
 #[cfg(doc)]
 use crate::window::Window;
@@ -148,6 +152,26 @@ pub enum WindowEvent {
     /// [`request_activation_token`]: crate::platform::startup_notify::WindowExtStartupNotify::request_activation_token
     ActivationTokenDone { serial: AsyncRequestSerial, token: ActivationToken },
 
+    /// The clipboard or primary selection contents were delivered back.
+    ///
+    /// Delivered in response to [`Window::request_clipboard`],
+    /// [`Window::request_primary_selection`] and [`Window::request_drop_data`], the `serial`
+    /// matches the one returned from that call.
+    ///
+    /// [`Window::request_clipboard`]: crate::window::Window::request_clipboard
+    /// [`Window::request_primary_selection`]: crate::window::Window::request_primary_selection
+    /// [`Window::request_drop_data`]: crate::window::Window::request_drop_data
+    ClipboardDone { serial: AsyncRequestSerial, data: Result<ClipboardData, ClipboardError> },
+
+    /// A drag started from this window made progress.
+    ///
+    /// Delivered for drags started with [`Window::start_drag`], the `serial` matches the one
+    /// returned from that call. The drag is over after [`DragSourceEvent::Finished`] or
+    /// [`DragSourceEvent::Cancelled`].
+    ///
+    /// [`Window::start_drag`]: crate::window::Window::start_drag
+    DragSource { serial: AsyncRequestSerial, event: DragSourceEvent },
+
     /// The size of the window's surface has changed.
     ///
     /// Contains the new dimensions of the surface (can also be retrieved with
@@ -172,6 +196,14 @@ pub enum WindowEvent {
     /// The window has been destroyed.
     Destroyed,
 
+    /// The popup was dismissed by the system, such as after a click outside of the windows of the
+    /// application.
+    ///
+    /// The popup is hidden and should be dropped. See [`WindowAttributes::with_popup`].
+    ///
+    /// [`WindowAttributes::with_popup`]: crate::window::WindowAttributes::with_popup
+    PopupDismissed,
+
     /// A file has been dropped into the window.
     ///
     /// When the user drops multiple files at once, this event will be emitted for each file
@@ -190,6 +222,42 @@ pub enum WindowEvent {
     /// hovered.
     HoveredFileCancelled,
 
+    /// A drag entered the window.
+    ///
+    /// `mime_types` are the representations the dragged data is offered in, `actions` are the
+    /// actions the source allows and `position` is the pointer position in surface coordinates.
+    ///
+    /// The drop is rejected unless the application accepts it with [`Window::accept_drop`],
+    /// except for `text/uri-list`, which is accepted by default to deliver
+    /// [`WindowEvent::DroppedFile`]. The data can be inspected while hovering with
+    /// [`Window::request_drop_data`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **iOS / Android / macOS / Orbital / Web / Windows:** Unsupported.
+    ///
+    /// [`Window::accept_drop`]: crate::window::Window::accept_drop
+    /// [`Window::request_drop_data`]: crate::window::Window::request_drop_data
+    DragEntered { mime_types: Vec<String>, actions: DragActions, position: PhysicalPosition<f64> },
+
+    /// The drag over the window moved.
+    ///
+    /// `actions` may change while dragging, e.g. when the user presses a modifier.
+    DragMoved { actions: DragActions, position: PhysicalPosition<f64> },
+
+    /// The drag was dropped on the window.
+    ///
+    /// Only delivered when the drop was accepted with [`Window::accept_drop`]. The data should be
+    /// requested with [`Window::request_drop_data`] in response to this event, the source is told
+    /// that the drop is finished once all requested data has been transferred.
+    ///
+    /// [`Window::accept_drop`]: crate::window::Window::accept_drop
+    /// [`Window::request_drop_data`]: crate::window::Window::request_drop_data
+    DragDropped { position: PhysicalPosition<f64> },
+
+    /// The drag left the window, or was dropped without being accepted.
+    DragLeft,
+
     /// The window gained or lost focus.
     ///
     /// The parameter is true if the window has gained focus, and false if it has lost focus.
@@ -218,8 +286,23 @@ pub enum WindowEvent {
     },
 
     /// The keyboard modifiers have changed.
+    ///
+    /// This includes the lock keys, such as caps lock, see [`Modifiers::locked_keys`].
     ModifiersChanged(Modifiers),
 
+    /// The user switched the active keyboard layout, for example from English to Russian.
+    ///
+    /// The logical keys of the following [`KeyboardInput`] events are those of the new layout. See
+    /// [`ActiveEventLoop::keyboard_layouts()`] for all the layouts.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    ///
+    /// [`KeyboardInput`]: Self::KeyboardInput
+    /// [`ActiveEventLoop::keyboard_layouts()`]: crate::event_loop::ActiveEventLoop::keyboard_layouts
+    KeyboardLayoutChanged(keyboard::KeyboardLayout),
+
     /// An event from an input method.
     ///
     /// **Note:** You have to explicitly enable this event using [`Window::set_ime_allowed`].
@@ -309,7 +392,40 @@ pub enum WindowEvent {
     },
 
     /// A mouse wheel movement or touchpad scroll occurred.
-    MouseWheel { device_id: Option<DeviceId>, delta: MouseScrollDelta, phase: TouchPhase },
+    ///
+    /// Scrolling with fingers or continuous devices starts with [`TouchPhase::Started`] and
+    /// finishes with [`TouchPhase::Ended`] once the fingers are lifted, which is when kinetic
+    /// scrolling should begin. The event ending the scroll may have no delta. Wheels only report
+    /// [`TouchPhase::Moved`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Only [`TouchPhase::Moved`] is reported.
+    MouseWheel {
+        device_id: Option<DeviceId>,
+        delta: MouseScrollDelta,
+        phase: TouchPhase,
+        /// The kind of motion producing the scroll, when known.
+        ///
+        /// ## Platform-specific
+        ///
+        /// - **X11:** Touchpads are reported as [`ScrollSource::Finger`], other devices as
+        ///   [`ScrollSource::Wheel`].
+        /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always [`None`].
+        source: Option<ScrollSource>,
+        /// The horizontal and vertical scroll in fractions of a wheel detent, where 120 is a whole
+        /// detent, for wheels.
+        ///
+        /// High-resolution wheels scroll by a part of a detent at a time. The sign convention is
+        /// the one of [`MouseScrollDelta::LineDelta`].
+        ///
+        /// ## Platform-specific
+        ///
+        /// - **Wayland:** Only whole detents are reported when the compositor doesn't support
+        ///   version 8 of `wl_pointer`.
+        /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always [`None`].
+        value120: Option<(i32, i32)>,
+    },
 
     /// An mouse button press has been received.
     PointerButton {
@@ -341,8 +457,10 @@ pub enum WindowEvent {
     ///
     /// ## Platform-specific
     ///
-    /// - Only available on **macOS** and **iOS**.
+    /// - Only available on **macOS**, **iOS**, **X11** and **Wayland**.
     /// - On iOS, not recognized by default. It must be enabled when needed.
+    /// - On X11, requires XInput 2.4, as provided by libinput touchpads.
+    /// - On Wayland, requires the compositor to support the pointer gestures protocol.
     PinchGesture {
         device_id: Option<DeviceId>,
         /// Positive values indicate magnification (zooming in) and  negative
@@ -357,8 +475,13 @@ pub enum WindowEvent {
     ///
     /// ## Platform-specific
     ///
-    /// - Only available on **iOS**.
+    /// - Only available on **iOS**, **X11** and **Wayland**.
     /// - On iOS, not recognized by default. It must be enabled when needed.
+    /// - On X11, requires XInput 2.4. Touchpads usually scroll with two fingers, so only swipes
+    ///   with three or more fingers are reported.
+    /// - On Wayland, only swipes with three or more fingers are reported as well. Holding fingers
+    ///   still on the touchpad is reported as a gesture without movement, which can be used to
+    ///   stop kinetic scrolling.
     PanGesture {
         device_id: Option<DeviceId>,
         /// Change in pixels of pan gesture from last update.
@@ -393,8 +516,9 @@ pub enum WindowEvent {
     ///
     /// ## Platform-specific
     ///
-    /// - Only available on **macOS** and **iOS**.
+    /// - Only available on **macOS**, **iOS**, **X11** and **Wayland**.
     /// - On iOS, not recognized by default. It must be enabled when needed.
+    /// - On X11 and Wayland, the rotation is part of the pinch gesture.
     RotationGesture {
         device_id: Option<DeviceId>,
         /// change in rotation in degrees
@@ -499,6 +623,12 @@ pub enum PointerKind {
     ///
     /// **macOS:** Unsupported.
     Touch(FingerId),
+    /// See [`PointerSource::Pen`] for more details.
+    ///
+    /// ## Platform-specific
+    ///
+    /// **Android / iOS / macOS / Orbital / Windows:** Unsupported.
+    Pen(PenTool),
     Unknown,
 }
 
@@ -549,6 +679,26 @@ pub enum PointerSource {
         ///   force will be 0.5 when a button is pressed or 0.0 otherwise.
         force: Option<Force>,
     },
+    /// Represents the pen, stylus or eraser of a drawing tablet.
+    ///
+    /// A [`WindowEvent::PointerEntered`] event is emitted when the tool comes into proximity of
+    /// the window, followed by [`WindowEvent::PointerMoved`] events when the tool is moved or
+    /// any of its axes changes. The tip touching the surface is reported as a
+    /// [`WindowEvent::PointerButton`] with [`PenButton::Contact`], the buttons on the barrel of
+    /// the pen with [`PenButton::Barrel`] and [`PenButton::SecondaryBarrel`]. A
+    /// [`WindowEvent::PointerLeft`] event is emitted when the tool leaves proximity.
+    ///
+    /// Tablets without proximity detection only report the tool while it touches the surface.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Windows:** Unsupported.
+    /// - **X11:** The axes are read from the valuators of the tablet device, which are identified
+    ///   by their labels, as set by the `wacom` and `libinput` drivers.
+    Pen {
+        tool: PenTool,
+        data: PenData,
+    },
     Unknown,
 }
 
@@ -557,6 +707,7 @@ impl From<PointerSource> for PointerKind {
         match source {
             PointerSource::Mouse => Self::Mouse,
             PointerSource::Touch { finger_id, .. } => Self::Touch(finger_id),
+            PointerSource::Pen { tool, .. } => Self::Pen(tool),
             PointerSource::Unknown => Self::Unknown,
         }
     }
@@ -578,6 +729,12 @@ pub enum ButtonSource {
         finger_id: FingerId,
         force: Option<Force>,
     },
+    /// See [`PointerSource::Pen`] for more details.
+    Pen {
+        tool: PenTool,
+        button: PenButton,
+        data: PenData,
+    },
     Unknown(u16),
 }
 
@@ -589,6 +746,12 @@ impl ButtonSource {
         match self {
             ButtonSource::Mouse(mouse) => mouse,
             ButtonSource::Touch { .. } => MouseButton::Left,
+            ButtonSource::Pen { button, .. } => match button {
+                PenButton::Contact => MouseButton::Left,
+                PenButton::Barrel => MouseButton::Right,
+                PenButton::SecondaryBarrel => MouseButton::Middle,
+                PenButton::Other(button) => MouseButton::Other(button),
+            },
             ButtonSource::Unknown(button) => match button {
                 0 => MouseButton::Left,
                 1 => MouseButton::Middle,
@@ -607,6 +770,56 @@ impl From<MouseButton> for ButtonSource {
     }
 }
 
+/// The tool of a [`PointerSource::Pen`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PenTool {
+    /// The tip of a pen or stylus.
+    Pen,
+    /// An eraser, either a dedicated tool or the inverted end of a pen.
+    Eraser,
+}
+
+/// A button of a [`ButtonSource::Pen`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PenButton {
+    /// The tip of the tool touched or left the surface.
+    Contact,
+    /// The first button on the barrel, usually the one closest to the tip.
+    Barrel,
+    /// The second button on the barrel.
+    SecondaryBarrel,
+    /// Any other button, identified by a platform specific code.
+    Other(u16),
+}
+
+/// The state of the axes of a [`PointerSource::Pen`].
+///
+/// Axes which aren't reported by the hardware are [`None`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PenData {
+    /// How hard the tip is pressed against the surface.
+    pub force: Option<Force>,
+    /// The tilt of the pen away from the perpendicular to the surface.
+    pub tilt: Option<PenTilt>,
+    /// The clockwise rotation of the pen around its own axis, in degrees in the range `[0, 360)`.
+    pub twist: Option<f64>,
+    /// The distance of the tip from the surface, normalized to `[0, 1]`.
+    pub distance: Option<f64>,
+}
+
+/// The tilt of a pen, in degrees in the range `[-90, 90]`.
+///
+/// A pen perpendicular to the surface has a tilt of `0` on both axes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PenTilt {
+    /// The angle between the pen and the perpendicular in the plane of the X axis, positive when
+    /// the top of the pen leans to the right.
+    pub x: f64,
+    /// The angle between the pen and the perpendicular in the plane of the Y axis, positive when
+    /// the top of the pen leans towards the user.
+    pub y: f64,
+}
+
 /// Identifier of an input device.
 ///
 /// Whenever you receive an event arising from a particular input device, this event contains a
@@ -634,6 +847,78 @@ impl DeviceId {
     }
 }
 
+/// The kind of an input device.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DeviceKind {
+    Keyboard,
+    /// A mouse or another device moving the pointer, such as a trackball.
+    Pointer,
+    Touchscreen,
+    Touchpad,
+    /// A drawing tablet, or one of its pens, erasers or pads.
+    Tablet,
+}
+
+/// Information about an input device.
+///
+/// See [`ActiveEventLoop::available_devices()`] and [`ActiveEventLoop::device_info()`].
+///
+/// [`ActiveEventLoop::available_devices()`]: crate::event_loop::ActiveEventLoop::available_devices
+/// [`ActiveEventLoop::device_info()`]: crate::event_loop::ActiveEventLoop::device_info
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DeviceInfo {
+    pub(crate) id: DeviceId,
+    pub(crate) kind: DeviceKind,
+    pub(crate) name: Option<String>,
+    pub(crate) vendor_product_id: Option<(u16, u16)>,
+}
+
+impl DeviceInfo {
+    /// The identifier of the device in the events it produces.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** The identifier is the protocol id of the object representing the device, such
+    ///   as the seat's `wl_pointer` or a tablet. Object ids are reused, so after a device is
+    ///   removed its id may later identify another device.
+    pub fn id(&self) -> DeviceId {
+        self.id
+    }
+
+    pub fn kind(&self) -> DeviceKind {
+        self.kind
+    }
+
+    /// The human-readable name of the device.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Only tablets have a name.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The USB or Bluetooth vendor id of the device.
+    ///
+    /// Along with [`product_id()`][Self::product_id], this identifies the model of the device
+    /// across connections, unlike [`id()`][Self::id].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Only tablets have vendor and product ids.
+    pub fn vendor_id(&self) -> Option<u16> {
+        self.vendor_product_id.map(|(vendor_id, _)| vendor_id)
+    }
+
+    /// The USB or Bluetooth product id of the device.
+    ///
+    /// See [`vendor_id()`][Self::vendor_id].
+    pub fn product_id(&self) -> Option<u16> {
+        self.vendor_product_id.map(|(_, product_id)| product_id)
+    }
+}
+
 /// Identifier of a finger in a touch event.
 ///
 /// Whenever a touch event is received it contains a `FingerId` which uniquely identifies the finger
@@ -669,6 +954,29 @@ impl FingerId {
 /// Note that these events are delivered regardless of input focus.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum DeviceEvent {
+    /// An input device was connected.
+    ///
+    /// Use [`ActiveEventLoop::device_info()`] to get its information. Devices that were connected
+    /// before the event loop started may not be reported, see
+    /// [`ActiveEventLoop::available_devices()`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    /// - **Wayland:** Keyboards, pointers and touchscreens are those of the seats, which may
+    ///   aggregate several physical devices. They are added once a seat gains the capability.
+    ///
+    /// [`ActiveEventLoop::device_info()`]: crate::event_loop::ActiveEventLoop::device_info
+    /// [`ActiveEventLoop::available_devices()`]: crate::event_loop::ActiveEventLoop::available_devices
+    Added,
+
+    /// An input device was disconnected.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    Removed,
+
     /// Change in physical position of a pointing device.
     ///
     /// This represents raw, unfiltered physical motion. Not to be confused with
@@ -707,6 +1015,86 @@ pub enum DeviceEvent {
     },
 
     Key(RawKeyEvent),
+
+    /// How held keys of the keyboard repeat changed.
+    ///
+    /// See [`ActiveEventLoop::key_repeat_info()`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    ///
+    /// [`ActiveEventLoop::key_repeat_info()`]: crate::event_loop::ActiveEventLoop::key_repeat_info
+    KeyRepeatInfoChanged(KeyRepeatInfo),
+
+    /// A button of a tablet pad was pressed or released.
+    ///
+    /// `button` is the index of the button on the pad. The buttons, rings and strips of a pad are
+    /// split into mode groups, `group` is the index of the group the button belongs to and `mode`
+    /// the current mode of that group, see [`DeviceEvent::PadModeChanged`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    /// - **X11:** Pads have a single mode group with a single mode. The X11 buttons 4 to 7 are
+    ///   reserved for scrolling, so the button indices skip them.
+    PadButton {
+        button: u32,
+        group: u32,
+        mode: u32,
+        state: ElementState,
+    },
+
+    /// A finger moved on a touch ring of a tablet pad.
+    ///
+    /// `angle` is in degrees clockwise from the top of the ring, in the range `[0, 360)`. It is
+    /// [`None`] once the finger left the ring, which lets kinetic scrolling be implemented.
+    ///
+    /// See [`DeviceEvent::PadButton`] for `group` and `mode`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    /// - **X11:** Pads have a single mode group with a single mode, and the finger leaving the
+    ///   ring isn't reported.
+    PadRing {
+        ring: u32,
+        group: u32,
+        mode: u32,
+        angle: Option<f64>,
+    },
+
+    /// A finger moved on a touch strip of a tablet pad.
+    ///
+    /// `position` is normalized to `[0, 1]`, where `0` is the top or left end of the strip. It is
+    /// [`None`] once the finger left the strip.
+    ///
+    /// See [`DeviceEvent::PadButton`] for `group` and `mode`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    /// - **X11:** Pads have a single mode group with a single mode, and the finger leaving the
+    ///   strip isn't reported.
+    PadStrip {
+        strip: u32,
+        group: u32,
+        mode: u32,
+        position: Option<f64>,
+    },
+
+    /// The mode of a mode group of a tablet pad changed.
+    ///
+    /// Applications usually assign different actions to the buttons, rings and strips of the group
+    /// in each mode, the pad often shows the current mode with LEDs.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows / X11:** Unsupported.
+    PadModeChanged {
+        group: u32,
+        mode: u32,
+    },
 }
 
 /// Describes a keyboard input as a raw device event.
@@ -870,6 +1258,10 @@ pub struct Modifiers {
     //
     // The field providing a metadata, it shouldn't be used as a source of truth.
     pub(crate) pressed_mods: ModifiersKeys,
+
+    pub(crate) locked_keys: LockKeys,
+
+    pub(crate) latched_keys: LockKeys,
 }
 
 impl Modifiers {
@@ -918,6 +1310,25 @@ impl Modifiers {
         self.mod_state(ModifiersKeys::RSUPER)
     }
 
+    /// The lock keys which are locked, such as caps lock after it was pressed once.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Windows:** Unsupported, always empty.
+    pub fn locked_keys(&self) -> LockKeys {
+        self.locked_keys
+    }
+
+    /// The lock keys which are latched, which are active until the next key press.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11 / Wayland:** Scroll lock is only an LED, so it's never latched.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported, always empty.
+    pub fn latched_keys(&self) -> LockKeys {
+        self.latched_keys
+    }
+
     fn mod_state(&self, modifier: ModifiersKeys) -> ModifiersKeyState {
         if self.pressed_mods.contains(modifier) {
             ModifiersKeyState::Pressed
@@ -929,7 +1340,7 @@ impl Modifiers {
 
 impl From<ModifiersState> for Modifiers {
     fn from(value: ModifiersState) -> Self {
-        Self { state: value, pressed_mods: Default::default() }
+        Self { state: value, ..Default::default() }
     }
 }
 
@@ -1125,6 +1536,22 @@ pub enum MouseScrollDelta {
     PixelDelta(PhysicalPosition<f64>),
 }
 
+/// The kind of motion producing a scroll.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ScrollSource {
+    /// A mouse wheel, scrolling in detents.
+    Wheel,
+    /// Fingers on a touchpad or a touchscreen.
+    ///
+    /// The scroll ends with [`TouchPhase::Ended`] when the fingers are lifted.
+    Finger,
+    /// A continuous motion, such as moving a mouse while holding a button to scroll.
+    Continuous,
+    /// Tilting a mouse wheel sideways.
+    WheelTilt,
+}
+
 /// Handle to synchronously change the size of the window from the [`WindowEvent`].
 #[derive(Debug, Clone)]
 pub struct SurfaceSizeWriter {
@@ -1193,12 +1620,32 @@ mod tests {
 
                 with_window_event(CloseRequested);
                 with_window_event(Destroyed);
+                with_window_event(PopupDismissed);
                 with_window_event(Focused(true));
                 with_window_event(Moved((0, 0).into()));
                 with_window_event(SurfaceResized((0, 0).into()));
                 with_window_event(DroppedFile("x.txt".into()));
                 with_window_event(HoveredFile("x.txt".into()));
                 with_window_event(HoveredFileCancelled);
+                with_window_event(DragEntered {
+                    mime_types: vec!["text/plain".into()],
+                    actions: crate::dnd::DragActions::COPY,
+                    position: (0.0, 0.0).into(),
+                });
+                with_window_event(DragMoved {
+                    actions: crate::dnd::DragActions::COPY,
+                    position: (0.0, 0.0).into(),
+                });
+                with_window_event(DragDropped { position: (0.0, 0.0).into() });
+                with_window_event(DragLeft);
+                with_window_event(ClipboardDone {
+                    serial: crate::event_loop::AsyncRequestSerial::get(),
+                    data: Ok(crate::clipboard::ClipboardData::text("x")),
+                });
+                with_window_event(DragSource {
+                    serial: crate::event_loop::AsyncRequestSerial::get(),
+                    event: crate::dnd::DragSourceEvent::Cancelled,
+                });
                 with_window_event(Ime(Enabled));
                 with_window_event(PointerMoved {
                     device_id: None,
@@ -1207,6 +1654,10 @@ mod tests {
                     source: PointerSource::Mouse,
                 });
                 with_window_event(ModifiersChanged(event::Modifiers::default()));
+                with_window_event(KeyboardLayoutChanged(crate::keyboard::KeyboardLayout {
+                    index: 1,
+                    name: Some("Russian".into()),
+                }));
                 with_window_event(PointerEntered {
                     device_id: None,
                     primary: true,
@@ -1223,6 +1674,8 @@ mod tests {
                     device_id: None,
                     delta: event::MouseScrollDelta::LineDelta(0.0, 0.0),
                     phase: event::TouchPhase::Started,
+                    source: Some(event::ScrollSource::Finger),
+                    value120: None,
                 });
                 with_window_event(PointerButton {
                     device_id: None,
@@ -1241,6 +1694,29 @@ mod tests {
                         force: Some(event::Force::Normalized(0.0)),
                     },
                 });
+                let data = event::PenData {
+                    force: Some(event::Force::Normalized(0.5)),
+                    tilt: Some(event::PenTilt { x: 10.0, y: -10.0 }),
+                    twist: Some(90.0),
+                    distance: None,
+                };
+                with_window_event(PointerMoved {
+                    device_id: None,
+                    primary: true,
+                    position: (0, 0).into(),
+                    source: PointerSource::Pen { tool: event::PenTool::Pen, data },
+                });
+                with_window_event(PointerButton {
+                    device_id: None,
+                    primary: true,
+                    state: event::ElementState::Pressed,
+                    position: (0, 0).into(),
+                    button: event::ButtonSource::Pen {
+                        tool: event::PenTool::Eraser,
+                        button: event::PenButton::Contact,
+                        data,
+                    },
+                });
                 with_window_event(PinchGesture {
                     device_id: None,
                     delta: 0.0,
@@ -1269,11 +1745,23 @@ mod tests {
                 let with_device_event =
                     |dev_ev| x(event::Event::DeviceEvent { device_id: None, event: dev_ev });
 
+                with_device_event(Added);
+                with_device_event(Removed);
                 with_device_event(PointerMotion { delta: (0.0, 0.0).into() });
                 with_device_event(MouseWheel {
                     delta: event::MouseScrollDelta::LineDelta(0.0, 0.0),
                 });
                 with_device_event(Button { button: 0, state: event::ElementState::Pressed });
+                with_device_event(PadButton {
+                    button: 0,
+                    group: 0,
+                    mode: 0,
+                    state: event::ElementState::Pressed,
+                });
+                with_device_event(PadRing { ring: 0, group: 0, mode: 0, angle: Some(90.0) });
+                with_device_event(PadStrip { strip: 0, group: 0, mode: 0, position: None });
+                with_device_event(PadModeChanged { group: 0, mode: 1 });
+                with_device_event(KeyRepeatInfoChanged(crate::keyboard::KeyRepeatInfo::Disabled));
             }
         }};
     }
@@ -23,6 +23,8 @@ use web_time::{Duration, Instant};
 
 use crate::application::ApplicationHandler;
 use crate::error::{EventLoopError, RequestError};
+use crate::event::{DeviceId, DeviceInfo};
+use crate::keyboard::{Key, KeyRepeatInfo, KeyboardLayout, LockKeys, ModifiersState, PhysicalKey};
 use crate::monitor::MonitorHandle;
 use crate::platform_impl;
 use crate::utils::AsAny;
@@ -364,6 +366,80 @@ pub trait ActiveEventLoop: AsAny {
     #[cfg_attr(not(any(web_platform, docsrs)), doc = "  detailed monitor permissions.")]
     fn primary_monitor(&self) -> Option<MonitorHandle>;
 
+    /// Returns the input devices connected to the system.
+    ///
+    /// Along with [`DeviceEvent::Added`] and [`DeviceEvent::Removed`], this lets applications keep
+    /// track of the input devices.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported, no devices are returned.
+    /// - **Wayland:** Keyboards, pointers and touchscreens are those of the seats, which may
+    ///   aggregate several physical devices.
+    ///
+    /// [`DeviceEvent::Added`]: crate::event::DeviceEvent::Added
+    /// [`DeviceEvent::Removed`]: crate::event::DeviceEvent::Removed
+    fn available_devices(&self) -> Box<dyn Iterator<Item = DeviceInfo>>;
+
+    /// Returns the information of an input device.
+    ///
+    /// Returns `None` if the device is not connected anymore.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported, always returns `None`.
+    fn device_info(&self, device_id: DeviceId) -> Option<DeviceInfo>;
+
+    /// Returns how held keys repeat, as configured by the user.
+    ///
+    /// Returns `None` if it cannot be determined, for example before a keyboard is available.
+    /// Changes are reported with [`DeviceEvent::KeyRepeatInfoChanged`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported, always returns `None`.
+    /// - **Wayland:** Each seat has its own keyboard, the information is the one of the keyboard
+    ///   that got it last.
+    ///
+    /// [`DeviceEvent::KeyRepeatInfoChanged`]: crate::event::DeviceEvent::KeyRepeatInfoChanged
+    fn key_repeat_info(&self) -> Option<KeyRepeatInfo>;
+
+    /// Returns the keyboard layouts configured by the user, in the order of their index.
+    ///
+    /// Switching between them is reported with [`WindowEvent::KeyboardLayoutChanged`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported, no layouts are returned.
+    /// - **Wayland:** Each seat has its own keyboard, the layouts are those of one of them.
+    ///
+    /// [`WindowEvent::KeyboardLayoutChanged`]: crate::event::WindowEvent::KeyboardLayoutChanged
+    fn keyboard_layouts(&self) -> Box<dyn Iterator<Item = KeyboardLayout>>;
+
+    /// Returns the key the physical key produces with the modifiers in the active layout, without
+    /// it being pressed.
+    ///
+    /// This is what [`KeyEvent::logical_key`] would be, which is useful to show the keys of
+    /// shortcuts to the user. Returns `None` if the physical key doesn't produce anything in the
+    /// layout, or if it cannot be determined.
+    ///
+    /// The `locked_keys` are the lock keys which are active, like caps lock, and `level3` selects
+    /// the third level of the key, as holding AltGr does. Scroll lock doesn't change the key.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported, always returns `None`.
+    /// - **Wayland:** Each seat has its own keyboard, the key is the one of one of them.
+    ///
+    /// [`KeyEvent::logical_key`]: crate::event::KeyEvent::logical_key
+    fn key_for_physical_key(
+        &self,
+        physical_key: PhysicalKey,
+        modifiers: ModifiersState,
+        locked_keys: LockKeys,
+        level3: bool,
+    ) -> Option<Key>;
+
     /// Change if or when [`DeviceEvent`]s are captured.
     ///
     /// Since the [`DeviceEvent`] capture can lead to high CPU usage for unfocused windows, winit
@@ -69,6 +69,9 @@
 //
 // --------- END OF W3C SHORT NOTICE ---------------------------------------------------------------
 
+use std::num::NonZeroU32;
+use std::time::Duration;
+
 use bitflags::bitflags;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -1735,6 +1738,65 @@ impl ModifiersState {
     }
 }
 
+bitflags! {
+    /// Represents the lock keys which are active, such as Caps Lock.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct LockKeys: u8 {
+        /// The "caps lock" key.
+        const CAPS_LOCK = 0b001;
+        /// The "num lock" key.
+        const NUM_LOCK = 0b010;
+        /// The "scroll lock" key.
+        const SCROLL_LOCK = 0b100;
+    }
+}
+
+impl LockKeys {
+    /// Returns `true` if caps lock is active.
+    pub fn caps_lock(&self) -> bool {
+        self.intersects(Self::CAPS_LOCK)
+    }
+
+    /// Returns `true` if num lock is active.
+    pub fn num_lock(&self) -> bool {
+        self.intersects(Self::NUM_LOCK)
+    }
+
+    /// Returns `true` if scroll lock is active.
+    pub fn scroll_lock(&self) -> bool {
+        self.intersects(Self::SCROLL_LOCK)
+    }
+}
+
+/// How held keys repeat, as configured by the user.
+///
+/// Applications doing their own repeat for custom actions can use this to behave like the rest of
+/// the system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum KeyRepeatInfo {
+    /// Held keys repeat.
+    Repeat {
+        /// The number of repeats per second.
+        rate: NonZeroU32,
+        /// The time a key is held before it starts repeating.
+        delay: Duration,
+    },
+    /// Held keys don't repeat.
+    Disabled,
+}
+
+/// A layout of the keyboard, such as "English (US)".
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct KeyboardLayout {
+    /// The index of the layout among those configured by the user.
+    pub index: u32,
+    /// The human-readable name of the layout, if it has one.
+    pub name: Option<String>,
+}
+
 /// The state of the particular modifiers key.
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
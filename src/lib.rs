@@ -287,6 +287,8 @@ pub use rwh_06 as raw_window_handle;
 pub mod application;
 #[cfg(any(doc, doctest, test))]
 pub mod changelog;
+pub mod clipboard;
+pub mod dnd;
 #[macro_use]
 pub mod error;
 mod cursor;
@@ -13,6 +13,10 @@
 //! * `wayland-csd-adwaita` (default).
 //! * `wayland-csd-adwaita-crossfont`.
 //! * `wayland-csd-adwaita-notitle`.
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::error::{NotSupportedError, RequestError};
 use crate::event_loop::{ActiveEventLoop, EventLoop, EventLoopBuilder};
 use crate::monitor::MonitorHandle;
 pub use crate::window::Theme;
@@ -72,10 +76,61 @@ impl EventLoopBuilderExtWayland for EventLoopBuilder {
 
 /// Additional methods on [`Window`] that are specific to Wayland.
 ///
+/// The methods about subsurfaces apply to child windows, created with
+/// [`WindowAttributes::with_parent_window`]. Like their position, the changes take effect with the
+/// next presentation of the parent.
+///
 /// [`Window`]: crate::window::Window
-pub trait WindowExtWayland {}
+pub trait WindowExtWayland {
+    /// Sets whether the child window is presented along with its parent.
+    ///
+    /// The new content of a synchronized child window is only shown once the parent presents,
+    /// which is the default. A desynchronized one presents on its own, like a video rendered
+    /// separately from the rest of the parent.
+    ///
+    /// Returns [`RequestError::NotSupported`] if the window isn't a child window.
+    fn set_subsurface_sync(&self, sync: bool) -> Result<(), RequestError>;
+
+    /// Places the child window just above `sibling`, which is either its parent or another child
+    /// of the parent.
+    ///
+    /// Returns [`RequestError::NotSupported`] if the window isn't a child window, or `sibling`
+    /// isn't its parent or another child of it.
+    fn place_subsurface_above(&self, sibling: &dyn CoreWindow) -> Result<(), RequestError>;
+
+    /// Places the child window just below `sibling`, which is either its parent or another child
+    /// of the parent.
+    ///
+    /// Returns [`RequestError::NotSupported`] if the window isn't a child window, or `sibling`
+    /// isn't its parent or another child of it.
+    fn place_subsurface_below(&self, sibling: &dyn CoreWindow) -> Result<(), RequestError>;
+}
+
+impl WindowExtWayland for dyn CoreWindow + '_ {
+    #[inline]
+    fn set_subsurface_sync(&self, sync: bool) -> Result<(), RequestError> {
+        wayland_window(self)?.set_subsurface_sync(sync)
+    }
+
+    #[inline]
+    fn place_subsurface_above(&self, sibling: &dyn CoreWindow) -> Result<(), RequestError> {
+        wayland_window(self)?.place_subsurface(wayland_window(sibling)?, true)
+    }
 
-impl WindowExtWayland for dyn CoreWindow + '_ {}
+    #[inline]
+    fn place_subsurface_below(&self, sibling: &dyn CoreWindow) -> Result<(), RequestError> {
+        wayland_window(self)?.place_subsurface(wayland_window(sibling)?, false)
+    }
+}
+
+fn wayland_window(
+    window: &dyn CoreWindow,
+) -> Result<&crate::platform_impl::wayland::Window, RequestError> {
+    window
+        .as_any()
+        .downcast_ref::<crate::platform_impl::wayland::Window>()
+        .ok_or_else(|| NotSupportedError::new("the window isn't a Wayland window").into())
+}
 
 /// Additional methods on [`WindowAttributes`] that are specific to Wayland.
 pub trait WindowAttributesExtWayland {
@@ -87,6 +142,22 @@ pub trait WindowAttributesExtWayland {
     /// For details about application ID conventions, see the
     /// [Desktop Entry Spec](https://specifications.freedesktop.org/desktop-entry-spec/desktop-entry-spec-latest.html#desktop-file-id)
     fn with_name(self, general: impl Into<String>, instance: impl Into<String>) -> Self;
+
+    /// Build the window as a layer surface of the desktop shell, such as a panel, a dock, an
+    /// overlay or a wallpaper.
+    ///
+    /// Layer surfaces have no decorations and aren't managed like regular windows: the compositor
+    /// places them on the output according to their [`Layer`] and anchors. The surface size is
+    /// used for the axes the surface isn't stretched along.
+    ///
+    /// The closing of the layer surface by the compositor, like when its output is removed, is
+    /// reported as [`WindowEvent::CloseRequested`].
+    ///
+    /// Creating the window fails with [`RequestError::NotSupported`] when the compositor doesn't
+    /// support `zwlr_layer_shell_v1`.
+    ///
+    /// [`WindowEvent::CloseRequested`]: crate::event::WindowEvent::CloseRequested
+    fn with_layer_shell(self, attributes: LayerShellAttributes) -> Self;
 }
 
 impl WindowAttributesExtWayland for WindowAttributes {
@@ -96,6 +167,12 @@ impl WindowAttributesExtWayland for WindowAttributes {
             Some(crate::platform_impl::ApplicationName::new(general.into(), instance.into()));
         self
     }
+
+    #[inline]
+    fn with_layer_shell(mut self, attributes: LayerShellAttributes) -> Self {
+        self.platform_specific.layer_shell = Some(attributes);
+        self
+    }
 }
 
 /// Additional methods on `MonitorHandle` that are specific to Wayland.
@@ -110,3 +187,150 @@ impl MonitorHandleExtWayland for MonitorHandle {
         self.inner.native_identifier()
     }
 }
+
+/// The attributes of a layer surface, used by desktop shell components.
+///
+/// Use this struct with [`WindowAttributesExtWayland::with_layer_shell`]. The margins and the
+/// exclusive zone are in logical pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerShellAttributes {
+    /// The layer the surface is shown in.
+    pub layer: Layer,
+
+    /// The edges of the output the surface is anchored to.
+    ///
+    /// The surface is centered along the axes it isn't anchored to, and stretched along the axes
+    /// it's anchored to both edges of.
+    pub anchor: LayerAnchor,
+
+    /// The space reserved along the anchored edge, which other surfaces like maximized windows
+    /// don't cover.
+    ///
+    /// With `0`, the surface is moved to not overlap the exclusive zones of other surfaces. With
+    /// `-1`, it's placed against the edge regardless of them.
+    pub exclusive_zone: i32,
+
+    /// The distance from the anchored edges, as top, right, bottom and left.
+    pub margin: (i32, i32, i32, i32),
+
+    /// How the surface gets the keyboard input.
+    pub keyboard_interactivity: KeyboardInteractivity,
+
+    /// The output the surface is shown on, picked by the compositor when `None`.
+    pub output: Option<MonitorHandle>,
+
+    /// The purpose of the surface, such as `"panel"` or `"wallpaper"`, which the compositor may
+    /// apply its own rules to.
+    pub namespace: String,
+}
+
+impl LayerShellAttributes {
+    /// Creates the attributes of a surface in the given layer, centered on the output.
+    pub fn new(layer: Layer) -> Self {
+        Self {
+            layer,
+            anchor: LayerAnchor::empty(),
+            exclusive_zone: 0,
+            margin: (0, 0, 0, 0),
+            keyboard_interactivity: KeyboardInteractivity::None,
+            output: None,
+            namespace: String::new(),
+        }
+    }
+
+    /// Sets the edges of the output the surface is anchored to.
+    #[inline]
+    pub fn with_anchor(mut self, anchor: LayerAnchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Sets the space reserved along the anchored edge.
+    #[inline]
+    pub fn with_exclusive_zone(mut self, exclusive_zone: i32) -> Self {
+        self.exclusive_zone = exclusive_zone;
+        self
+    }
+
+    /// Sets the distance from the anchored edges.
+    #[inline]
+    pub fn with_margin(mut self, top: i32, right: i32, bottom: i32, left: i32) -> Self {
+        self.margin = (top, right, bottom, left);
+        self
+    }
+
+    /// Sets how the surface gets the keyboard input.
+    #[inline]
+    pub fn with_keyboard_interactivity(
+        mut self,
+        keyboard_interactivity: KeyboardInteractivity,
+    ) -> Self {
+        self.keyboard_interactivity = keyboard_interactivity;
+        self
+    }
+
+    /// Sets the output the surface is shown on.
+    #[inline]
+    pub fn with_output(mut self, output: Option<MonitorHandle>) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// Sets the purpose of the surface.
+    #[inline]
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = namespace.into();
+        self
+    }
+}
+
+/// The layer of a layer surface, from the bottom to the top.
+///
+/// Regular windows are shown between [`Layer::Bottom`] and [`Layer::Top`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Layer {
+    /// Below everything, like a wallpaper.
+    Background,
+    /// Below the windows, like a desktop widget.
+    Bottom,
+    /// Above the windows, like a panel or a dock.
+    Top,
+    /// Above everything including fullscreen windows, like a lock screen or an on-screen display.
+    Overlay,
+}
+
+bitflags::bitflags! {
+    /// The edges of the output a layer surface is anchored to.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    pub struct LayerAnchor: u32 {
+        /// The top edge of the output.
+        const TOP = 1 << 0;
+        /// The bottom edge of the output.
+        const BOTTOM = 1 << 1;
+        /// The left edge of the output.
+        const LEFT = 1 << 2;
+        /// The right edge of the output.
+        const RIGHT = 1 << 3;
+    }
+}
+
+/// How a layer surface gets the keyboard input.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum KeyboardInteractivity {
+    /// The surface never gets the keyboard input.
+    #[default]
+    None,
+    /// The surface gets all the keyboard input while it's shown, in the [`Layer::Top`] and
+    /// [`Layer::Overlay`] layers.
+    Exclusive,
+    /// The surface gets the keyboard input when the user focuses it, like a regular window.
+    ///
+    /// Creating the window fails with [`RequestError::NotSupported`] when the compositor only
+    /// supports `zwlr_layer_shell_v1` before version 4.
+    ///
+    /// [`RequestError::NotSupported`]: crate::error::RequestError::NotSupported
+    OnDemand,
+}
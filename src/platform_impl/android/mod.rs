@@ -12,12 +12,14 @@ use android_activity::{
 use tracing::{debug, trace, warn};
 
 use crate::application::ApplicationHandler;
+use crate::clipboard::ClipboardData;
 use crate::cursor::Cursor;
+use crate::dnd::{DragActions, DragData};
 use crate::dpi::{PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size};
 use crate::error::{EventLoopError, NotSupportedError, RequestError};
 use crate::event::{self, DeviceId, FingerId, Force, StartCause, SurfaceSizeWriter};
 use crate::event_loop::{
-    ActiveEventLoop as RootActiveEventLoop, ControlFlow, DeviceEvents,
+    ActiveEventLoop as RootActiveEventLoop, AsyncRequestSerial, ControlFlow, DeviceEvents,
     EventLoopProxy as CoreEventLoopProxy, EventLoopProxyProvider,
     OwnedDisplayHandle as CoreOwnedDisplayHandle,
 };
@@ -696,6 +698,32 @@ impl RootActiveEventLoop for ActiveEventLoop {
         None
     }
 
+    fn available_devices(&self) -> Box<dyn Iterator<Item = crate::event::DeviceInfo>> {
+        Box::new(std::iter::empty())
+    }
+
+    fn device_info(&self, _device_id: crate::event::DeviceId) -> Option<crate::event::DeviceInfo> {
+        None
+    }
+
+    fn key_repeat_info(&self) -> Option<crate::keyboard::KeyRepeatInfo> {
+        None
+    }
+
+    fn keyboard_layouts(&self) -> Box<dyn Iterator<Item = crate::keyboard::KeyboardLayout>> {
+        Box::new(std::iter::empty())
+    }
+
+    fn key_for_physical_key(
+        &self,
+        _physical_key: crate::keyboard::PhysicalKey,
+        _modifiers: crate::keyboard::ModifiersState,
+        _locked_keys: crate::keyboard::LockKeys,
+        _level3: bool,
+    ) -> Option<crate::keyboard::Key> {
+        None
+    }
+
     fn system_theme(&self) -> Option<Theme> {
         None
     }
@@ -935,6 +963,14 @@ impl CoreWindow for Window {
         }
     }
 
+    fn set_transient_for(&self, _parent: Option<&dyn CoreWindow>) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_transient_for is not supported").into())
+    }
+
+    fn set_modal(&self, _modal: bool) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_modal is not supported").into())
+    }
+
     fn set_ime_purpose(&self, _purpose: ImePurpose) {}
 
     fn focus_window(&self) {}
@@ -947,10 +983,36 @@ impl CoreWindow for Window {
         Err(NotSupportedError::new("set_cursor_position is not supported").into())
     }
 
+    fn pointer_position(&self) -> Option<crate::dpi::PhysicalPosition<f64>> {
+        None
+    }
+
+    fn global_pointer_position(
+        &self,
+    ) -> Result<crate::dpi::PhysicalPosition<f64>, crate::error::RequestError> {
+        Err(crate::error::NotSupportedError::new("global_pointer_position is not supported").into())
+    }
+
     fn set_cursor_grab(&self, _: CursorGrabMode) -> Result<(), RequestError> {
         Err(NotSupportedError::new("set_cursor_grab is not supported").into())
     }
 
+    fn set_cursor_confine_region(
+        &self,
+        _region: Option<&[crate::window::Rect]>,
+    ) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_cursor_confine_region is not supported")
+            .into())
+    }
+
+    fn set_pointer_capture(
+        &self,
+        _device_id: Option<crate::event::DeviceId>,
+        _capture: bool,
+    ) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_pointer_capture is not supported").into())
+    }
+
     fn set_cursor_visible(&self, _: bool) {}
 
     fn drag_window(&self) -> Result<(), RequestError> {
@@ -968,6 +1030,20 @@ impl CoreWindow for Window {
         Err(NotSupportedError::new("set_cursor_hittest is not supported").into())
     }
 
+    fn set_hit_test_zones(
+        &self,
+        _zones: &[(crate::window::Rect, crate::window::HitTestZone)],
+    ) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_hit_test_zones is not supported").into())
+    }
+
+    fn set_input_region(
+        &self,
+        _region: Option<&[crate::window::Rect]>,
+    ) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_input_region is not supported").into())
+    }
+
     fn set_theme(&self, _theme: Option<Theme>) {}
 
     fn theme(&self) -> Option<Theme> {
@@ -980,10 +1056,57 @@ impl CoreWindow for Window {
         HAS_FOCUS.load(Ordering::Relaxed)
     }
 
+    fn pressed_keys(&self) -> Box<dyn Iterator<Item = crate::keyboard::PhysicalKey>> {
+        Box::new(std::iter::empty())
+    }
+
+    fn pressed_buttons(&self) -> Box<dyn Iterator<Item = crate::event::MouseButton>> {
+        Box::new(std::iter::empty())
+    }
+
     fn title(&self) -> String {
         String::new()
     }
 
+    fn set_clipboard(&self, _data: Vec<ClipboardData>) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("set_clipboard is not supported").into())
+    }
+
+    fn request_clipboard(&self, _mime_types: &[&str]) -> Result<AsyncRequestSerial, RequestError> {
+        Err(NotSupportedError::new("request_clipboard is not supported").into())
+    }
+
+    fn set_primary_selection(&self, _data: Vec<ClipboardData>) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("set_primary_selection is not supported").into())
+    }
+
+    fn request_primary_selection(
+        &self,
+        _mime_types: &[&str],
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        Err(NotSupportedError::new("request_primary_selection is not supported").into())
+    }
+
+    fn start_drag(
+        &self,
+        _data: DragData,
+        _icon: Option<CustomCursor>,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        Err(NotSupportedError::new("start_drag is not supported").into())
+    }
+
+    fn accept_drop(&self, _mime_type: &str, _action: DragActions) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("accept_drop is not supported").into())
+    }
+
+    fn reject_drop(&self) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("reject_drop is not supported").into())
+    }
+
+    fn request_drop_data(&self, _mime_types: &[&str]) -> Result<AsyncRequestSerial, RequestError> {
+        Err(NotSupportedError::new("request_drop_data is not supported").into())
+    }
+
     fn reset_dead_keys(&self) {}
 
     fn rwh_06_display_handle(&self) -> &dyn rwh_06::HasDisplayHandle {
@@ -124,6 +124,32 @@ impl RootActiveEventLoop for ActiveEventLoop {
         Some(RootMonitorHandle { inner: monitor })
     }
 
+    fn available_devices(&self) -> Box<dyn Iterator<Item = crate::event::DeviceInfo>> {
+        Box::new(std::iter::empty())
+    }
+
+    fn device_info(&self, _device_id: crate::event::DeviceId) -> Option<crate::event::DeviceInfo> {
+        None
+    }
+
+    fn key_repeat_info(&self) -> Option<crate::keyboard::KeyRepeatInfo> {
+        None
+    }
+
+    fn keyboard_layouts(&self) -> Box<dyn Iterator<Item = crate::keyboard::KeyboardLayout>> {
+        Box::new(std::iter::empty())
+    }
+
+    fn key_for_physical_key(
+        &self,
+        _physical_key: crate::keyboard::PhysicalKey,
+        _modifiers: crate::keyboard::ModifiersState,
+        _locked_keys: crate::keyboard::LockKeys,
+        _level3: bool,
+    ) -> Option<crate::keyboard::Key> {
+        None
+    }
+
     fn listen_device_events(&self, _allowed: DeviceEvents) {}
 
     fn system_theme(&self) -> Option<Theme> {
@@ -717,6 +717,8 @@ declare_class!(
                 device_id: None,
                 delta,
                 phase,
+                source: None,
+                value120: None,
             });
         }
 
@@ -17,13 +17,16 @@ use super::app_state::EventWrapper;
 use super::view::WinitView;
 use super::view_controller::WinitViewController;
 use super::{app_state, monitor, ActiveEventLoop, Fullscreen, MonitorHandle};
-use crate::cursor::Cursor;
+use crate::clipboard::ClipboardData;
+use crate::cursor::{Cursor, CustomCursor};
+use crate::dnd::{DragActions, DragData};
 use crate::dpi::{
     LogicalInsets, LogicalPosition, LogicalSize, PhysicalInsets, PhysicalPosition, PhysicalSize,
     Position, Size,
 };
 use crate::error::{NotSupportedError, RequestError};
 use crate::event::{Event, WindowEvent};
+use crate::event_loop::AsyncRequestSerial;
 use crate::icon::Icon;
 use crate::monitor::MonitorHandle as CoreMonitorHandle;
 use crate::platform::ios::{ScreenEdge, StatusBarStyle, ValidOrientations};
@@ -719,6 +722,14 @@ impl CoreWindow for Window {
         self.maybe_wait_on_main(|delegate| delegate.set_window_level(level));
     }
 
+    fn set_transient_for(&self, _parent: Option<&dyn CoreWindow>) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_transient_for is not supported").into())
+    }
+
+    fn set_modal(&self, _modal: bool) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_modal is not supported").into())
+    }
+
     fn set_window_icon(&self, window_icon: Option<Icon>) {
         self.maybe_wait_on_main(|delegate| delegate.set_window_icon(window_icon));
     }
@@ -743,6 +754,14 @@ impl CoreWindow for Window {
         self.maybe_wait_on_main(|delegate| delegate.has_focus())
     }
 
+    fn pressed_keys(&self) -> Box<dyn Iterator<Item = crate::keyboard::PhysicalKey>> {
+        Box::new(std::iter::empty())
+    }
+
+    fn pressed_buttons(&self) -> Box<dyn Iterator<Item = crate::event::MouseButton>> {
+        Box::new(std::iter::empty())
+    }
+
     fn request_user_attention(&self, request_type: Option<UserAttentionType>) {
         self.maybe_wait_on_main(|delegate| delegate.request_user_attention(request_type));
     }
@@ -763,6 +782,45 @@ impl CoreWindow for Window {
         self.maybe_wait_on_main(|delegate| delegate.title())
     }
 
+    fn set_clipboard(&self, _data: Vec<ClipboardData>) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("set_clipboard is not supported").into())
+    }
+
+    fn request_clipboard(&self, _mime_types: &[&str]) -> Result<AsyncRequestSerial, RequestError> {
+        Err(NotSupportedError::new("request_clipboard is not supported").into())
+    }
+
+    fn set_primary_selection(&self, _data: Vec<ClipboardData>) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("set_primary_selection is not supported").into())
+    }
+
+    fn request_primary_selection(
+        &self,
+        _mime_types: &[&str],
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        Err(NotSupportedError::new("request_primary_selection is not supported").into())
+    }
+
+    fn start_drag(
+        &self,
+        _data: DragData,
+        _icon: Option<CustomCursor>,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        Err(NotSupportedError::new("start_drag is not supported").into())
+    }
+
+    fn accept_drop(&self, _mime_type: &str, _action: DragActions) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("accept_drop is not supported").into())
+    }
+
+    fn reject_drop(&self) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("reject_drop is not supported").into())
+    }
+
+    fn request_drop_data(&self, _mime_types: &[&str]) -> Result<AsyncRequestSerial, RequestError> {
+        Err(NotSupportedError::new("request_drop_data is not supported").into())
+    }
+
     fn set_cursor(&self, cursor: Cursor) {
         self.maybe_wait_on_main(|delegate| delegate.set_cursor(cursor));
     }
@@ -771,10 +829,34 @@ impl CoreWindow for Window {
         Ok(self.maybe_wait_on_main(|delegate| delegate.set_cursor_position(position))?)
     }
 
+    fn pointer_position(&self) -> Option<crate::dpi::PhysicalPosition<f64>> {
+        None
+    }
+
+    fn global_pointer_position(&self) -> Result<crate::dpi::PhysicalPosition<f64>, RequestError> {
+        Err(crate::error::NotSupportedError::new("global_pointer_position is not supported").into())
+    }
+
     fn set_cursor_grab(&self, mode: crate::window::CursorGrabMode) -> Result<(), RequestError> {
         Ok(self.maybe_wait_on_main(|delegate| delegate.set_cursor_grab(mode))?)
     }
 
+    fn set_cursor_confine_region(
+        &self,
+        _region: Option<&[crate::window::Rect]>,
+    ) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_cursor_confine_region is not supported")
+            .into())
+    }
+
+    fn set_pointer_capture(
+        &self,
+        _device_id: Option<crate::event::DeviceId>,
+        _capture: bool,
+    ) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_pointer_capture is not supported").into())
+    }
+
     fn set_cursor_visible(&self, visible: bool) {
         self.maybe_wait_on_main(|delegate| delegate.set_cursor_visible(visible))
     }
@@ -794,10 +876,24 @@ impl CoreWindow for Window {
         self.maybe_wait_on_main(|delegate| delegate.show_window_menu(position))
     }
 
+    fn set_hit_test_zones(
+        &self,
+        _zones: &[(crate::window::Rect, crate::window::HitTestZone)],
+    ) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_hit_test_zones is not supported").into())
+    }
+
     fn set_cursor_hittest(&self, hittest: bool) -> Result<(), RequestError> {
         Ok(self.maybe_wait_on_main(|delegate| delegate.set_cursor_hittest(hittest))?)
     }
 
+    fn set_input_region(
+        &self,
+        _region: Option<&[crate::window::Rect]>,
+    ) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_input_region is not supported").into())
+    }
+
     fn current_monitor(&self) -> Option<CoreMonitorHandle> {
         self.maybe_wait_on_main(|delegate| {
             delegate.current_monitor().map(|inner| CoreMonitorHandle { inner })
@@ -0,0 +1,82 @@
+//! Parsing of the `text/uri-list` used to drop files, shared by X11 and Wayland.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::Utf8Error;
+
+use percent_encoding::percent_decode;
+
+#[derive(Debug)]
+pub enum DndDataParseError {
+    EmptyData,
+    InvalidUtf8(#[allow(dead_code)] Utf8Error),
+    HostnameSpecified(#[allow(dead_code)] String),
+    UnexpectedProtocol(#[allow(dead_code)] String),
+    UnresolvablePath(#[allow(dead_code)] io::Error),
+}
+
+impl From<Utf8Error> for DndDataParseError {
+    fn from(e: Utf8Error) -> Self {
+        DndDataParseError::InvalidUtf8(e)
+    }
+}
+
+impl From<io::Error> for DndDataParseError {
+    fn from(e: io::Error) -> Self {
+        DndDataParseError::UnresolvablePath(e)
+    }
+}
+
+/// Parse the local paths out of a `text/uri-list`.
+pub fn parse_uri_list(data: &[u8]) -> Result<Vec<PathBuf>, DndDataParseError> {
+    if !data.is_empty() {
+        let mut path_list = Vec::new();
+        let decoded = percent_decode(data).decode_utf8()?.into_owned();
+        for uri in decoded.split("\r\n").filter(|u| !u.is_empty()) {
+            // The format is specified as protocol://host/path
+            // However, it's typically simply protocol:///path
+            let path_str = if uri.starts_with("file://") {
+                let path_str = uri.replace("file://", "");
+                if !path_str.starts_with('/') {
+                    // A hostname is specified
+                    // Supporting this case is beyond the scope of my mental health
+                    return Err(DndDataParseError::HostnameSpecified(path_str));
+                }
+                path_str
+            } else {
+                // Only the file protocol is supported
+                return Err(DndDataParseError::UnexpectedProtocol(uri.to_owned()));
+            };
+
+            let path = Path::new(&path_str).canonicalize()?;
+            path_list.push(path);
+        }
+        Ok(path_list)
+    } else {
+        Err(DndDataParseError::EmptyData)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_paths() {
+        let paths = parse_uri_list(b"file:///\r\nfile://%2F\r\n").unwrap();
+        assert_eq!(paths, [Path::new("/"), Path::new("/")]);
+    }
+
+    #[test]
+    fn invalid_uris() {
+        assert!(matches!(parse_uri_list(b""), Err(DndDataParseError::EmptyData)));
+        assert!(matches!(
+            parse_uri_list(b"file://host/path\r\n"),
+            Err(DndDataParseError::HostnameSpecified(host)) if host == "host/path"
+        ));
+        assert!(matches!(
+            parse_uri_list(b"https://host/path\r\n"),
+            Err(DndDataParseError::UnexpectedProtocol(_))
+        ));
+    }
+}
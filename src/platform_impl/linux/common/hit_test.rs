@@ -0,0 +1,174 @@
+//! The hit-test zones of windows drawing their own decorations, shared by X11 and Wayland.
+
+use std::time::Duration;
+
+use crate::dpi::PhysicalPosition;
+use crate::event::{ElementState, MouseButton};
+use crate::window::{HitTestZone, Rect, ResizeDirection};
+
+/// The longest time between the clicks of a double-click on the caption.
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+
+/// The native action of a click in a zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HitTestAction {
+    Move,
+    Resize(ResizeDirection),
+    ToggleMaximized,
+    ShowMenu,
+}
+
+#[derive(Debug, Default)]
+pub struct HitTestZones {
+    zones: Vec<(Rect, HitTestZone)>,
+
+    /// The time of the latest press on the caption, to detect double-clicks.
+    caption_pressed: Option<Duration>,
+
+    /// Whether the left button was pressed on the maximize button, which acts on release.
+    maximize_pressed: bool,
+}
+
+impl HitTestZones {
+    pub fn set(&mut self, zones: &[(Rect, HitTestZone)]) {
+        self.zones = zones.to_vec();
+        self.caption_pressed = None;
+        self.maximize_pressed = false;
+    }
+
+    /// The zone at the surface position.
+    pub fn zone_at(&self, position: PhysicalPosition<f64>, scale_factor: f64) -> HitTestZone {
+        self.zones
+            .iter()
+            .find(|(rect, _)| {
+                let (origin, size) = rect.to_physical(scale_factor);
+                let (x, y) = (position.x - origin.x as f64, position.y - origin.y as f64);
+                x >= 0. && y >= 0. && x < size.width as f64 && y < size.height as f64
+            })
+            .map_or(HitTestZone::Client, |(_, zone)| *zone)
+    }
+
+    /// The action of a button of the pointer at the surface position.
+    ///
+    /// `time` is the timestamp of the event, from an arbitrary point.
+    pub fn button(
+        &mut self,
+        button: MouseButton,
+        state: ElementState,
+        position: PhysicalPosition<f64>,
+        scale_factor: f64,
+        time: Duration,
+    ) -> Option<HitTestAction> {
+        if self.zones.is_empty() {
+            return None;
+        }
+
+        let zone = self.zone_at(position, scale_factor);
+        let maximize_pressed = std::mem::take(&mut self.maximize_pressed);
+        if zone != HitTestZone::Caption || button != MouseButton::Left {
+            self.caption_pressed = None;
+        }
+
+        match (zone, button, state) {
+            (HitTestZone::Caption, MouseButton::Left, ElementState::Pressed) => {
+                let pressed = self.caption_pressed.replace(time);
+                if pressed.is_some_and(|pressed| time.saturating_sub(pressed) <= DOUBLE_CLICK_TIME)
+                {
+                    self.caption_pressed = None;
+                    Some(HitTestAction::ToggleMaximized)
+                } else {
+                    Some(HitTestAction::Move)
+                }
+            },
+            (HitTestZone::Caption, MouseButton::Right, ElementState::Pressed) => {
+                Some(HitTestAction::ShowMenu)
+            },
+            (HitTestZone::Resize(direction), MouseButton::Left, ElementState::Pressed) => {
+                Some(HitTestAction::Resize(direction))
+            },
+            (HitTestZone::MaximizeButton, MouseButton::Left, ElementState::Pressed) => {
+                self.maximize_pressed = true;
+                None
+            },
+            (HitTestZone::MaximizeButton, MouseButton::Left, ElementState::Released)
+                if maximize_pressed =>
+            {
+                Some(HitTestAction::ToggleMaximized)
+            },
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dpi::{LogicalPosition, LogicalSize};
+
+    fn zones() -> HitTestZones {
+        let mut zones = HitTestZones::default();
+        zones.set(&[
+            (
+                Rect::new(LogicalPosition::new(90., 0.), LogicalSize::new(10., 10.)),
+                HitTestZone::MaximizeButton,
+            ),
+            (
+                Rect::new(LogicalPosition::new(0., 0.), LogicalSize::new(100., 10.)),
+                HitTestZone::Caption,
+            ),
+        ]);
+        zones
+    }
+
+    fn click(
+        zones: &mut HitTestZones,
+        x: f64,
+        button: MouseButton,
+        state: ElementState,
+        time: u64,
+    ) -> Option<HitTestAction> {
+        let position = PhysicalPosition::new(x * 2., 10.);
+        zones.button(button, state, position, 2., Duration::from_millis(time))
+    }
+
+    #[test]
+    fn zone_at() {
+        let zones = zones();
+        assert_eq!(zones.zone_at(PhysicalPosition::new(190., 5.), 2.), HitTestZone::MaximizeButton);
+        assert_eq!(zones.zone_at(PhysicalPosition::new(10., 19.), 2.), HitTestZone::Caption);
+        assert_eq!(zones.zone_at(PhysicalPosition::new(10., 20.), 2.), HitTestZone::Client);
+    }
+
+    #[test]
+    fn caption_double_click() {
+        use ElementState::{Pressed, Released};
+        use MouseButton::{Left, Right};
+
+        let mut zones = zones();
+        assert_eq!(click(&mut zones, 10., Left, Pressed, 1000), Some(HitTestAction::Move));
+        assert_eq!(click(&mut zones, 10., Left, Released, 1100), None);
+        assert_eq!(
+            click(&mut zones, 10., Left, Pressed, 1200),
+            Some(HitTestAction::ToggleMaximized)
+        );
+        assert_eq!(click(&mut zones, 10., Left, Pressed, 1300), Some(HitTestAction::Move));
+        assert_eq!(click(&mut zones, 10., Left, Pressed, 2000), Some(HitTestAction::Move));
+        assert_eq!(click(&mut zones, 10., Right, Pressed, 2100), Some(HitTestAction::ShowMenu));
+        assert_eq!(click(&mut zones, 10., Left, Pressed, 2200), Some(HitTestAction::Move));
+    }
+
+    #[test]
+    fn maximize_button_acts_on_release() {
+        use ElementState::{Pressed, Released};
+        use MouseButton::Left;
+
+        let mut zones = zones();
+        assert_eq!(click(&mut zones, 95., Left, Pressed, 0), None);
+        assert_eq!(
+            click(&mut zones, 95., Left, Released, 100),
+            Some(HitTestAction::ToggleMaximized)
+        );
+        assert_eq!(click(&mut zones, 95., Left, Pressed, 200), None);
+        assert_eq!(click(&mut zones, 10., Left, Released, 300), None);
+    }
+}
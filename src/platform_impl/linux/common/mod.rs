@@ -1 +1,3 @@
+pub mod dnd;
+pub mod hit_test;
 pub mod xkb;
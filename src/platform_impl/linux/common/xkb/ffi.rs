@@ -0,0 +1,81 @@
+//! libxkbcommon functions which `xkbcommon-dl` doesn't load.
+//!
+//! They're loaded the same way as the ones of `xkbcommon-dl`, but separately, so a missing symbol
+//! only disables what depends on it.
+
+use std::os::raw::{c_char, c_int};
+
+use dlib::dlopen_external_library;
+use tracing::info;
+use xkbcommon_dl::{xkb_keymap, xkb_layout_index_t, xkb_state};
+
+dlopen_external_library!(XkbCommonExt,
+functions:
+    fn xkb_keymap_layout_get_name(*mut xkb_keymap, xkb_layout_index_t) -> *const c_char,
+    fn xkb_state_led_name_is_active(*mut xkb_state, *const c_char) -> c_int,
+);
+
+/// Load the functions, if libxkbcommon has them.
+pub fn xkbcommon_ext_option() -> Option<XkbCommonExt> {
+    ["libxkbcommon.so.0", "libxkbcommon.so"].into_iter().find_map(|name| {
+        unsafe { XkbCommonExt::open(name) }
+            .map_err(|err| info!("Failed loading extra functions from `{name}`: {err:?}"))
+            .ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+    use std::ptr;
+
+    use xkbcommon_dl::{
+        xkb_context_flags, xkb_keymap_compile_flags, xkb_keymap_format, xkbcommon_option,
+        XKB_LED_NAME_SCROLL,
+    };
+
+    use super::*;
+
+    const KEYMAP: &[u8] = b"xkb_keymap {
+        xkb_keycodes { minimum = 8; maximum = 255; };
+        xkb_types { };
+        xkb_compat { indicator \"Scroll Lock\" { modifiers = Mod3; }; };
+        xkb_symbols { name[group1] = \"Test\"; };
+    };\0";
+
+    #[test]
+    fn functions() {
+        // Nothing to test without libxkbcommon.
+        let (Some(xkbh), Some(ext)) = (xkbcommon_option(), xkbcommon_ext_option()) else {
+            return;
+        };
+
+        unsafe {
+            let context = (xkbh.xkb_context_new)(xkb_context_flags::XKB_CONTEXT_NO_FLAGS);
+            assert!(!context.is_null());
+            let keymap = (xkbh.xkb_keymap_new_from_string)(
+                context,
+                KEYMAP.as_ptr() as *const c_char,
+                xkb_keymap_format::XKB_KEYMAP_FORMAT_TEXT_V1,
+                xkb_keymap_compile_flags::XKB_KEYMAP_COMPILE_NO_FLAGS,
+            );
+            assert!(!keymap.is_null());
+
+            let name = (ext.xkb_keymap_layout_get_name)(keymap, 0);
+            assert_eq!(CStr::from_ptr(name).to_str(), Ok("Test"));
+            assert_eq!((ext.xkb_keymap_layout_get_name)(keymap, 1), ptr::null());
+
+            // Lock `Mod3`, the fifth real modifier.
+            let state = (xkbh.xkb_state_new)(keymap);
+            assert!(!state.is_null());
+            let scroll = XKB_LED_NAME_SCROLL.as_ptr() as *const c_char;
+            assert_eq!((ext.xkb_state_led_name_is_active)(state, scroll), 0);
+            (xkbh.xkb_state_update_mask)(state, 0, 0, 1 << 5, 0, 0, 0);
+            assert_eq!((ext.xkb_state_led_name_is_active)(state, scroll), 1);
+            (xkbh.xkb_state_unref)(state);
+
+            (xkbh.xkb_keymap_unref)(keymap);
+            (xkbh.xkb_context_unref)(context);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! XKB keymap.
 
-use std::ffi::c_char;
+use std::ffi::{c_char, CStr};
 use std::ops::Deref;
 use std::ptr::{self, NonNull};
 
@@ -9,15 +9,18 @@ use x11_dl::xlib_xcb::xcb_connection_t;
 use xkb::XKB_MOD_INVALID;
 use xkbcommon_dl::{
     self as xkb, xkb_keycode_t, xkb_keymap, xkb_keymap_compile_flags, xkb_keysym_t,
-    xkb_layout_index_t, xkb_mod_index_t,
+    xkb_layout_index_t, xkb_mod_index_t, xkb_mod_mask_t,
 };
 #[cfg(wayland_platform)]
 use {memmap2::MmapOptions, std::os::unix::io::OwnedFd};
 
-use crate::keyboard::{Key, KeyCode, KeyLocation, NamedKey, NativeKey, NativeKeyCode, PhysicalKey};
+use crate::keyboard::{
+    Key, KeyCode, KeyLocation, LockKeys, ModifiersState, NamedKey, NativeKey, NativeKeyCode,
+    PhysicalKey,
+};
 #[cfg(x11_platform)]
 use crate::platform_impl::common::xkb::XKBXH;
-use crate::platform_impl::common::xkb::{XkbContext, XKBH};
+use crate::platform_impl::common::xkb::{XkbContext, XKBEH, XKBH};
 
 /// Map the raw X11-style keycode to the `KeyCode` enum.
 ///
@@ -960,6 +963,9 @@ impl XkbKeymap {
             mod3: mod_index_for_name(keymap, b"Mod3\0"),
             logo: mod_index_for_name(keymap, xkb::XKB_MOD_NAME_LOGO),
             mod5: mod_index_for_name(keymap, b"Mod5\0"),
+            // The virtual modifier is usually mapped to `Mod5`.
+            level3: mod_index_for_name(keymap, b"LevelThree\0")
+                .or_else(|| mod_index_for_name(keymap, b"Mod5\0")),
         };
 
         Self { keymap, _mods_indices: mods_indices, _core_keyboard_id }
@@ -970,6 +976,26 @@ impl XkbKeymap {
         self._mods_indices
     }
 
+    /// The mask of the modifiers, those which aren't in the keymap are left out.
+    pub fn mod_mask(&self, modifiers: ModifiersState, level3: bool) -> xkb_mod_mask_t {
+        let indices = self._mods_indices;
+        index_mask([
+            (modifiers.shift_key(), indices.shift),
+            (modifiers.control_key(), indices.ctrl),
+            (modifiers.alt_key(), indices.alt),
+            (modifiers.super_key(), indices.logo),
+            (level3, indices.level3),
+        ])
+    }
+
+    /// The mask of the locked modifiers of the lock keys.
+    ///
+    /// Scroll lock isn't a modifier, so it's left out.
+    pub fn locked_mask(&self, locked_keys: LockKeys) -> xkb_mod_mask_t {
+        let indices = self._mods_indices;
+        index_mask([(locked_keys.caps_lock(), indices.caps), (locked_keys.num_lock(), indices.num)])
+    }
+
     pub fn first_keysym_by_level(
         &mut self,
         layout: xkb_layout_index_t,
@@ -994,6 +1020,22 @@ impl XkbKeymap {
         }
     }
 
+    /// The number of layouts of the keymap.
+    pub fn num_layouts(&self) -> xkb_layout_index_t {
+        unsafe { (XKBH.xkb_keymap_num_layouts)(self.keymap.as_ptr()) }
+    }
+
+    /// The name of the layout, such as "English (US)".
+    pub fn layout_name(&self, layout: xkb_layout_index_t) -> Option<String> {
+        let xkbeh = XKBEH.as_ref()?;
+        let name = unsafe { (xkbeh.xkb_keymap_layout_get_name)(self.keymap.as_ptr(), layout) };
+        if name.is_null() {
+            return None;
+        }
+
+        Some(unsafe { CStr::from_ptr(name) }.to_string_lossy().into_owned())
+    }
+
     /// Check whether the given key repeats.
     pub fn key_repeats(&mut self, keycode: xkb_keycode_t) -> bool {
         unsafe { (XKBH.xkb_keymap_key_repeats)(self.keymap.as_ptr(), keycode) == 1 }
@@ -1028,6 +1070,15 @@ pub struct ModsIndices {
     pub mod3: Option<xkb_mod_index_t>,
     pub logo: Option<xkb_mod_index_t>,
     pub mod5: Option<xkb_mod_index_t>,
+    /// The modifier selecting the third level, as done by AltGr.
+    pub level3: Option<xkb_mod_index_t>,
+}
+
+/// The mask of the modifiers which are active and in the keymap.
+fn index_mask<const N: usize>(mods: [(bool, Option<xkb_mod_index_t>); N]) -> xkb_mod_mask_t {
+    mods.into_iter()
+        .filter_map(|(active, index)| index.filter(|_| active))
+        .fold(0, |mask, index| mask | 1 << index)
 }
 
 fn mod_index_for_name(keymap: NonNull<xkb_keymap>, name: &[u8]) -> Option<xkb_mod_index_t> {
@@ -15,15 +15,17 @@ use xkbcommon_dl::{
 use {x11_dl::xlib_xcb::xcb_connection_t, xkbcommon_dl::x11::xkbcommon_x11_handle};
 
 use crate::event::{ElementState, KeyEvent};
-use crate::keyboard::{Key, KeyLocation};
+use crate::keyboard::{Key, KeyLocation, KeyboardLayout, LockKeys, ModifiersState, PhysicalKey};
 use crate::platform_impl::KeyEventExtra;
 use crate::utils::Lazy;
 
 mod compose;
+mod ffi;
 mod keymap;
 mod state;
 
 use compose::{ComposeStatus, XkbComposeState, XkbComposeTable};
+use ffi::{xkbcommon_ext_option, XkbCommonExt};
 #[cfg(x11_platform)]
 pub use keymap::raw_keycode_to_physicalkey;
 use keymap::XkbKeymap;
@@ -35,6 +37,7 @@ static RESET_DEAD_KEYS: AtomicBool = AtomicBool::new(false);
 
 static XKBH: Lazy<&'static XkbCommon> = Lazy::new(xkbcommon_handle);
 static XKBCH: Lazy<&'static XkbCommonCompose> = Lazy::new(xkbcommon_compose_handle);
+static XKBEH: Lazy<Option<XkbCommonExt>> = Lazy::new(xkbcommon_ext_option);
 #[cfg(feature = "x11")]
 static XKBXH: Lazy<&'static xkb::x11::XkbCommonX11> = Lazy::new(xkbcommon_x11_handle);
 
@@ -127,6 +130,24 @@ impl Context {
         self.keymap.as_mut()
     }
 
+    /// The layouts of the keymap, in the order of their index.
+    pub fn layouts(&self) -> Vec<KeyboardLayout> {
+        let Some(keymap) = self.keymap.as_ref() else {
+            return Vec::new();
+        };
+
+        (0..keymap.num_layouts())
+            .map(|index| KeyboardLayout { index, name: keymap.layout_name(index) })
+            .collect()
+    }
+
+    /// The active layout.
+    pub fn active_layout(&self) -> Option<KeyboardLayout> {
+        let index = self.state.as_ref()?.active_layout();
+        let name = self.keymap.as_ref()?.layout_name(index);
+        Some(KeyboardLayout { index, name })
+    }
+
     #[cfg(wayland_platform)]
     pub fn set_keymap_from_fd(&mut self, fd: OwnedFd, size: usize) {
         let keymap = XkbKeymap::from_fd(&self.context, fd, size);
@@ -149,6 +170,26 @@ impl Context {
         self.keymap = keymap;
     }
 
+    /// The key the physical key produces with the modifiers, without it being pressed.
+    ///
+    /// The layout is the active one unless one is given.
+    pub fn key_for_physical_key(
+        &mut self,
+        physical_key: PhysicalKey,
+        modifiers: ModifiersState,
+        locked_keys: LockKeys,
+        level3: bool,
+        layout: Option<u32>,
+    ) -> Option<Key> {
+        let keycode = physicalkey_to_scancode(physical_key)? + 8;
+        let layout = layout.or_else(|| Some(self.state.as_ref()?.active_layout()))?;
+        let keymap = self.keymap.as_ref()?;
+        let mut state = XkbState::new_detached(keymap)?;
+        let depressed = keymap.mod_mask(modifiers, level3);
+        state.update_modifiers(depressed, 0, keymap.locked_mask(locked_keys), 0, 0, layout);
+        self.key_context_with_state(&mut state)?.key_for_keycode(keycode)
+    }
+
     /// Key builder context with the user provided xkb state.
     pub fn key_context(&mut self) -> Option<KeyContext<'_>> {
         let state = self.state.as_mut()?;
@@ -162,7 +203,6 @@ impl Context {
     /// Key builder context with the user provided xkb state.
     ///
     /// Should be used when the original context must not be altered.
-    #[cfg(x11_platform)]
     pub fn key_context_with_state<'a>(
         &'a mut self,
         state: &'a mut XkbState,
@@ -203,6 +243,21 @@ impl<'a> KeyContext<'a> {
         KeyEvent { physical_key, logical_key, text, location, state, repeat, platform_specific }
     }
 
+    /// The key the keycode produces in the state, outside of a key event.
+    fn key_for_keycode(&mut self, keycode: u32) -> Option<Key> {
+        let keysym = self.state.get_one_sym_raw(keycode);
+        if keysym == 0 {
+            return None;
+        }
+
+        match keymap::keysym_to_key(keysym) {
+            Key::Unidentified(native) => Some(
+                self.keysym_to_utf8_raw(keysym).map_or(Key::Unidentified(native), Key::Character),
+            ),
+            key => Some(key),
+        }
+    }
+
     fn keysym_to_utf8_raw(&mut self, keysym: u32) -> Option<SmolStr> {
         self.scratch_buffer.clear();
         self.scratch_buffer.reserve(8);
@@ -10,15 +10,19 @@ use xkbcommon_dl::{
     self as xkb, xkb_keycode_t, xkb_keysym_t, xkb_layout_index_t, xkb_state, xkb_state_component,
 };
 
+use crate::event::Modifiers;
+use crate::keyboard::LockKeys;
 use crate::platform_impl::common::xkb::keymap::XkbKeymap;
 #[cfg(x11_platform)]
 use crate::platform_impl::common::xkb::XKBXH;
-use crate::platform_impl::common::xkb::{make_string_with, XKBH};
+use crate::platform_impl::common::xkb::{make_string_with, XKBEH, XKBH};
 
 #[derive(Debug)]
 pub struct XkbState {
     state: NonNull<xkb_state>,
     modifiers: ModifiersState,
+    /// The index of the active layout.
+    layout: xkb_layout_index_t,
 }
 
 impl XkbState {
@@ -37,10 +41,17 @@ impl XkbState {
         Some(Self::new_inner(state))
     }
 
+    /// A state which the keyboard doesn't drive, to look up keys with chosen modifiers.
+    pub fn new_detached(keymap: &XkbKeymap) -> Option<Self> {
+        let state = NonNull::new(unsafe { (XKBH.xkb_state_new)(keymap.as_ptr()) })?;
+        Some(Self::new_inner(state))
+    }
+
     fn new_inner(state: NonNull<xkb_state>) -> Self {
         let modifiers = ModifiersState::default();
-        let mut this = Self { state, modifiers };
+        let mut this = Self { state, modifiers, layout: 0 };
         this.reload_modifiers();
+        this.reload_layout();
         this
     }
 
@@ -96,6 +107,11 @@ impl XkbState {
         self.modifiers
     }
 
+    /// The index of the active layout.
+    pub fn active_layout(&self) -> xkb_layout_index_t {
+        self.layout
+    }
+
     pub fn update_modifiers(
         &mut self,
         mods_depressed: u32,
@@ -117,10 +133,31 @@ impl XkbState {
             )
         };
 
-        if mask.contains(xkb_state_component::XKB_STATE_MODS_EFFECTIVE) {
+        // The lock keys may change while the effective mods stay the same, like when caps lock
+        // is released after it was locked.
+        if mask.intersects(
+            xkb_state_component::XKB_STATE_MODS_EFFECTIVE
+                | xkb_state_component::XKB_STATE_MODS_LOCKED
+                | xkb_state_component::XKB_STATE_MODS_LATCHED
+                | xkb_state_component::XKB_STATE_LEDS,
+        ) {
             // Effective value of mods have changed, we need to update our state.
             self.reload_modifiers();
         }
+
+        if mask.contains(xkb_state_component::XKB_STATE_LAYOUT_EFFECTIVE) {
+            self.reload_layout();
+        }
+    }
+
+    /// Reload the active layout.
+    fn reload_layout(&mut self) {
+        self.layout = unsafe {
+            (XKBH.xkb_state_serialize_layout)(
+                self.state.as_ptr(),
+                xkb_state_component::XKB_STATE_LAYOUT_EFFECTIVE,
+            )
+        };
     }
 
     /// Reload the modifiers.
@@ -131,15 +168,63 @@ impl XkbState {
         self.modifiers.caps_lock = self.mod_name_is_active(xkb::XKB_MOD_NAME_CAPS);
         self.modifiers.logo = self.mod_name_is_active(xkb::XKB_MOD_NAME_LOGO);
         self.modifiers.num_lock = self.mod_name_is_active(xkb::XKB_MOD_NAME_NUM);
+        self.modifiers.locked_keys = self.locked_keys();
+        self.modifiers.latched_keys = self.latched_keys();
+    }
+
+    /// The lock keys whose LED is lit.
+    fn locked_keys(&mut self) -> LockKeys {
+        let mut locked_keys = LockKeys::empty();
+        for (lock_key, name) in [
+            (LockKeys::CAPS_LOCK, xkb::XKB_LED_NAME_CAPS),
+            (LockKeys::NUM_LOCK, xkb::XKB_LED_NAME_NUM),
+            (LockKeys::SCROLL_LOCK, xkb::XKB_LED_NAME_SCROLL),
+        ] {
+            locked_keys.set(lock_key, self.led_name_is_active(name));
+        }
+        locked_keys
+    }
+
+    /// The lock keys whose modifier is latched.
+    ///
+    /// Scroll lock is only an LED, so it's never latched.
+    fn latched_keys(&mut self) -> LockKeys {
+        let latched = xkb_state_component::XKB_STATE_MODS_LATCHED;
+        let mut latched_keys = LockKeys::empty();
+        for (lock_key, name) in [
+            (LockKeys::CAPS_LOCK, xkb::XKB_MOD_NAME_CAPS),
+            (LockKeys::NUM_LOCK, xkb::XKB_MOD_NAME_NUM),
+        ] {
+            latched_keys.set(lock_key, self.mod_name_is_active_in(name, &latched));
+        }
+        latched_keys
+    }
+
+    /// Check if the LED is lit within xkb.
+    fn led_name_is_active(&mut self, name: &[u8]) -> bool {
+        let Some(xkbeh) = XKBEH.as_ref() else {
+            return false;
+        };
+
+        unsafe {
+            (xkbeh.xkb_state_led_name_is_active)(
+                self.state.as_ptr(),
+                name.as_ptr() as *const c_char,
+            ) > 0
+        }
     }
 
     /// Check if the modifier is active within xkb.
     fn mod_name_is_active(&mut self, name: &[u8]) -> bool {
+        self.mod_name_is_active_in(name, &xkb_state_component::XKB_STATE_MODS_EFFECTIVE)
+    }
+
+    fn mod_name_is_active_in(&mut self, name: &[u8], component: &xkb_state_component) -> bool {
         unsafe {
             (XKBH.xkb_state_mod_name_is_active)(
                 self.state.as_ptr(),
                 name.as_ptr() as *const c_char,
-                xkb_state_component::XKB_STATE_MODS_EFFECTIVE,
+                xkb_state_component::from_bits_retain(component.bits()),
             ) > 0
         }
     }
@@ -175,6 +260,21 @@ pub struct ModifiersState {
     pub logo: bool,
     /// The "Num lock" key
     pub num_lock: bool,
+    /// The lock keys which are locked.
+    pub locked_keys: LockKeys,
+    /// The lock keys which are latched.
+    pub latched_keys: LockKeys,
+}
+
+impl From<ModifiersState> for Modifiers {
+    fn from(mods: ModifiersState) -> Modifiers {
+        Modifiers {
+            state: mods.into(),
+            locked_keys: mods.locked_keys,
+            latched_keys: mods.latched_keys,
+            ..Default::default()
+        }
+    }
 }
 
 impl From<ModifiersState> for crate::keyboard::ModifiersState {
@@ -67,6 +67,8 @@ impl ApplicationName {
 pub struct PlatformSpecificWindowAttributes {
     pub name: Option<ApplicationName>,
     pub activation_token: Option<ActivationToken>,
+    #[cfg(wayland_platform)]
+    pub layer_shell: Option<crate::platform::wayland::LayerShellAttributes>,
     #[cfg(x11_platform)]
     pub x11: X11WindowAttributes,
 }
@@ -90,6 +92,8 @@ impl Default for PlatformSpecificWindowAttributes {
         Self {
             name: None,
             activation_token: None,
+            #[cfg(wayland_platform)]
+            layer_shell: None,
             #[cfg(x11_platform)]
             x11: X11WindowAttributes {
                 visual_id: None,
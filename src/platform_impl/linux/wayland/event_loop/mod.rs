@@ -31,6 +31,7 @@ pub mod sink;
 use proxy::EventLoopProxy;
 use sink::EventSink;
 
+use super::seat::{ClipboardRequest, DragRequest};
 use super::state::{WindowCompositorUpdate, WinitState};
 use super::window::state::FrameCallbackState;
 use super::{logical_to_physical_rounded, WindowId};
@@ -120,11 +121,37 @@ impl EventLoop {
             })
             .map_err(|err| os_error!(err))?;
 
+        // A channel for the clipboard requests from winit's windows.
+        let (clipboard_sender, clipboard_channel) = calloop::channel::channel();
+        let clipboard_queue_handle = queue_handle.clone();
+        event_loop
+            .handle()
+            .insert_source(clipboard_channel, move |event, _, winit_state: &mut WinitState| {
+                if let calloop::channel::Event::Msg(request) = event {
+                    winit_state.handle_clipboard_request(request, &clipboard_queue_handle);
+                }
+            })
+            .map_err(|err| os_error!(err.error))?;
+
+        // A channel for the drags started from winit's windows.
+        let (drag_sender, drag_channel) = calloop::channel::channel();
+        let drag_queue_handle = queue_handle.clone();
+        event_loop
+            .handle()
+            .insert_source(drag_channel, move |event, _, winit_state: &mut WinitState| {
+                if let calloop::channel::Event::Msg(request) = event {
+                    winit_state.handle_drag_request(request, &drag_queue_handle);
+                }
+            })
+            .map_err(|err| os_error!(err.error))?;
+
         let handle = Arc::new(OwnedDisplayHandle::new(connection));
         let active_event_loop = ActiveEventLoop {
             handle: handle.clone(),
             wayland_dispatcher: wayland_dispatcher.clone(),
             event_loop_awakener,
+            clipboard_sender,
+            drag_sender,
             event_loop_proxy: EventLoopProxy::new(ping).into(),
             queue_handle,
             control_flow: Cell::new(ControlFlow::default()),
@@ -411,6 +438,17 @@ impl EventLoop {
             window_ids.extend(state.window_requests.get_mut().keys());
         });
 
+        // Apply the responses to the drag over our windows.
+        self.with_state(|state| {
+            for window_id in window_ids.iter() {
+                let requests = state.window_requests.get_mut()[window_id].take_drop_requests();
+                for request in requests {
+                    state.handle_drop_request(*window_id, request);
+                }
+            }
+            state.update_drop();
+        });
+
         for window_id in window_ids.iter() {
             let event = self.with_state(|state| {
                 let window_requests = state.window_requests.get_mut();
@@ -549,6 +587,12 @@ pub struct ActiveEventLoop {
     /// The event loop wakeup source.
     pub event_loop_awakener: calloop::ping::Ping,
 
+    /// The sender of clipboard requests to the event loop.
+    pub clipboard_sender: calloop::channel::Sender<ClipboardRequest>,
+
+    /// The sender of drag requests to the event loop.
+    pub drag_sender: calloop::channel::Sender<DragRequest>,
+
     /// The main queue used by the event loop.
     pub queue_handle: QueueHandle<WinitState>,
 
@@ -632,6 +676,32 @@ impl RootActiveEventLoop for ActiveEventLoop {
         None
     }
 
+    fn available_devices(&self) -> Box<dyn Iterator<Item = crate::event::DeviceInfo>> {
+        Box::new(self.state.borrow().devices.values().cloned().collect::<Vec<_>>().into_iter())
+    }
+
+    fn device_info(&self, device_id: crate::event::DeviceId) -> Option<crate::event::DeviceInfo> {
+        self.state.borrow().devices.get(&device_id).cloned()
+    }
+
+    fn key_repeat_info(&self) -> Option<crate::keyboard::KeyRepeatInfo> {
+        self.state.borrow().key_repeat_info
+    }
+
+    fn keyboard_layouts(&self) -> Box<dyn Iterator<Item = crate::keyboard::KeyboardLayout>> {
+        Box::new(self.state.borrow().keyboard_layouts().into_iter())
+    }
+
+    fn key_for_physical_key(
+        &self,
+        physical_key: crate::keyboard::PhysicalKey,
+        modifiers: crate::keyboard::ModifiersState,
+        locked_keys: crate::keyboard::LockKeys,
+        level3: bool,
+    ) -> Option<crate::keyboard::Key> {
+        self.state.borrow_mut().key_for_physical_key(physical_key, modifiers, locked_keys, level3)
+    }
+
     fn owned_display_handle(&self) -> CoreOwnedDisplayHandle {
         CoreOwnedDisplayHandle::new(self.handle.clone())
     }
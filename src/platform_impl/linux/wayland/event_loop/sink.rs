@@ -2,7 +2,7 @@
 
 use std::vec::Drain;
 
-use crate::event::{DeviceEvent, Event, WindowEvent};
+use crate::event::{DeviceEvent, DeviceId, Event, WindowEvent};
 use crate::window::WindowId;
 
 /// An event loop's sink to deliver events from the Wayland event callbacks
@@ -25,8 +25,8 @@ impl EventSink {
 
     /// Add new device event to a queue.
     #[inline]
-    pub fn push_device_event(&mut self, event: DeviceEvent) {
-        self.window_events.push(Event::DeviceEvent { event, device_id: None });
+    pub fn push_device_event(&mut self, event: DeviceEvent, device_id: Option<DeviceId>) {
+        self.window_events.push(Event::DeviceEvent { event, device_id });
     }
 
     /// Add new window event to a queue.
@@ -8,6 +8,7 @@ pub use window::Window;
 
 pub(super) use crate::cursor::OnlyCursorImage as CustomCursor;
 use crate::dpi::{LogicalSize, PhysicalSize};
+use crate::event::{DeviceId, ElementState};
 use crate::window::WindowId;
 
 mod event_loop;
@@ -23,9 +24,40 @@ fn make_wid(surface: &WlSurface) -> WindowId {
     WindowId::from_raw(surface.id().as_ptr() as usize)
 }
 
+/// Get the DeviceId out of the object representing an input device.
+#[inline]
+fn make_did(device: &impl Proxy) -> DeviceId {
+    DeviceId::from_raw(device.id().protocol_id() as i64)
+}
+
+/// Update the keys or buttons which are pressed, keeping them in the order they were pressed.
+fn update_pressed<T: PartialEq>(pressed: &mut Vec<T>, item: T, state: ElementState) {
+    pressed.retain(|pressed| *pressed != item);
+    if state == ElementState::Pressed {
+        pressed.push(item);
+    }
+}
+
 /// The default routine does floor, but we need round on Wayland.
 fn logical_to_physical_rounded(size: LogicalSize<u32>, scale_factor: f64) -> PhysicalSize<u32> {
     let width = size.width as f64 * scale_factor;
     let height = size.height as f64 * scale_factor;
     (width.round(), height.round()).into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pressed_order() {
+        let mut pressed = Vec::new();
+        update_pressed(&mut pressed, 1, ElementState::Pressed);
+        update_pressed(&mut pressed, 2, ElementState::Pressed);
+        update_pressed(&mut pressed, 1, ElementState::Pressed);
+        assert_eq!(pressed, [2, 1]);
+        update_pressed(&mut pressed, 2, ElementState::Released);
+        update_pressed(&mut pressed, 3, ElementState::Released);
+        assert_eq!(pressed, [1]);
+    }
+}
@@ -0,0 +1,464 @@
+//! Clipboard handling through the data device of the seats.
+//!
+//! The primary selection shares most of the logic, see the `primary_selection` module.
+
+use std::fmt;
+use std::io::{ErrorKind, Read, Write};
+use std::os::unix::io::AsFd;
+
+use calloop::PostAction;
+use sctk::data_device_manager::data_device::DataDeviceHandler;
+use sctk::data_device_manager::data_offer::{DataOfferHandler, DragOffer};
+use sctk::data_device_manager::data_source::DataSourceHandler;
+use sctk::data_device_manager::{ReadPipe, WritePipe};
+use sctk::reexports::client::protocol::wl_data_device::WlDataDevice;
+use sctk::reexports::client::protocol::wl_data_device_manager::DndAction;
+use sctk::reexports::client::protocol::wl_data_source::WlDataSource;
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::{Connection, Proxy, QueueHandle};
+use tracing::warn;
+
+use crate::clipboard::{self, ClipboardData, ClipboardError};
+use crate::event::WindowEvent;
+use crate::event_loop::AsyncRequestSerial;
+use crate::platform_impl::wayland::state::WinitState;
+use crate::window::WindowId;
+
+/// Target names offered in addition to the MIME types when the contents contain text, so X11
+/// clients running through Xwayland can paste it.
+const TEXT_ALIASES: [&str; 4] = ["text/plain", "UTF8_STRING", "STRING", "TEXT"];
+
+/// A selection which can be shared with other clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    Clipboard,
+    Primary,
+}
+
+/// A clipboard request from the `Window` to the event loop.
+#[derive(Debug)]
+pub enum ClipboardRequest {
+    Set {
+        selection: Selection,
+        contents: Vec<ClipboardData>,
+    },
+    Read {
+        selection: Selection,
+        window_id: WindowId,
+        serial: AsyncRequestSerial,
+        mime_types: Vec<String>,
+    },
+}
+
+/// Where the contents read from a pipe are delivered.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum ReadTarget {
+    /// Delivered as [`WindowEvent::ClipboardDone`].
+    Clipboard(WindowId, AsyncRequestSerial),
+    /// The data of a drop, delivered as [`WindowEvent::ClipboardDone`] as well.
+    Drop(WindowId, AsyncRequestSerial),
+    /// The `text/uri-list` of a drop, delivered as file events.
+    DropFiles,
+}
+
+/// A selection source we've offered to the compositor.
+#[derive(Debug)]
+pub struct SelectionSource<S> {
+    pub source: S,
+    pub contents: Vec<ClipboardData>,
+}
+
+impl WinitState {
+    pub fn handle_clipboard_request(
+        &mut self,
+        request: ClipboardRequest,
+        queue_handle: &QueueHandle<Self>,
+    ) {
+        match request {
+            ClipboardRequest::Set { selection: Selection::Clipboard, contents } => {
+                self.set_clipboard(contents, queue_handle)
+            },
+            ClipboardRequest::Set { selection: Selection::Primary, contents } => {
+                self.set_primary_selection(contents, queue_handle)
+            },
+            ClipboardRequest::Read { selection, window_id, serial, mime_types } => {
+                self.read_selection(selection, window_id, serial, mime_types)
+            },
+        }
+    }
+
+    fn set_clipboard(&mut self, contents: Vec<ClipboardData>, queue_handle: &QueueHandle<Self>) {
+        let Some(manager) = self.data_device_manager_state.as_ref() else {
+            warn!("wl_data_device_manager is not available");
+            return;
+        };
+
+        let Some((data_device, serial)) = self.latest_input_seat().and_then(|(seat, serial)| {
+            Some((self.seats.get(&seat.id())?.data_device.as_ref()?, serial))
+        }) else {
+            warn!("Ignoring clipboard update without any prior input");
+            return;
+        };
+
+        if contents.is_empty() {
+            data_device.unset_selection(serial);
+            self.clipboard_source = None;
+            return;
+        }
+
+        let source = manager.create_copy_paste_source(queue_handle, offered_mime_types(&contents));
+        source.set_selection(data_device, serial);
+        self.clipboard_source = Some(SelectionSource { source, contents });
+    }
+
+    fn set_primary_selection(
+        &mut self,
+        contents: Vec<ClipboardData>,
+        queue_handle: &QueueHandle<Self>,
+    ) {
+        let Some(manager) = self.primary_selection_manager_state.as_ref() else {
+            warn!("zwp_primary_selection_device_manager_v1 is not available");
+            return;
+        };
+
+        let Some((device, serial)) = self.latest_input_seat().and_then(|(seat, serial)| {
+            Some((self.seats.get(&seat.id())?.primary_selection_device.as_ref()?, serial))
+        }) else {
+            warn!("Ignoring primary selection update without any prior input");
+            return;
+        };
+
+        if contents.is_empty() {
+            device.unset_selection(serial);
+            self.primary_selection_source = None;
+            return;
+        }
+
+        let source = manager.create_selection_source(queue_handle, offered_mime_types(&contents));
+        source.set_selection(device, serial);
+        self.primary_selection_source = Some(SelectionSource { source, contents });
+    }
+
+    fn read_selection(
+        &mut self,
+        selection: Selection,
+        window_id: WindowId,
+        serial: AsyncRequestSerial,
+        mime_types: Vec<String>,
+    ) {
+        // Reading our own selection doesn't need a round trip through the compositor.
+        let own_contents = match selection {
+            Selection::Clipboard => self.clipboard_source.as_ref().map(|source| &source.contents),
+            Selection::Primary => {
+                self.primary_selection_source.as_ref().map(|source| &source.contents)
+            },
+        };
+        if let Some(contents) = own_contents {
+            let data = mime_types
+                .iter()
+                .find_map(|mime_type| {
+                    clipboard::find_data(contents, mime_type)
+                        .map(|entry| ClipboardData::new(mime_type.clone(), entry.data.clone()))
+                })
+                .ok_or(ClipboardError::NoMatchingMimeType);
+            self.push_clipboard_done(window_id, serial, data);
+            return;
+        }
+
+        // Prefer the seat with the most recent input, the selection is per seat.
+        let mut seats: Vec<_> = self.seats.values().collect();
+        seats.sort_by_key(|seat| std::cmp::Reverse(seat.latest_serial));
+
+        let pipe = match selection {
+            Selection::Clipboard => seats
+                .iter()
+                .find_map(|seat| seat.data_device.as_ref()?.data().selection_offer())
+                .map(|offer| {
+                    let offered = offer.with_mime_types(|offered| offered.to_vec());
+                    receive_offer(mime_types, &offered, |mime_type| offer.receive(mime_type))
+                }),
+            Selection::Primary => seats
+                .iter()
+                .find_map(|seat| seat.primary_selection_device.as_ref()?.data().selection_offer())
+                .map(|offer| {
+                    let offered = offer.with_mime_types(|offered| offered.to_vec());
+                    receive_offer(mime_types, &offered, |mime_type| offer.receive(mime_type))
+                }),
+        };
+
+        match pipe {
+            Some(Ok((mime_type, pipe))) => {
+                self.read_pipe(mime_type, pipe, ReadTarget::Clipboard(window_id, serial))
+            },
+            Some(Err(err)) => self.push_clipboard_done(window_id, serial, Err(err)),
+            None => self.push_clipboard_done(window_id, serial, Err(ClipboardError::Empty)),
+        }
+    }
+
+    /// Read the transferred contents from the pipe without blocking the event loop.
+    pub(super) fn read_pipe(&mut self, mime_type: String, pipe: ReadPipe, target: ReadTarget) {
+        if let Err(err) = rustix::io::ioctl_fionbio(pipe.as_fd(), true) {
+            warn!("Failed to make the clipboard pipe non-blocking: {err}");
+        }
+
+        let mut data = Vec::new();
+        let mut mime_type = Some(mime_type);
+        let result = self.loop_handle.insert_source(pipe, move |_, file, state| {
+            let mut buf = [0; 4096];
+            loop {
+                let result = match (&**file).read(&mut buf) {
+                    Ok(0) => {
+                        Ok(ClipboardData::new(mime_type.take().unwrap(), std::mem::take(&mut data)))
+                    },
+                    Ok(len) => {
+                        data.extend_from_slice(&buf[..len]);
+                        continue;
+                    },
+                    Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                    Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                        return PostAction::Continue;
+                    },
+                    Err(err) => {
+                        warn!("Failed to read the clipboard contents: {err}");
+                        Err(ClipboardError::TransferFailed)
+                    },
+                };
+
+                state.finish_read(target, result);
+                return PostAction::Remove;
+            }
+        });
+
+        if let Err(err) = result {
+            warn!("Failed to register the clipboard pipe: {}", err.error);
+            self.finish_read(target, Err(ClipboardError::TransferFailed));
+        }
+    }
+
+    fn finish_read(&mut self, target: ReadTarget, result: Result<ClipboardData, ClipboardError>) {
+        match target {
+            ReadTarget::Clipboard(window_id, serial) => {
+                self.push_clipboard_done(window_id, serial, result)
+            },
+            ReadTarget::Drop(window_id, serial) => self.drop_data_read(window_id, serial, result),
+            ReadTarget::DropFiles => self.drop_files_read(result),
+        }
+    }
+
+    pub(super) fn push_clipboard_done(
+        &mut self,
+        window_id: WindowId,
+        serial: AsyncRequestSerial,
+        data: Result<ClipboardData, ClipboardError>,
+    ) {
+        self.events_sink.push_window_event(WindowEvent::ClipboardDone { serial, data }, window_id);
+        self.dispatched_events = true;
+    }
+}
+
+/// The MIME types to offer for `contents`.
+pub(super) fn offered_mime_types(contents: &[ClipboardData]) -> Vec<String> {
+    let mut mime_types: Vec<String> = contents.iter().map(|d| d.mime_type.clone()).collect();
+    if mime_types.iter().any(|mime_type| clipboard::is_text_mime_type(mime_type)) {
+        for alias in TEXT_ALIASES {
+            if !mime_types.iter().any(|mime_type| mime_type == alias) {
+                mime_types.push(alias.to_owned());
+            }
+        }
+    }
+
+    mime_types
+}
+
+/// Pick the first of `mime_types` that is `offered` and start receiving it.
+///
+/// Returns the requested MIME type, which may differ from the received one for text.
+pub(super) fn receive_offer<E: fmt::Display>(
+    mime_types: Vec<String>,
+    offered: &[String],
+    receive: impl FnOnce(String) -> Result<ReadPipe, E>,
+) -> Result<(String, ReadPipe), ClipboardError> {
+    let selected = mime_types.into_iter().find_map(|mime_type| {
+        if offered.contains(&mime_type) {
+            return Some((mime_type.clone(), mime_type));
+        }
+
+        // Prefer the UTF-8 variants, `STRING` is Latin-1.
+        if clipboard::is_text_mime_type(&mime_type) {
+            let offered_text = [ClipboardData::TEXT_MIME_TYPE, "UTF8_STRING", "text/plain"]
+                .into_iter()
+                .find(|text| offered.iter().any(|offered| offered == text))?;
+            return Some((mime_type, offered_text.to_owned()));
+        }
+
+        None
+    });
+
+    let (mime_type, offered_mime_type) = selected.ok_or(ClipboardError::NoMatchingMimeType)?;
+    match receive(offered_mime_type) {
+        Ok(pipe) => Ok((mime_type, pipe)),
+        Err(err) => {
+            warn!("Failed to receive the clipboard contents: {err}");
+            Err(ClipboardError::TransferFailed)
+        },
+    }
+}
+
+/// Our contents converted to `mime_type`, if available.
+pub(super) fn convert_contents(contents: &[ClipboardData], mime_type: &str) -> Option<Vec<u8>> {
+    let data = clipboard::find_data(contents, mime_type)?;
+    if mime_type == "STRING" {
+        Some(clipboard::utf8_to_latin1(&data.data))
+    } else {
+        Some(data.data.clone())
+    }
+}
+
+/// Write `data` to the pipe without blocking the event loop.
+pub(super) fn send_data(state: &mut WinitState, pipe: WritePipe, data: Vec<u8>) {
+    if let Err(err) = rustix::io::ioctl_fionbio(pipe.as_fd(), true) {
+        warn!("Failed to make the clipboard pipe non-blocking: {err}");
+    }
+
+    let mut written = 0;
+    let result = state.loop_handle.insert_source(pipe, move |_, file, _| loop {
+        if written == data.len() {
+            return PostAction::Remove;
+        }
+
+        match (&**file).write(&data[written..]) {
+            Ok(len) => written += len,
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) if err.kind() == ErrorKind::WouldBlock => return PostAction::Continue,
+            Err(err) => {
+                warn!("Failed to write the clipboard contents: {err}");
+                return PostAction::Remove;
+            },
+        }
+    });
+
+    if let Err(err) = result {
+        warn!("Failed to register the clipboard pipe: {}", err.error);
+    }
+}
+
+impl DataDeviceHandler for WinitState {
+    fn enter(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        data_device: &WlDataDevice,
+        x: f64,
+        y: f64,
+        surface: &WlSurface,
+    ) {
+        self.drop_enter(data_device, x, y, surface);
+    }
+
+    fn leave(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataDevice) {
+        self.drop_leave();
+    }
+
+    fn motion(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataDevice, x: f64, y: f64) {
+        self.drop_motion(x, y);
+    }
+
+    fn selection(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataDevice) {
+        // The offer is queried from the data device when the clipboard is read.
+    }
+
+    fn drop_performed(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlDataDevice) {
+        self.drop_performed();
+    }
+}
+
+impl DataOfferHandler for WinitState {
+    fn source_actions(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &mut DragOffer,
+        actions: DndAction,
+    ) {
+        self.drop_source_actions(actions);
+    }
+
+    fn selected_action(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &mut DragOffer,
+        _: DndAction,
+    ) {
+    }
+}
+
+impl DataSourceHandler for WinitState {
+    fn accept_mime(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        source: &WlDataSource,
+        mime: Option<String>,
+    ) {
+        self.drag_accept_mime(source, mime);
+    }
+
+    fn send_request(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        source: &WlDataSource,
+        mime_type: String,
+        pipe: WritePipe,
+    ) {
+        let data = self
+            .drag_contents(source)
+            .or_else(|| {
+                self.clipboard_source
+                    .as_ref()
+                    .filter(|selection| selection.source.inner() == source)
+                    .map(|selection| selection.contents.as_slice())
+            })
+            .and_then(|contents| convert_contents(contents, &mime_type));
+
+        // Dropping the pipe without writing anything signals that there's no data.
+        if let Some(data) = data {
+            send_data(self, pipe, data);
+        }
+    }
+
+    fn cancelled(&mut self, _: &Connection, _: &QueueHandle<Self>, source: &WlDataSource) {
+        if self.drag_cancelled(source) {
+            return;
+        }
+
+        if self
+            .clipboard_source
+            .as_ref()
+            .is_some_and(|selection| selection.source.inner() == source)
+        {
+            self.clipboard_source = None;
+        }
+    }
+
+    fn dnd_dropped(&mut self, _: &Connection, _: &QueueHandle<Self>, source: &WlDataSource) {
+        self.drag_dropped(source);
+    }
+
+    fn dnd_finished(&mut self, _: &Connection, _: &QueueHandle<Self>, source: &WlDataSource) {
+        self.drag_finished(source);
+    }
+
+    fn action(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        source: &WlDataSource,
+        action: DndAction,
+    ) {
+        self.drag_action(source, action);
+    }
+}
+
+sctk::delegate_data_device!(WinitState);
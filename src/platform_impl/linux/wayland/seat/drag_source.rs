@@ -0,0 +1,233 @@
+//! Dragging data out of our windows through the data device.
+
+use std::sync::Arc;
+
+use sctk::data_device_manager::data_source::DragSource;
+use sctk::reexports::client::protocol::wl_data_device_manager::DndAction;
+use sctk::reexports::client::protocol::wl_data_source::WlDataSource;
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::{Proxy, QueueHandle};
+use tracing::warn;
+
+use super::data_device::offered_mime_types;
+use super::WinitPointerDataExt;
+use crate::clipboard::ClipboardData;
+use crate::cursor::CursorImage;
+use crate::dnd::{DragActions, DragData, DragSourceEvent};
+use crate::event::WindowEvent;
+use crate::event_loop::AsyncRequestSerial;
+use crate::platform_impl::wayland::state::WinitState;
+use crate::platform_impl::wayland::types::cursor::CustomCursor;
+use crate::window::WindowId;
+
+/// A request from a window to start a drag.
+#[derive(Debug)]
+pub struct DragRequest {
+    pub window_id: WindowId,
+    pub serial: AsyncRequestSerial,
+    pub surface: WlSurface,
+    pub data: DragData,
+    pub(crate) icon: Option<Arc<CursorImage>>,
+}
+
+/// The drag started from one of our windows.
+#[derive(Debug)]
+pub struct ActiveDrag {
+    source: DragSource,
+    window_id: WindowId,
+    serial: AsyncRequestSerial,
+    contents: Vec<ClipboardData>,
+    /// Whether the target accepts one of the offered MIME types.
+    accepted: bool,
+    /// The action selected by the compositor.
+    action: DragActions,
+    /// The action we've reported last.
+    reported_action: DragActions,
+    /// The surface showing the icon, along with the buffer it's using.
+    _icon: Option<(WlSurface, CustomCursor)>,
+}
+
+impl WinitState {
+    pub fn handle_drag_request(&mut self, request: DragRequest, queue_handle: &QueueHandle<Self>) {
+        if let Some(drag) = self.drag_source.take() {
+            self.push_drag_event(drag.window_id, drag.serial, DragSourceEvent::Cancelled);
+        }
+
+        let DragRequest { window_id, serial, surface, data, icon } = request;
+        let Some(manager) = self.data_device_manager_state.as_ref() else {
+            warn!("wl_data_device_manager is not available");
+            self.push_drag_event(window_id, serial, DragSourceEvent::Cancelled);
+            return;
+        };
+
+        // The drag must be started with the serial of the button press on the window, from the
+        // seat of the input starting it.
+        let seat = self.latest_input_seat().and_then(|(seat, _)| {
+            let seat = self.seats.get(&seat.id())?;
+            let pointer = seat.pointer.as_ref()?.pointer();
+            let pointer_data = pointer.winit_data();
+            (pointer_data.focused_window() == Some(window_id))
+                .then(|| Some((seat.data_device.as_ref()?, pointer_data.latest_button_serial())))
+                .flatten()
+        });
+        let Some((data_device, button_serial)) = seat else {
+            warn!("Ignoring drag without a pointer on the window");
+            self.push_drag_event(window_id, serial, DragSourceEvent::Cancelled);
+            return;
+        };
+
+        let source = manager.create_drag_and_drop_source(
+            queue_handle,
+            offered_mime_types(&data.contents),
+            to_dnd_action(data.actions),
+        );
+
+        let icon = icon.map(|image| {
+            let buffer = {
+                let mut pool = self.custom_cursor_pool.lock().unwrap();
+                CustomCursor::new(&mut pool, &image)
+            };
+            (self.compositor_state.create_surface(queue_handle), buffer)
+        });
+
+        source.start_drag(
+            data_device,
+            &surface,
+            icon.as_ref().map(|(surface, _)| surface),
+            button_serial,
+        );
+
+        // The icon is positioned relative to the pointer hotspot.
+        if let Some((surface, buffer)) = icon.as_ref() {
+            if surface.version() >= 5 {
+                surface.offset(-buffer.hotspot_x, -buffer.hotspot_y);
+                surface.attach(Some(buffer.buffer.wl_buffer()), 0, 0);
+            } else {
+                surface.attach(
+                    Some(buffer.buffer.wl_buffer()),
+                    -buffer.hotspot_x,
+                    -buffer.hotspot_y,
+                );
+            }
+            surface.damage_buffer(0, 0, buffer.w, buffer.h);
+            surface.commit();
+        }
+
+        self.drag_source = Some(ActiveDrag {
+            source,
+            window_id,
+            serial,
+            contents: data.contents,
+            accepted: false,
+            action: DragActions::empty(),
+            reported_action: DragActions::empty(),
+            _icon: icon,
+        });
+    }
+
+    /// The drag using `source`, if it's ours.
+    fn drag_for_source(&mut self, source: &WlDataSource) -> Option<&mut ActiveDrag> {
+        self.drag_source.as_mut().filter(|drag| drag.source.inner() == source)
+    }
+
+    /// Returns `false` if the source doesn't belong to the drag.
+    pub(super) fn drag_accept_mime(&mut self, source: &WlDataSource, mime: Option<String>) -> bool {
+        let Some(drag) = self.drag_for_source(source) else {
+            return false;
+        };
+
+        drag.accepted = mime.is_some();
+        self.report_drag_action();
+        true
+    }
+
+    /// Returns `false` if the source doesn't belong to the drag.
+    pub(super) fn drag_action(&mut self, source: &WlDataSource, action: DndAction) -> bool {
+        let Some(drag) = self.drag_for_source(source) else {
+            return false;
+        };
+
+        drag.action = from_dnd_action(action);
+        self.report_drag_action();
+        true
+    }
+
+    /// The contents of the drag, if `source` belongs to it.
+    pub(super) fn drag_contents(&self, source: &WlDataSource) -> Option<&[ClipboardData]> {
+        self.drag_source
+            .as_ref()
+            .filter(|drag| drag.source.inner() == source)
+            .map(|drag| drag.contents.as_slice())
+    }
+
+    pub(super) fn drag_dropped(&mut self, source: &WlDataSource) {
+        if let Some(drag) = self.drag_for_source(source) {
+            let (window_id, serial) = (drag.window_id, drag.serial);
+            self.push_drag_event(window_id, serial, DragSourceEvent::Dropped);
+        }
+    }
+
+    pub(super) fn drag_finished(&mut self, source: &WlDataSource) {
+        if self.drag_for_source(source).is_some() {
+            let drag = self.drag_source.take().unwrap();
+            let event = DragSourceEvent::Finished(drag.reported_action);
+            self.push_drag_event(drag.window_id, drag.serial, event);
+        }
+    }
+
+    /// Returns `false` if the source doesn't belong to the drag.
+    pub(super) fn drag_cancelled(&mut self, source: &WlDataSource) -> bool {
+        if self.drag_for_source(source).is_none() {
+            return false;
+        }
+
+        let drag = self.drag_source.take().unwrap();
+        self.push_drag_event(drag.window_id, drag.serial, DragSourceEvent::Cancelled);
+        true
+    }
+
+    fn report_drag_action(&mut self) {
+        let Some(drag) = self.drag_source.as_mut() else {
+            return;
+        };
+
+        let action = if drag.accepted { drag.action } else { DragActions::empty() };
+        if action != drag.reported_action {
+            drag.reported_action = action;
+            let (window_id, serial) = (drag.window_id, drag.serial);
+            self.push_drag_event(window_id, serial, DragSourceEvent::Action(action));
+        }
+    }
+
+    fn push_drag_event(
+        &mut self,
+        window_id: WindowId,
+        serial: AsyncRequestSerial,
+        event: DragSourceEvent,
+    ) {
+        self.events_sink.push_window_event(WindowEvent::DragSource { serial, event }, window_id);
+        self.dispatched_events = true;
+    }
+}
+
+/// `wl_data_device_manager` has no equivalent of [`DragActions::LINK`].
+pub(super) fn to_dnd_action(actions: DragActions) -> DndAction {
+    let mut action = DndAction::empty();
+    if actions.contains(DragActions::COPY) {
+        action |= DndAction::Copy;
+    }
+    if actions.contains(DragActions::MOVE) {
+        action |= DndAction::Move;
+    }
+    action
+}
+
+fn from_dnd_action(action: DndAction) -> DragActions {
+    if action.contains(DndAction::Copy) {
+        DragActions::COPY
+    } else if action.contains(DndAction::Move) {
+        DragActions::MOVE
+    } else {
+        DragActions::empty()
+    }
+}
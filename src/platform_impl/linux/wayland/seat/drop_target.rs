@@ -0,0 +1,325 @@
+//! Drops of data on our windows through the data device.
+
+use std::path::PathBuf;
+
+use sctk::data_device_manager::data_device::DataDeviceData;
+use sctk::data_device_manager::data_offer::DragOffer;
+use sctk::reexports::client::protocol::wl_data_device::WlDataDevice;
+use sctk::reexports::client::protocol::wl_data_device_manager::DndAction;
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::Proxy;
+use tracing::warn;
+
+use super::data_device::{receive_offer, ReadTarget};
+use super::drag_source::to_dnd_action;
+use crate::clipboard::{ClipboardData, ClipboardError};
+use crate::dnd::DragActions;
+use crate::dpi::{LogicalPosition, PhysicalPosition};
+use crate::event::WindowEvent;
+use crate::event_loop::AsyncRequestSerial;
+use crate::platform_impl::common::dnd;
+use crate::platform_impl::wayland::make_wid;
+use crate::platform_impl::wayland::state::WinitState;
+use crate::window::WindowId;
+
+const URI_LIST_MIME_TYPE: &str = "text/uri-list";
+
+/// A response of a window to the drag over it.
+#[derive(Debug)]
+pub enum DropRequest {
+    Accept { mime_type: String, action: DragActions },
+    Reject,
+    Read { serial: AsyncRequestSerial, mime_types: Vec<String> },
+}
+
+/// The drag over one of our windows.
+#[derive(Debug)]
+pub struct ActiveDrop {
+    /// The data device the drag is offered on.
+    data_device: WlDataDevice,
+    window_id: WindowId,
+    scale_factor: f64,
+    position: PhysicalPosition<f64>,
+    /// The MIME type and action the window accepts the drop with.
+    response: Option<(String, DragActions)>,
+    /// The files of the `text/uri-list`, once transferred.
+    files: Option<Vec<PathBuf>>,
+    dropped: bool,
+    /// The transfers of the offered data in progress.
+    pending_reads: usize,
+}
+
+impl ActiveDrop {
+    fn offer(&self) -> Option<DragOffer> {
+        self.data_device.data::<DataDeviceData>()?.drag_offer()
+    }
+
+    fn accepts_files(&self) -> bool {
+        self.response.as_ref().is_some_and(|(mime_type, _)| mime_type == URI_LIST_MIME_TYPE)
+    }
+}
+
+impl WinitState {
+    pub fn handle_drop_request(&mut self, window_id: WindowId, request: DropRequest) {
+        let drop = self.drop_target.as_mut().filter(|drop| drop.window_id == window_id);
+        match request {
+            DropRequest::Accept { mime_type, action } => {
+                if let Some(drop) = drop.filter(|drop| !drop.dropped) {
+                    drop.response = Some((mime_type, action));
+                    self.send_drop_response();
+                }
+            },
+            DropRequest::Reject => {
+                if let Some(drop) = drop.filter(|drop| !drop.dropped) {
+                    drop.response = None;
+                    self.send_drop_response();
+                }
+            },
+            DropRequest::Read { serial, mime_types } => {
+                let Some(offer) = drop.as_ref().and_then(|drop| drop.offer()) else {
+                    self.push_clipboard_done(window_id, serial, Err(ClipboardError::Empty));
+                    return;
+                };
+
+                let offered = offer.with_mime_types(|offered| offered.to_vec());
+                match receive_offer(mime_types, &offered, |mime_type| offer.receive(mime_type)) {
+                    Ok((mime_type, pipe)) => {
+                        drop.unwrap().pending_reads += 1;
+                        self.read_pipe(mime_type, pipe, ReadTarget::Drop(window_id, serial));
+                    },
+                    Err(err) => self.push_clipboard_done(window_id, serial, Err(err)),
+                }
+            },
+        }
+    }
+
+    /// Tell the source that the drop is finished once the requested data is transferred.
+    ///
+    /// Called after the application responded to the drag events.
+    pub fn update_drop(&mut self) {
+        let Some(drop) = self.drop_target.as_ref() else {
+            return;
+        };
+
+        if !drop.dropped || drop.pending_reads > 0 {
+            return;
+        }
+
+        if let Some(offer) = drop.offer() {
+            offer.finish();
+            offer.destroy();
+        }
+        self.drop_target = None;
+    }
+
+    pub(super) fn drop_enter(
+        &mut self,
+        data_device: &WlDataDevice,
+        x: f64,
+        y: f64,
+        surface: &WlSurface,
+    ) {
+        // A drag without data only moves the pointer.
+        let Some(offer) = data_device.data::<DataDeviceData>().and_then(|data| data.drag_offer())
+        else {
+            return;
+        };
+
+        let window_id = make_wid(surface);
+        let Some(scale_factor) = self
+            .windows
+            .get_mut()
+            .get(&window_id)
+            .map(|window| window.lock().unwrap().scale_factor())
+        else {
+            return;
+        };
+
+        let position = LogicalPosition::new(x, y).to_physical(scale_factor);
+        let mime_types = offer.with_mime_types(|offered| offered.to_vec());
+        let mut drop = ActiveDrop {
+            data_device: data_device.clone(),
+            window_id,
+            scale_factor,
+            position,
+            response: None,
+            files: None,
+            dropped: false,
+            pending_reads: 0,
+        };
+
+        // Files are accepted by default, which are read right away to deliver `HoveredFile`.
+        if mime_types.iter().any(|mime_type| mime_type == URI_LIST_MIME_TYPE) {
+            drop.response = Some((URI_LIST_MIME_TYPE.to_owned(), DragActions::COPY));
+            match offer.receive(URI_LIST_MIME_TYPE.to_owned()) {
+                Ok(pipe) => {
+                    drop.pending_reads += 1;
+                    self.read_pipe(URI_LIST_MIME_TYPE.to_owned(), pipe, ReadTarget::DropFiles);
+                },
+                Err(err) => warn!("Failed to receive the dropped files: {err}"),
+            }
+        }
+
+        self.drop_target = Some(drop);
+        self.send_drop_response();
+
+        let actions = from_dnd_actions(offer.source_actions);
+        self.push_drop_event(window_id, WindowEvent::DragEntered { mime_types, actions, position });
+    }
+
+    pub(super) fn drop_motion(&mut self, x: f64, y: f64) {
+        let Some(drop) = self.drop_target.as_mut().filter(|drop| !drop.dropped) else {
+            return;
+        };
+
+        drop.position = LogicalPosition::new(x, y).to_physical(drop.scale_factor);
+        let Some(offer) = drop.offer() else {
+            return;
+        };
+
+        let (window_id, position) = (drop.window_id, drop.position);
+        let actions = from_dnd_actions(offer.source_actions);
+        self.push_drop_event(window_id, WindowEvent::DragMoved { actions, position });
+    }
+
+    pub(super) fn drop_source_actions(&mut self, actions: DndAction) {
+        let Some(drop) = self.drop_target.as_ref().filter(|drop| !drop.dropped) else {
+            return;
+        };
+
+        let (window_id, position) = (drop.window_id, drop.position);
+        let actions = from_dnd_actions(actions);
+        self.push_drop_event(window_id, WindowEvent::DragMoved { actions, position });
+    }
+
+    pub(super) fn drop_leave(&mut self) {
+        // The drag also leaves after it was dropped, the offer stays valid in that case.
+        if !self.drop_target.as_ref().is_some_and(|drop| !drop.dropped) {
+            return;
+        }
+
+        let drop = self.drop_target.take().unwrap();
+        self.push_drop_event(drop.window_id, WindowEvent::HoveredFileCancelled);
+        self.push_drop_event(drop.window_id, WindowEvent::DragLeft);
+    }
+
+    pub(super) fn drop_performed(&mut self) {
+        let Some(drop) = self.drop_target.as_mut() else {
+            return;
+        };
+
+        let window_id = drop.window_id;
+        if drop.response.is_none() {
+            if let Some(offer) = drop.offer() {
+                offer.destroy();
+            }
+            self.drop_target = None;
+            self.push_drop_event(window_id, WindowEvent::DragLeft);
+            return;
+        }
+
+        drop.dropped = true;
+        let position = drop.position;
+        let files = if drop.accepts_files() { drop.files.clone() } else { None };
+        for path in files.into_iter().flatten() {
+            self.push_drop_event(window_id, WindowEvent::DroppedFile(path));
+        }
+
+        // `update_drop` finishes the drop after the application requested the data.
+        self.push_drop_event(window_id, WindowEvent::DragDropped { position });
+    }
+
+    pub(super) fn drop_data_read(
+        &mut self,
+        window_id: WindowId,
+        serial: AsyncRequestSerial,
+        result: Result<ClipboardData, ClipboardError>,
+    ) {
+        self.push_clipboard_done(window_id, serial, result);
+        // The drop is finished by `update_drop` after the dispatch, once the application has seen
+        // the events of this one.
+        if let Some(drop) = self.drop_target.as_mut().filter(|drop| drop.window_id == window_id) {
+            drop.pending_reads = drop.pending_reads.saturating_sub(1);
+        }
+    }
+
+    pub(super) fn drop_files_read(&mut self, result: Result<ClipboardData, ClipboardError>) {
+        let Some(drop) = self.drop_target.as_mut() else {
+            return;
+        };
+        drop.pending_reads = drop.pending_reads.saturating_sub(1);
+
+        let files = match result.map(|data| dnd::parse_uri_list(&data.data)) {
+            Ok(Ok(files)) => files,
+            Ok(Err(err)) => {
+                warn!("Failed to parse the dropped files: {err:?}");
+                Vec::new()
+            },
+            Err(_) => Vec::new(),
+        };
+
+        // The files of a drop that happened before the transfer finished are delivered now.
+        let window_id = drop.window_id;
+        let event: fn(PathBuf) -> WindowEvent =
+            if drop.dropped { WindowEvent::DroppedFile } else { WindowEvent::HoveredFile };
+        let deliver = !drop.dropped || drop.accepts_files();
+        drop.files = Some(files.clone());
+        for path in files.into_iter().filter(|_| deliver) {
+            self.push_drop_event(window_id, event(path));
+        }
+    }
+
+    /// Let the source know whether the data is accepted, and with which action.
+    fn send_drop_response(&self) {
+        let Some(drop) = self.drop_target.as_ref() else {
+            return;
+        };
+        let Some(offer) = drop.offer() else {
+            return;
+        };
+
+        match drop.response.as_ref() {
+            Some((mime_type, action)) => {
+                let action = to_dnd_action(*action);
+                offer.accept_mime_type(offer.serial, Some(mime_type.clone()));
+                offer.set_actions(action, action);
+            },
+            None => {
+                offer.accept_mime_type(offer.serial, None);
+                offer.set_actions(DndAction::empty(), DndAction::empty());
+            },
+        }
+    }
+
+    fn push_drop_event(&mut self, window_id: WindowId, event: WindowEvent) {
+        self.events_sink.push_window_event(event, window_id);
+        self.dispatched_events = true;
+    }
+}
+
+/// `wl_data_device_manager` has no equivalent of [`DragActions::LINK`].
+fn from_dnd_actions(actions: DndAction) -> DragActions {
+    let mut drag_actions = DragActions::empty();
+    if actions.contains(DndAction::Copy) {
+        drag_actions |= DragActions::COPY;
+    }
+    if actions.contains(DndAction::Move) {
+        drag_actions |= DragActions::MOVE;
+    }
+    drag_actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dnd_actions() {
+        assert_eq!(from_dnd_actions(DndAction::empty()), DragActions::empty());
+        assert_eq!(from_dnd_actions(DndAction::Copy | DndAction::Ask), DragActions::COPY);
+        let actions = DragActions::COPY | DragActions::MOVE;
+        assert_eq!(from_dnd_actions(to_dnd_action(actions)), actions);
+        // Links can't be expressed.
+        assert_eq!(from_dnd_actions(to_dnd_action(DragActions::LINK)), DragActions::empty());
+    }
+}
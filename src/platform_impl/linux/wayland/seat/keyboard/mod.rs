@@ -1,8 +1,10 @@
 //! The keyboard input handling.
 
-use std::sync::Mutex;
+use std::num::NonZeroU32;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+use ahash::AHashMap;
 use calloop::timer::{TimeoutAction, Timer};
 use calloop::{LoopHandle, RegistrationToken};
 use sctk::reexports::client::protocol::wl_keyboard::{
@@ -12,11 +14,12 @@ use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::{Connection, Dispatch, Proxy, QueueHandle, WEnum};
 use tracing::warn;
 
-use crate::event::{ElementState, WindowEvent};
-use crate::keyboard::ModifiersState;
-use crate::platform_impl::common::xkb::Context;
+use crate::event::{DeviceEvent, ElementState, WindowEvent};
+use crate::keyboard::{KeyRepeatInfo, ModifiersState};
+use crate::platform_impl::common::xkb::{self, Context};
 use crate::platform_impl::wayland::event_loop::sink::EventSink;
 use crate::platform_impl::wayland::state::WinitState;
+use crate::platform_impl::wayland::window::WindowState;
 use crate::platform_impl::wayland::{self, WindowId};
 
 impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
@@ -35,6 +38,13 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                 return;
             },
         };
+
+        // Keep the serial, so the selection could be set in response to the input.
+        if let WlKeyboardEvent::Enter { serial, .. } | WlKeyboardEvent::Key { serial, .. } = &event
+        {
+            seat_state.latest_serial = Some(*serial);
+        }
+
         let keyboard_state = match seat_state.keyboard_state.as_mut() {
             Some(keyboard_state) => keyboard_state,
             None => {
@@ -59,15 +69,20 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     warn!("unknown keymap format 0x{:x}", value)
                 },
             },
-            WlKeyboardEvent::Enter { surface, .. } => {
+            WlKeyboardEvent::Enter { surface, keys, .. } => {
                 let window_id = wayland::make_wid(&surface);
+                let pressed_keys = keys
+                    .chunks_exact(4)
+                    .map(|key| u32::from_ne_bytes(key.try_into().unwrap()))
+                    .map(xkb::scancode_to_physicalkey)
+                    .collect();
 
                 // Mark the window as focused.
                 let was_unfocused = match state.windows.get_mut().get(&window_id) {
                     Some(window) => {
                         let mut window = window.lock().unwrap();
                         let was_unfocused = !window.has_focus();
-                        window.add_seat_focus(data.seat.id());
+                        window.add_seat_focus(data.seat.id(), pressed_keys);
                         was_unfocused
                     },
                     None => return,
@@ -89,7 +104,7 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                 // HACK: this is just for GNOME not fixing their ordering issue of modifiers.
                 if std::mem::take(&mut seat_state.modifiers_pending) {
                     state.events_sink.push_window_event(
-                        WindowEvent::ModifiersChanged(seat_state.modifiers.into()),
+                        WindowEvent::ModifiersChanged(seat_state.modifiers),
                         window_id,
                     );
                 }
@@ -130,6 +145,7 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                 }
             },
             WlKeyboardEvent::Key { key, state: WEnum::Value(WlKeyState::Pressed), .. } => {
+                update_pressed_key(state.windows.get_mut(), data, key, ElementState::Pressed);
                 let key = key + 8;
 
                 key_input(
@@ -202,6 +218,7 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     .ok();
             },
             WlKeyboardEvent::Key { key, state: WEnum::Value(WlKeyState::Released), .. } => {
+                update_pressed_key(state.windows.get_mut(), data, key, ElementState::Released);
                 let key = key + 8;
 
                 key_input(
@@ -232,7 +249,9 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     None => return,
                 };
 
+                let layout = xkb_state.active_layout();
                 xkb_state.update_modifiers(mods_depressed, mods_latched, mods_locked, 0, 0, group);
+                let layout_changed = xkb_state.active_layout() != layout;
                 seat_state.modifiers = xkb_state.modifiers().into();
 
                 // HACK: part of the workaround from `WlKeyboardEvent::Enter`.
@@ -245,11 +264,23 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                 };
 
                 state.events_sink.push_window_event(
-                    WindowEvent::ModifiersChanged(seat_state.modifiers.into()),
+                    WindowEvent::ModifiersChanged(seat_state.modifiers),
                     window_id,
                 );
+
+                if let Some(layout) = xkb_context.active_layout().filter(|_| layout_changed) {
+                    state
+                        .events_sink
+                        .push_window_event(WindowEvent::KeyboardLayoutChanged(layout), window_id);
+                }
             },
             WlKeyboardEvent::RepeatInfo { rate, delay } => {
+                let delay = Duration::from_millis(delay as u64);
+                let key_repeat_info = match NonZeroU32::new(rate as u32) {
+                    Some(rate) => KeyRepeatInfo::Repeat { rate, delay },
+                    None => KeyRepeatInfo::Disabled,
+                };
+
                 keyboard_state.repeat_info = if rate == 0 {
                     // Stop the repeat once we get a disable event.
                     keyboard_state.current_repeat = None;
@@ -259,9 +290,17 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     RepeatInfo::Disable
                 } else {
                     let gap = Duration::from_micros(1_000_000 / rate as u64);
-                    let delay = Duration::from_millis(delay as u64);
                     RepeatInfo::Repeat { gap, delay }
                 };
+
+                // The first information of the keyboard isn't a change.
+                let previous = state.key_repeat_info.replace(key_repeat_info);
+                if previous.is_some_and(|previous| previous != key_repeat_info) {
+                    state.events_sink.push_device_event(
+                        DeviceEvent::KeyRepeatInfoChanged(key_repeat_info),
+                        Some(wayland::make_did(wl_keyboard)),
+                    );
+                }
             },
             _ => unreachable!(),
         }
@@ -371,7 +410,25 @@ fn key_input(
 
     if let Some(mut key_context) = keyboard_state.xkb_context.key_context() {
         let event = key_context.process_key_event(keycode, state, repeat);
-        let event = WindowEvent::KeyboardInput { device_id: None, event, is_synthetic: false };
+        let device_id = Some(wayland::make_did(&keyboard_state.keyboard));
+        let event = WindowEvent::KeyboardInput { device_id, event, is_synthetic: false };
         event_sink.push_window_event(event, window_id);
     }
 }
+
+/// Keep track of the keys pressed on the focused window.
+fn update_pressed_key(
+    windows: &mut AHashMap<WindowId, Arc<Mutex<WindowState>>>,
+    data: &KeyboardData,
+    scancode: u32,
+    state: ElementState,
+) {
+    let Some(window_id) = *data.window_id.lock().unwrap() else {
+        return;
+    };
+
+    if let Some(window) = windows.get(&window_id) {
+        let key = xkb::scancode_to_physicalkey(scancode);
+        window.lock().unwrap().update_pressed_key(&data.seat.id(), key, state);
+    }
+}
@@ -1,38 +1,180 @@
 //! Seat handling.
 
-use std::sync::Arc;
+use std::mem;
+use std::sync::{Arc, Mutex};
 
 use ahash::AHashMap;
+use sctk::data_device_manager::data_device::DataDevice;
+use sctk::primary_selection::device::PrimarySelectionDevice;
 use sctk::reexports::client::backend::ObjectId;
-use sctk::reexports::client::protocol::wl_seat::WlSeat;
+use sctk::reexports::client::globals::GlobalList;
+use sctk::reexports::client::protocol::wl_seat::{self, WlSeat};
 use sctk::reexports::client::protocol::wl_touch::WlTouch;
-use sctk::reexports::client::{Connection, Proxy, QueueHandle};
+use sctk::reexports::client::{delegate_dispatch, Connection, Dispatch, Proxy, QueueHandle, WEnum};
 use sctk::reexports::protocols::wp::relative_pointer::zv1::client::zwp_relative_pointer_v1::ZwpRelativePointerV1;
+use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_seat_v2::ZwpTabletSeatV2;
 use sctk::reexports::protocols::wp::text_input::zv3::client::zwp_text_input_v3::ZwpTextInputV3;
-use sctk::seat::pointer::{ThemeSpec, ThemedPointer};
-use sctk::seat::{Capability as SeatCapability, SeatHandler, SeatState};
+use sctk::registry::{RegistryHandler, RegistryState};
+use sctk::seat::pointer::cursor_shape::CursorShapeManager;
+use sctk::seat::touch::TouchData;
+use sctk::seat::Capability as SeatCapability;
 use tracing::warn;
 
-use crate::event::WindowEvent;
-use crate::keyboard::ModifiersState;
+use crate::event::{DeviceEvent, DeviceId, DeviceInfo, DeviceKind, Modifiers, WindowEvent};
+use crate::keyboard::{Key, KeyboardLayout, LockKeys, ModifiersState, PhysicalKey};
+use crate::platform_impl::wayland::make_did;
 use crate::platform_impl::wayland::state::WinitState;
 
+mod data_device;
+mod drag_source;
+mod drop_target;
 mod keyboard;
 mod pointer;
+mod primary_selection;
+mod tablet;
 mod text_input;
 mod touch;
 
+pub use data_device::{ClipboardRequest, Selection, SelectionSource};
+pub use drag_source::{ActiveDrag, DragRequest};
+pub use drop_target::{ActiveDrop, DropRequest};
 use keyboard::{KeyboardData, KeyboardState};
+use pointer::gestures::PointerGestures;
+pub use pointer::gestures::PointerGesturesState;
 pub use pointer::relative_pointer::RelativePointerState;
-pub use pointer::{PointerConstraintsState, WinitPointerData, WinitPointerDataExt};
+pub use pointer::{PointerConstraintsState, ThemedPointer, WinitPointerData, WinitPointerDataExt};
+pub use tablet::TabletManagerState;
 use text_input::TextInputData;
 pub use text_input::{TextInputState, ZwpTextInputV3Ext};
 use touch::TouchPoint;
 
+/// `wl_pointer` reports the scroll of high-resolution wheels since version 8.
+const MAX_SEAT_VERSION: u32 = 8;
+
+/// The seats of the compositor.
+///
+/// They're bound by winit rather than sctk, which doesn't go past version 7.
+#[derive(Debug)]
+pub struct SeatState {
+    seats: Vec<WlSeat>,
+
+    /// The manager setting the cursor icons drawn by the compositor.
+    cursor_shape_manager: Option<CursorShapeManager>,
+}
+
+impl SeatState {
+    pub fn new(
+        globals: &GlobalList,
+        registry_state: &RegistryState,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> Self {
+        let seats = registry_state
+            .bind_all(queue_handle, 1..=MAX_SEAT_VERSION, SeatData::new)
+            .expect("failed to bind global");
+        let cursor_shape_manager = CursorShapeManager::bind(globals, queue_handle).ok();
+        Self { seats, cursor_shape_manager }
+    }
+
+    pub fn seats(&self) -> impl Iterator<Item = &WlSeat> {
+        self.seats.iter()
+    }
+}
+
+impl RegistryHandler<WinitState> for SeatState {
+    fn new_global(
+        state: &mut WinitState,
+        _: &Connection,
+        queue_handle: &QueueHandle<WinitState>,
+        name: u32,
+        interface: &str,
+        _: u32,
+    ) {
+        if interface != WlSeat::interface().name {
+            return;
+        }
+
+        let seat: WlSeat = state
+            .registry_state
+            .bind_specific(queue_handle, name, 1..=MAX_SEAT_VERSION, SeatData::new(name))
+            .expect("failed to bind global");
+        state.seats.insert(seat.id(), WinitSeatState::new());
+        state.seat_state.seats.push(seat);
+    }
+
+    fn remove_global(
+        state: &mut WinitState,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+        name: u32,
+        interface: &str,
+    ) {
+        if interface != WlSeat::interface().name {
+            return;
+        }
+
+        let seats = &mut state.seat_state.seats;
+        if let Some(index) =
+            seats.iter().position(|seat| seat.data::<SeatData>().unwrap().name == name)
+        {
+            let seat = seats.remove(index);
+            state.remove_seat(&seat);
+        }
+    }
+}
+
+impl Dispatch<WlSeat, SeatData, WinitState> for SeatState {
+    fn event(
+        state: &mut WinitState,
+        seat: &WlSeat,
+        event: wl_seat::Event,
+        data: &SeatData,
+        _: &Connection,
+        queue_handle: &QueueHandle<WinitState>,
+    ) {
+        let wl_seat::Event::Capabilities { capabilities } = event else {
+            return;
+        };
+
+        let capabilities = match capabilities {
+            WEnum::Value(capabilities) => capabilities,
+            WEnum::Unknown(bits) => wl_seat::Capability::from_bits_truncate(bits),
+        };
+        let old_capabilities = mem::replace(&mut *data.capabilities.lock().unwrap(), capabilities);
+
+        for (flag, capability) in [
+            (wl_seat::Capability::Keyboard, SeatCapability::Keyboard),
+            (wl_seat::Capability::Pointer, SeatCapability::Pointer),
+            (wl_seat::Capability::Touch, SeatCapability::Touch),
+        ] {
+            match (old_capabilities.contains(flag), capabilities.contains(flag)) {
+                (false, true) => state.new_capability(queue_handle, seat, capability),
+                (true, false) => state.remove_capability(seat, capability),
+                _ => (),
+            }
+        }
+    }
+}
+
+/// The data of a seat.
+#[derive(Debug)]
+pub struct SeatData {
+    /// The name of the global the seat was bound from.
+    name: u32,
+
+    /// The capabilities the seat announced last.
+    capabilities: Mutex<wl_seat::Capability>,
+}
+
+impl SeatData {
+    fn new(name: u32) -> Self {
+        Self { name, capabilities: Mutex::new(wl_seat::Capability::empty()) }
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct WinitSeatState {
     /// The pointer bound on the seat.
-    pointer: Option<Arc<ThemedPointer<WinitPointerData>>>,
+    pointer: Option<Arc<ThemedPointer>>,
 
     /// The touch bound on the seat.
     touch: Option<WlTouch>,
@@ -49,14 +191,29 @@ pub struct WinitSeatState {
     /// The relative pointer bound on the seat.
     relative_pointer: Option<ZwpRelativePointerV1>,
 
+    /// The gestures of the pointer bound on the seat.
+    pointer_gestures: Option<PointerGestures>,
+
     /// The keyboard bound on the seat.
     keyboard_state: Option<KeyboardState>,
 
     /// The current modifiers state on the seat.
-    modifiers: ModifiersState,
+    modifiers: Modifiers,
 
     /// Whether we have pending modifiers.
     modifiers_pending: bool,
+
+    /// The data device bound on the seat.
+    data_device: Option<DataDevice>,
+
+    /// The primary selection device bound on the seat.
+    primary_selection_device: Option<PrimarySelectionDevice>,
+
+    /// The tablets, tools and pads of the seat.
+    tablet_seat: Option<ZwpTabletSeatV2>,
+
+    /// The serial of the latest keyboard or pointer button event, required to set the selection.
+    latest_serial: Option<u32>,
 }
 
 impl WinitSeatState {
@@ -65,16 +222,11 @@ impl WinitSeatState {
     }
 }
 
-impl SeatHandler for WinitState {
-    fn seat_state(&mut self) -> &mut SeatState {
-        &mut self.seat_state
-    }
-
+impl WinitState {
     fn new_capability(
         &mut self,
-        _: &Connection,
         queue_handle: &QueueHandle<Self>,
-        seat: WlSeat,
+        seat: &WlSeat,
         capability: SeatCapability,
     ) {
         let seat_state = match self.seats.get_mut(&seat.id()) {
@@ -85,30 +237,30 @@ impl SeatHandler for WinitState {
             },
         };
 
+        let mut added = None;
         match capability {
             SeatCapability::Touch if seat_state.touch.is_none() => {
-                seat_state.touch = self.seat_state.get_touch(queue_handle, &seat).ok();
+                let touch = seat.get_touch(queue_handle, TouchData::new(seat.clone()));
+                added = Some((make_did(&touch), DeviceKind::Touchscreen));
+                seat_state.touch = Some(touch);
             },
             SeatCapability::Keyboard if seat_state.keyboard_state.is_none() => {
                 let keyboard = seat.get_keyboard(queue_handle, KeyboardData::new(seat.clone()));
+                added = Some((make_did(&keyboard), DeviceKind::Keyboard));
                 seat_state.keyboard_state =
                     Some(KeyboardState::new(keyboard, self.loop_handle.clone()));
             },
             SeatCapability::Pointer if seat_state.pointer.is_none() => {
                 let surface = self.compositor_state.create_surface(queue_handle);
                 let surface_id = surface.id();
-                let pointer_data = WinitPointerData::new(seat.clone());
-                let themed_pointer = self
-                    .seat_state
-                    .get_pointer_with_theme_and_data(
-                        queue_handle,
-                        &seat,
-                        self.shm.wl_shm(),
-                        surface,
-                        ThemeSpec::System,
-                        pointer_data,
-                    )
-                    .expect("failed to create pointer with present capability.");
+                let pointer = seat.get_pointer(queue_handle, WinitPointerData::new(seat.clone()));
+                let themed_pointer = ThemedPointer::new(
+                    pointer,
+                    surface,
+                    self.shm.wl_shm().clone(),
+                    self.seat_state.cursor_shape_manager.as_ref(),
+                    queue_handle,
+                );
 
                 seat_state.relative_pointer = self.relative_pointer.as_ref().map(|manager| {
                     manager.get_relative_pointer(
@@ -118,34 +270,64 @@ impl SeatHandler for WinitState {
                     )
                 });
 
+                seat_state.pointer_gestures = self.pointer_gestures.as_ref().map(|manager| {
+                    PointerGestures::new(manager, themed_pointer.pointer(), queue_handle)
+                });
+
                 let themed_pointer = Arc::new(themed_pointer);
 
                 // Register cursor surface.
                 self.pointer_surfaces.insert(surface_id, themed_pointer.clone());
 
+                added = Some((make_did(themed_pointer.pointer()), DeviceKind::Pointer));
                 seat_state.pointer = Some(themed_pointer);
             },
             _ => (),
         }
 
+        if let Some((device_id, kind)) = added {
+            self.add_device(device_id, kind, None, None);
+        }
+        let seat_state = self.seats.get_mut(&seat.id()).unwrap();
+
+        if let Some(manager) = seat_state
+            .data_device
+            .is_none()
+            .then_some(self.data_device_manager_state.as_ref())
+            .flatten()
+        {
+            seat_state.data_device = Some(manager.get_data_device(queue_handle, seat));
+        }
+
+        if let Some(manager) = seat_state
+            .primary_selection_device
+            .is_none()
+            .then_some(self.primary_selection_manager_state.as_ref())
+            .flatten()
+        {
+            seat_state.primary_selection_device =
+                Some(manager.get_selection_device(queue_handle, seat));
+        }
+
+        if let Some(manager) =
+            seat_state.tablet_seat.is_none().then_some(self.tablet_manager.as_ref()).flatten()
+        {
+            seat_state.tablet_seat =
+                Some(manager.get_tablet_seat(seat, queue_handle, sctk::globals::GlobalData));
+        }
+
         if let Some(text_input_state) =
             seat_state.text_input.is_none().then_some(self.text_input_state.as_ref()).flatten()
         {
             seat_state.text_input = Some(Arc::new(text_input_state.get_text_input(
-                &seat,
+                seat,
                 queue_handle,
                 TextInputData::default(),
             )));
         }
     }
 
-    fn remove_capability(
-        &mut self,
-        _: &Connection,
-        _queue_handle: &QueueHandle<Self>,
-        seat: WlSeat,
-        capability: SeatCapability,
-    ) {
+    fn remove_capability(&mut self, seat: &WlSeat, capability: SeatCapability) {
         let seat_state = match self.seats.get_mut(&seat.id()) {
             Some(seat_state) => seat_state,
             None => {
@@ -161,6 +343,7 @@ impl SeatHandler for WinitState {
         match capability {
             SeatCapability::Touch => {
                 if let Some(touch) = seat_state.touch.take() {
+                    self.remove_device(make_did(&touch));
                     if touch.version() >= 3 {
                         touch.release();
                     }
@@ -171,6 +354,10 @@ impl SeatHandler for WinitState {
                     relative_pointer.destroy();
                 }
 
+                if let Some(pointer_gestures) = seat_state.pointer_gestures.take() {
+                    pointer_gestures.destroy();
+                }
+
                 if let Some(pointer) = seat_state.pointer.take() {
                     let pointer_data = pointer.pointer().winit_data();
 
@@ -182,40 +369,102 @@ impl SeatHandler for WinitState {
                     pointer_data.unlock_pointer();
                     pointer_data.unconfine_pointer();
 
-                    if pointer.pointer().version() >= 3 {
-                        pointer.pointer().release();
-                    }
+                    self.remove_device(make_did(pointer.pointer()));
                 }
             },
             SeatCapability::Keyboard => {
-                seat_state.keyboard_state = None;
+                if let Some(keyboard_state) = seat_state.keyboard_state.take() {
+                    self.remove_device(make_did(&keyboard_state.keyboard));
+                }
                 self.on_keyboard_destroy(&seat.id());
             },
             _ => (),
         }
     }
 
-    fn new_seat(
+    fn remove_seat(&mut self, seat: &WlSeat) {
+        if let Some(seat_state) = self.seats.remove(&seat.id()) {
+            let keyboard = seat_state.keyboard_state.as_ref().map(|state| &state.keyboard);
+            let pointer = seat_state.pointer.as_ref().map(|pointer| pointer.pointer());
+            let device_ids = keyboard
+                .map(make_did)
+                .into_iter()
+                .chain(pointer.map(make_did))
+                .chain(seat_state.touch.as_ref().map(make_did));
+            for device_id in device_ids {
+                self.remove_device(device_id);
+            }
+
+            if let Some(tablet_seat) = seat_state.tablet_seat {
+                tablet_seat.destroy();
+            }
+        }
+        self.on_keyboard_destroy(&seat.id());
+    }
+}
+
+impl WinitState {
+    /// Register a connected input device, and let the application know about it.
+    pub(crate) fn add_device(
         &mut self,
-        _connection: &Connection,
-        _queue_handle: &QueueHandle<Self>,
-        seat: WlSeat,
+        id: DeviceId,
+        kind: DeviceKind,
+        name: Option<String>,
+        vendor_product_id: Option<(u16, u16)>,
     ) {
-        self.seats.insert(seat.id(), WinitSeatState::new());
+        self.devices.insert(id, DeviceInfo { id, kind, name, vendor_product_id });
+        self.events_sink.push_device_event(DeviceEvent::Added, Some(id));
     }
 
-    fn remove_seat(
+    pub(crate) fn remove_device(&mut self, id: DeviceId) {
+        if self.devices.remove(&id).is_some() {
+            self.events_sink.push_device_event(DeviceEvent::Removed, Some(id));
+        }
+    }
+
+    /// The layouts of the keyboard of the first seat which has a keymap.
+    pub(crate) fn keyboard_layouts(&self) -> Vec<KeyboardLayout> {
+        self.seats
+            .values()
+            .filter_map(|seat_state| seat_state.keyboard_state.as_ref())
+            .map(|keyboard_state| keyboard_state.xkb_context.layouts())
+            .find(|layouts| !layouts.is_empty())
+            .unwrap_or_default()
+    }
+
+    /// The key the physical key produces, with the keymap of the first seat where it produces one.
+    pub(crate) fn key_for_physical_key(
         &mut self,
-        _connection: &Connection,
-        _queue_handle: &QueueHandle<Self>,
-        seat: WlSeat,
-    ) {
-        let _ = self.seats.remove(&seat.id());
-        self.on_keyboard_destroy(&seat.id());
+        physical_key: PhysicalKey,
+        modifiers: ModifiersState,
+        locked_keys: LockKeys,
+        level3: bool,
+    ) -> Option<Key> {
+        self.seats
+            .values_mut()
+            .filter_map(|seat_state| seat_state.keyboard_state.as_mut())
+            .find_map(|keyboard_state| {
+                keyboard_state.xkb_context.key_for_physical_key(
+                    physical_key,
+                    modifiers,
+                    locked_keys,
+                    level3,
+                    None,
+                )
+            })
+    }
+
+    /// The seat with the most recent input, along with the serial of that input.
+    pub(crate) fn latest_input_seat(&self) -> Option<(WlSeat, u32)> {
+        let (id, serial) = self
+            .seats
+            .iter()
+            .filter_map(|(id, seat_state)| Some((id, seat_state.latest_serial?)))
+            .max_by_key(|(_, serial)| *serial)?;
+        let seat = self.seat_state.seats().find(|seat| seat.id() == *id)?;
+        Some((seat.clone(), serial))
     }
-}
 
-impl WinitState {
     fn on_keyboard_destroy(&mut self, seat: &ObjectId) {
         for (window_id, window) in self.windows.get_mut() {
             let mut window = window.lock().unwrap();
@@ -228,4 +477,4 @@ impl WinitState {
     }
 }
 
-sctk::delegate_seat!(WinitState);
+delegate_dispatch!(WinitState: [WlSeat: SeatData] => SeatState);
@@ -0,0 +1,279 @@
+//! Pointer gestures, such as pinching on touchpads.
+
+use std::ops::Deref;
+use std::sync::Mutex;
+
+use sctk::globals::GlobalData;
+use sctk::reexports::client::globals::{BindError, GlobalList};
+use sctk::reexports::client::protocol::wl_pointer::WlPointer;
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::{delegate_dispatch, Connection, Dispatch, Proxy, QueueHandle};
+use sctk::reexports::protocols::wp::pointer_gestures::zv1::client::zwp_pointer_gesture_hold_v1::{
+    self, ZwpPointerGestureHoldV1,
+};
+use sctk::reexports::protocols::wp::pointer_gestures::zv1::client::zwp_pointer_gesture_pinch_v1::{
+    self, ZwpPointerGesturePinchV1,
+};
+use sctk::reexports::protocols::wp::pointer_gestures::zv1::client::zwp_pointer_gesture_swipe_v1::{
+    self, ZwpPointerGestureSwipeV1,
+};
+use sctk::reexports::protocols::wp::pointer_gestures::zv1::client::zwp_pointer_gestures_v1::ZwpPointerGesturesV1;
+
+use crate::dpi::{LogicalPosition, PhysicalPosition};
+use crate::event::{DeviceId, TouchPhase, WindowEvent};
+use crate::platform_impl::wayland::state::WinitState;
+use crate::platform_impl::wayland::{self, WindowId};
+
+/// Wrapper around the pointer gestures.
+pub struct PointerGesturesState {
+    manager: ZwpPointerGesturesV1,
+}
+
+impl PointerGesturesState {
+    /// Create new pointer gestures manager.
+    pub fn new(
+        globals: &GlobalList,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> Result<Self, BindError> {
+        let manager = globals.bind(queue_handle, 1..=3, GlobalData)?;
+        Ok(Self { manager })
+    }
+}
+
+impl Deref for PointerGesturesState {
+    type Target = ZwpPointerGesturesV1;
+
+    fn deref(&self) -> &Self::Target {
+        &self.manager
+    }
+}
+
+/// The gestures of a pointer.
+#[derive(Debug)]
+pub struct PointerGestures {
+    swipe: ZwpPointerGestureSwipeV1,
+    pinch: ZwpPointerGesturePinchV1,
+    /// Hold gestures need version 3 of the protocol.
+    hold: Option<ZwpPointerGestureHoldV1>,
+}
+
+impl PointerGestures {
+    pub fn new(
+        manager: &PointerGesturesState,
+        pointer: &WlPointer,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> Self {
+        let data = || PointerGestureData::new(wayland::make_did(pointer));
+        let swipe = manager.get_swipe_gesture(pointer, queue_handle, data());
+        let pinch = manager.get_pinch_gesture(pointer, queue_handle, data());
+        let hold = (manager.version() >= 3)
+            .then(|| manager.get_hold_gesture(pointer, queue_handle, data()));
+        Self { swipe, pinch, hold }
+    }
+
+    pub fn destroy(self) {
+        self.swipe.destroy();
+        self.pinch.destroy();
+        if let Some(hold) = self.hold {
+            hold.destroy();
+        }
+    }
+}
+
+/// The data of a gesture object.
+#[derive(Debug)]
+pub struct PointerGestureData {
+    /// The pointer performing the gesture.
+    device_id: DeviceId,
+
+    inner: Mutex<PointerGestureInner>,
+}
+
+impl PointerGestureData {
+    fn new(device_id: DeviceId) -> Self {
+        Self { device_id, inner: Mutex::new(PointerGestureInner { window_id: None, scale: 1.0 }) }
+    }
+
+    /// End the gesture, returning the window it was over along with its final phase.
+    fn end(&self, cancelled: i32) -> Option<(WindowId, TouchPhase)> {
+        let window_id = self.inner.lock().unwrap().window_id.take()?;
+        let phase = if cancelled != 0 { TouchPhase::Cancelled } else { TouchPhase::Ended };
+        Some((window_id, phase))
+    }
+}
+
+#[derive(Debug)]
+struct PointerGestureInner {
+    /// The window the gesture in progress started on.
+    window_id: Option<WindowId>,
+
+    /// The scale of the pinch in progress, which the compositor reports since it began.
+    scale: f64,
+}
+
+impl WinitState {
+    /// The scale factor of the window, if it's one of ours.
+    fn gesture_scale_factor(&mut self, window_id: WindowId) -> Option<f64> {
+        self.windows.get_mut().get(&window_id).map(|window| window.lock().unwrap().scale_factor())
+    }
+
+    /// Start a gesture on the surface, returning the window it's over.
+    fn gesture_begin(
+        &mut self,
+        data: &PointerGestureData,
+        surface: &WlSurface,
+    ) -> Option<WindowId> {
+        let window_id = wayland::make_wid(surface);
+        let window_id = self.gesture_scale_factor(window_id).map(|_| window_id);
+        let mut inner = data.inner.lock().unwrap();
+        inner.window_id = window_id;
+        inner.scale = 1.0;
+        window_id
+    }
+}
+
+impl Dispatch<ZwpPointerGesturesV1, GlobalData, WinitState> for PointerGesturesState {
+    fn event(
+        _state: &mut WinitState,
+        _proxy: &ZwpPointerGesturesV1,
+        _event: <ZwpPointerGesturesV1 as Proxy>::Event,
+        _data: &GlobalData,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<WinitState>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwpPointerGestureSwipeV1, PointerGestureData, WinitState> for PointerGesturesState {
+    fn event(
+        state: &mut WinitState,
+        _proxy: &ZwpPointerGestureSwipeV1,
+        event: <ZwpPointerGestureSwipeV1 as Proxy>::Event,
+        data: &PointerGestureData,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<WinitState>,
+    ) {
+        let (window_id, delta, phase) = match event {
+            zwp_pointer_gesture_swipe_v1::Event::Begin { surface, .. } => {
+                match state.gesture_begin(data, &surface) {
+                    Some(window_id) => {
+                        (window_id, PhysicalPosition::new(0., 0.), TouchPhase::Started)
+                    },
+                    None => return,
+                }
+            },
+            zwp_pointer_gesture_swipe_v1::Event::Update { dx, dy, .. } => {
+                let Some(window_id) = data.inner.lock().unwrap().window_id else {
+                    return;
+                };
+                let Some(scale_factor) = state.gesture_scale_factor(window_id) else {
+                    return;
+                };
+                let delta = LogicalPosition::new(dx, dy).to_physical(scale_factor);
+                (window_id, delta, TouchPhase::Moved)
+            },
+            zwp_pointer_gesture_swipe_v1::Event::End { cancelled, .. } => {
+                match data.end(cancelled) {
+                    Some((window_id, phase)) => (window_id, PhysicalPosition::new(0., 0.), phase),
+                    None => return,
+                }
+            },
+            _ => return,
+        };
+
+        state.events_sink.push_window_event(
+            WindowEvent::PanGesture { device_id: Some(data.device_id), delta, phase },
+            window_id,
+        );
+    }
+}
+
+impl Dispatch<ZwpPointerGesturePinchV1, PointerGestureData, WinitState> for PointerGesturesState {
+    fn event(
+        state: &mut WinitState,
+        _proxy: &ZwpPointerGesturePinchV1,
+        event: <ZwpPointerGesturePinchV1 as Proxy>::Event,
+        data: &PointerGestureData,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<WinitState>,
+    ) {
+        let (window_id, scale_delta, rotation, phase) = match event {
+            zwp_pointer_gesture_pinch_v1::Event::Begin { surface, .. } => {
+                match state.gesture_begin(data, &surface) {
+                    Some(window_id) => (window_id, 0., 0., TouchPhase::Started),
+                    None => return,
+                }
+            },
+            zwp_pointer_gesture_pinch_v1::Event::Update { scale, rotation, .. } => {
+                let mut inner = data.inner.lock().unwrap();
+                let Some(window_id) = inner.window_id else {
+                    return;
+                };
+                let scale_delta = scale - std::mem::replace(&mut inner.scale, scale);
+                (window_id, scale_delta, rotation, TouchPhase::Moved)
+            },
+            zwp_pointer_gesture_pinch_v1::Event::End { cancelled, .. } => {
+                match data.end(cancelled) {
+                    Some((window_id, phase)) => (window_id, 0., 0., phase),
+                    None => return,
+                }
+            },
+            _ => return,
+        };
+
+        let device_id = Some(data.device_id);
+        state.events_sink.push_window_event(
+            WindowEvent::PinchGesture { device_id, delta: scale_delta, phase },
+            window_id,
+        );
+
+        // The compositor rotates clockwise, while the event is counterclockwise.
+        state.events_sink.push_window_event(
+            WindowEvent::RotationGesture { device_id, delta: -rotation as f32, phase },
+            window_id,
+        );
+    }
+}
+
+impl Dispatch<ZwpPointerGestureHoldV1, PointerGestureData, WinitState> for PointerGesturesState {
+    fn event(
+        state: &mut WinitState,
+        _proxy: &ZwpPointerGestureHoldV1,
+        event: <ZwpPointerGestureHoldV1 as Proxy>::Event,
+        data: &PointerGestureData,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<WinitState>,
+    ) {
+        // Holding the fingers still is reported as a pan without movement, which lets kinetic
+        // scrolling be stopped.
+        let (window_id, phase) = match event {
+            zwp_pointer_gesture_hold_v1::Event::Begin { surface, .. } => {
+                match state.gesture_begin(data, &surface) {
+                    Some(window_id) => (window_id, TouchPhase::Started),
+                    None => return,
+                }
+            },
+            zwp_pointer_gesture_hold_v1::Event::End { cancelled, .. } => {
+                match data.end(cancelled) {
+                    Some(end) => end,
+                    None => return,
+                }
+            },
+            _ => return,
+        };
+
+        state.events_sink.push_window_event(
+            WindowEvent::PanGesture {
+                device_id: Some(data.device_id),
+                delta: PhysicalPosition::new(0., 0.),
+                phase,
+            },
+            window_id,
+        );
+    }
+}
+
+delegate_dispatch!(WinitState: [ZwpPointerGesturesV1: GlobalData] => PointerGesturesState);
+delegate_dispatch!(WinitState: [ZwpPointerGestureSwipeV1: PointerGestureData] => PointerGesturesState);
+delegate_dispatch!(WinitState: [ZwpPointerGesturePinchV1: PointerGestureData] => PointerGesturesState);
+delegate_dispatch!(WinitState: [ZwpPointerGestureHoldV1: PointerGestureData] => PointerGesturesState);
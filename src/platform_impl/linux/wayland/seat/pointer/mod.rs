@@ -1,19 +1,25 @@
 //! The pointer events.
 
+use std::collections::hash_map::Entry;
 use std::ops::Deref;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use std::{env, iter};
 
+use ahash::AHashMap;
 use tracing::warn;
+use wayland_cursor::CursorTheme;
 
 use sctk::reexports::client::delegate_dispatch;
-use sctk::reexports::client::protocol::wl_pointer::WlPointer;
+use sctk::reexports::client::protocol::wl_pointer::{self, Axis, AxisSource, WlPointer};
+use sctk::reexports::client::protocol::wl_region::WlRegion;
 use sctk::reexports::client::protocol::wl_seat::WlSeat;
+use sctk::reexports::client::protocol::wl_shm::WlShm;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
-use sctk::reexports::client::{Connection, Proxy, QueueHandle, Dispatch};
+use sctk::reexports::client::{Connection, Proxy, QueueHandle, Dispatch, WEnum};
 use sctk::reexports::protocols::wp::pointer_constraints::zv1::client::zwp_confined_pointer_v1::ZwpConfinedPointerV1;
 use sctk::reexports::protocols::wp::pointer_constraints::zv1::client::zwp_locked_pointer_v1::ZwpLockedPointerV1;
-use sctk::reexports::protocols::wp::cursor_shape::v1::client::wp_cursor_shape_device_v1::WpCursorShapeDeviceV1;
+use sctk::reexports::protocols::wp::cursor_shape::v1::client::wp_cursor_shape_device_v1::{Shape, WpCursorShapeDeviceV1};
 use sctk::reexports::protocols::wp::cursor_shape::v1::client::wp_cursor_shape_manager_v1::WpCursorShapeManagerV1;
 use sctk::reexports::protocols::wp::pointer_constraints::zv1::client::zwp_pointer_constraints_v1::{Lifetime, ZwpPointerConstraintsV1};
 use sctk::reexports::client::globals::{BindError, GlobalList};
@@ -21,17 +27,24 @@ use sctk::reexports::csd_frame::FrameClick;
 
 use sctk::compositor::SurfaceData;
 use sctk::globals::GlobalData;
+use sctk::seat::pointer::cursor_shape::CursorShapeManager;
 use sctk::seat::pointer::{
     PointerData, PointerDataExt, PointerEvent, PointerEventKind, PointerHandler,
+    PointerThemeError,
 };
-use sctk::seat::SeatState;
 
 use crate::dpi::{LogicalPosition, PhysicalPosition};
-use crate::event::{ElementState, MouseButton, MouseScrollDelta, PointerSource, PointerKind, TouchPhase, WindowEvent};
+use crate::event::{
+    ElementState, MouseButton, MouseScrollDelta, PointerKind, PointerSource, ScrollSource,
+    TouchPhase, WindowEvent,
+};
 
+use crate::platform_impl::wayland::seat::SeatState;
 use crate::platform_impl::wayland::state::WinitState;
 use crate::platform_impl::wayland::{self, WindowId};
+use crate::window::CursorIcon;
 
+pub mod gestures;
 pub mod relative_pointer;
 
 impl PointerHandler for WinitState {
@@ -59,6 +72,8 @@ impl PointerHandler for WinitState {
             },
         };
 
+        let device_id = Some(wayland::make_did(pointer));
+        let mut latest_serial = None;
         for event in events {
             let surface = &event.surface;
 
@@ -125,7 +140,7 @@ impl PointerHandler for WinitState {
                     self.events_sink.push_window_event(
                         WindowEvent::PointerEntered {
                             primary: true,
-                            device_id: None,
+                            device_id,
                             position,
                             kind: PointerKind::Mouse,
                         },
@@ -135,18 +150,25 @@ impl PointerHandler for WinitState {
                     window.pointer_entered(Arc::downgrade(themed_pointer));
 
                     // Set the currently focused surface.
-                    pointer.winit_data().inner.lock().unwrap().surface = Some(window_id);
+                    let mut pointer_data = pointer.winit_data().inner.lock().unwrap();
+                    pointer_data.surface = Some(window_id);
+                    pointer_data.pressed_buttons.clear();
+                    pointer_data.position = Some(event.position.into());
                 },
                 PointerEventKind::Leave { .. } => {
                     window.pointer_left(Arc::downgrade(themed_pointer));
 
                     // Remove the active surface.
-                    pointer.winit_data().inner.lock().unwrap().surface = None;
+                    let mut pointer_data = pointer.winit_data().inner.lock().unwrap();
+                    pointer_data.surface = None;
+                    pointer_data.pressed_buttons.clear();
+                    pointer_data.position = None;
+                    drop(pointer_data);
 
                     self.events_sink.push_window_event(
                         WindowEvent::PointerLeft {
                             primary: true,
-                            device_id: None,
+                            device_id,
                             position: Some(position),
                             kind: PointerKind::Mouse,
                         },
@@ -154,43 +176,66 @@ impl PointerHandler for WinitState {
                     );
                 },
                 PointerEventKind::Motion { .. } => {
+                    pointer.winit_data().inner.lock().unwrap().position =
+                        Some(event.position.into());
+
                     self.events_sink.push_window_event(
                         WindowEvent::PointerMoved {
                             primary: true,
-                            device_id: None,
+                            device_id,
                             position,
                             source: PointerSource::Mouse,
                         },
                         window_id,
                     );
                 },
-                ref kind @ PointerEventKind::Press { button, serial, .. }
-                | ref kind @ PointerEventKind::Release { button, serial, .. } => {
-                    // Update the last button serial.
-                    pointer.winit_data().inner.lock().unwrap().latest_button_serial = serial;
-
+                ref kind @ PointerEventKind::Press { button, serial, time }
+                | ref kind @ PointerEventKind::Release { button, serial, time } => {
                     let button = wayland_button_to_winit(button);
                     let state = if matches!(kind, PointerEventKind::Press { .. }) {
                         ElementState::Pressed
                     } else {
                         ElementState::Released
                     };
+
+                    // Update the last button serial and the pressed buttons.
+                    let mut pointer_data = pointer.winit_data().inner.lock().unwrap();
+                    pointer_data.latest_button_serial = serial;
+                    wayland::update_pressed(&mut pointer_data.pressed_buttons, button, state);
+                    drop(pointer_data);
+                    latest_serial = Some(serial);
                     self.events_sink.push_window_event(
                         WindowEvent::PointerButton {
                             primary: true,
-                            device_id: None,
+                            device_id,
                             state,
                             position,
                             button: button.into(),
                         },
                         window_id,
                     );
+
+                    window.hit_test_click(
+                        button,
+                        state,
+                        event.position.into(),
+                        seat,
+                        serial,
+                        Duration::from_millis(time as u64),
+                    );
                 },
-                PointerEventKind::Axis { horizontal, vertical, .. } => {
+                PointerEventKind::Axis { horizontal, vertical, source, .. } => {
                     // Get the current phase.
                     let mut pointer_data = pointer.winit_data().inner.lock().unwrap();
 
-                    let has_discrete_scroll = horizontal.discrete != 0 || vertical.discrete != 0;
+                    // Wheels report their scroll in fractions of a detent since version 8, and
+                    // in whole detents before.
+                    let value120 = pointer_data.value120.take().or_else(|| {
+                        (horizontal.discrete != 0 || vertical.discrete != 0)
+                            .then(|| (horizontal.discrete * 120, vertical.discrete * 120))
+                    });
+                    let has_discrete_scroll = value120.is_some();
+                    let source = source.and_then(wayland_axis_source_to_winit);
 
                     // Figure out what to do about start/ended phases here.
                     //
@@ -202,6 +247,13 @@ impl PointerHandler for WinitState {
                         match pointer_data.phase {
                             // Discrete scroll only results in moved events.
                             _ if has_discrete_scroll => TouchPhase::Moved,
+                            _ if matches!(
+                                source,
+                                Some(ScrollSource::Wheel | ScrollSource::WheelTilt)
+                            ) =>
+                            {
+                                TouchPhase::Moved
+                            },
                             TouchPhase::Started | TouchPhase::Moved => TouchPhase::Moved,
                             _ => TouchPhase::Started,
                         }
@@ -210,14 +262,13 @@ impl PointerHandler for WinitState {
                     // Update the phase.
                     pointer_data.phase = phase;
 
+                    // NOTE: Wayland sign convention is the inverse of winit.
+                    let value120 = value120.map(|(x, y)| (-x, -y));
+
                     // Mice events have both pixel and discrete delta's at the same time. So prefer
                     // the descrite values if they are present.
-                    let delta = if has_discrete_scroll {
-                        // NOTE: Wayland sign convention is the inverse of winit.
-                        MouseScrollDelta::LineDelta(
-                            (-horizontal.discrete) as f32,
-                            (-vertical.discrete) as f32,
-                        )
+                    let delta = if let Some((x, y)) = value120 {
+                        MouseScrollDelta::LineDelta(x as f32 / 120.0, y as f32 / 120.0)
                     } else {
                         // NOTE: Wayland sign convention is the inverse of winit.
                         MouseScrollDelta::PixelDelta(
@@ -227,12 +278,16 @@ impl PointerHandler for WinitState {
                     };
 
                     self.events_sink.push_window_event(
-                        WindowEvent::MouseWheel { device_id: None, delta, phase },
+                        WindowEvent::MouseWheel { device_id, delta, phase, source, value120 },
                         window_id,
                     )
                 },
             }
         }
+
+        if let Some(seat_state) = latest_serial.and_then(|_| self.seats.get_mut(&seat.id())) {
+            seat_state.latest_serial = latest_serial;
+        }
     }
 }
 
@@ -285,12 +340,13 @@ impl WinitPointerData {
         pointer_constraints: &PointerConstraintsState,
         surface: &WlSurface,
         pointer: &WlPointer,
+        region: Option<&WlRegion>,
         queue_handle: &QueueHandle<WinitState>,
     ) {
         self.inner.lock().unwrap().confined_pointer = Some(pointer_constraints.confine_pointer(
             surface,
             pointer,
-            None,
+            region,
             Lifetime::Persistent,
             queue_handle,
             GlobalData,
@@ -314,6 +370,16 @@ impl WinitPointerData {
         self.inner.lock().unwrap().surface
     }
 
+    /// The buttons pressed on the active window.
+    pub fn pressed_buttons(&self) -> Vec<MouseButton> {
+        self.inner.lock().unwrap().pressed_buttons.clone()
+    }
+
+    /// The surface local position on the active window.
+    pub fn position(&self) -> Option<LogicalPosition<f64>> {
+        self.inner.lock().unwrap().position
+    }
+
     /// Last button serial.
     pub fn latest_button_serial(&self) -> u32 {
         self.sctk_data.latest_button_serial().unwrap_or_default()
@@ -324,6 +390,17 @@ impl WinitPointerData {
         self.sctk_data.latest_enter_serial().unwrap_or_default()
     }
 
+    /// Add the scroll of a wheel in the current frame, in fractions of a detent.
+    fn add_value120(&self, axis: Axis, value120: i32) {
+        let mut inner = self.inner.lock().unwrap();
+        let (horizontal, vertical) = inner.value120.get_or_insert((0, 0));
+        match axis {
+            Axis::HorizontalScroll => *horizontal += value120,
+            Axis::VerticalScroll => *vertical += value120,
+            _ => (),
+        }
+    }
+
     pub fn set_locked_cursor_position(&self, surface_x: f64, surface_y: f64) {
         let inner = self.inner.lock().unwrap();
         if let Some(locked_pointer) = inner.locked_pointer.as_ref() {
@@ -354,6 +431,15 @@ pub struct WinitPointerDataInner {
 
     /// Current axis phase.
     phase: TouchPhase,
+
+    /// The buttons pressed on the focused window.
+    pressed_buttons: Vec<MouseButton>,
+
+    /// The surface local position on the focused window.
+    position: Option<LogicalPosition<f64>>,
+
+    /// The scroll of wheels in the current frame, in fractions of a detent.
+    value120: Option<(i32, i32)>,
 }
 
 impl Drop for WinitPointerDataInner {
@@ -376,6 +462,9 @@ impl Default for WinitPointerDataInner {
             confined_pointer: None,
             latest_button_serial: 0,
             phase: TouchPhase::Ended,
+            pressed_buttons: Vec::new(),
+            position: None,
+            value120: None,
         }
     }
 }
@@ -401,6 +490,16 @@ fn wayland_button_to_winit(button: u32) -> MouseButton {
     }
 }
 
+fn wayland_axis_source_to_winit(source: AxisSource) -> Option<ScrollSource> {
+    match source {
+        AxisSource::Wheel => Some(ScrollSource::Wheel),
+        AxisSource::Finger => Some(ScrollSource::Finger),
+        AxisSource::Continuous => Some(ScrollSource::Continuous),
+        AxisSource::WheelTilt => Some(ScrollSource::WheelTilt),
+        _ => None,
+    }
+}
+
 pub trait WinitPointerDataExt {
     fn winit_data(&self) -> &WinitPointerData;
 }
@@ -469,35 +568,202 @@ impl Dispatch<ZwpConfinedPointerV1, GlobalData, WinitState> for PointerConstrain
     }
 }
 
-impl Dispatch<WpCursorShapeDeviceV1, GlobalData, WinitState> for SeatState {
+impl Dispatch<WlPointer, WinitPointerData, WinitState> for SeatState {
     fn event(
-        _: &mut WinitState,
-        _: &WpCursorShapeDeviceV1,
-        _: <WpCursorShapeDeviceV1 as Proxy>::Event,
-        _: &GlobalData,
-        _: &Connection,
-        _: &QueueHandle<WinitState>,
+        state: &mut WinitState,
+        pointer: &WlPointer,
+        event: wl_pointer::Event,
+        data: &WinitPointerData,
+        connection: &Connection,
+        queue_handle: &QueueHandle<WinitState>,
     ) {
-        unreachable!("wp_cursor_shape_manager has no events")
+        match event {
+            // sctk doesn't handle the events past version 7, so keep the scroll of wheels for the
+            // frame it emits.
+            wl_pointer::Event::AxisValue120 { axis: WEnum::Value(axis), value120 } => {
+                data.add_value120(axis, value120);
+            },
+            wl_pointer::Event::AxisValue120 { .. } => (),
+            event => {
+                let frame = matches!(event, wl_pointer::Event::Frame);
+                <sctk::seat::SeatState as Dispatch<WlPointer, WinitPointerData, WinitState>>::event(
+                    state,
+                    pointer,
+                    event,
+                    data,
+                    connection,
+                    queue_handle,
+                );
+
+                // Don't carry the scroll over when the frame didn't have an axis event for it.
+                if frame {
+                    data.inner.lock().unwrap().value120 = None;
+                }
+            },
+        }
     }
 }
 
-impl Dispatch<WpCursorShapeManagerV1, GlobalData, WinitState> for SeatState {
-    fn event(
-        _: &mut WinitState,
-        _: &WpCursorShapeManagerV1,
-        _: <WpCursorShapeManagerV1 as Proxy>::Event,
-        _: &GlobalData,
-        _: &Connection,
-        _: &QueueHandle<WinitState>,
-    ) {
-        unreachable!("wp_cursor_device_manager has no events")
+/// A pointer, along with the surface showing its cursor.
+#[derive(Debug)]
+pub struct ThemedPointer {
+    pointer: WlPointer,
+
+    /// The surface presenting the cursor icons drawn by winit.
+    surface: WlSurface,
+
+    /// The device setting the cursor icons drawn by the compositor, when it can.
+    shape_device: Option<WpCursorShapeDeviceV1>,
+
+    shm: WlShm,
+
+    /// The cursor theme, loaded for each scale it was needed at.
+    themes: Mutex<AHashMap<u32, CursorTheme>>,
+}
+
+impl ThemedPointer {
+    pub fn new(
+        pointer: WlPointer,
+        surface: WlSurface,
+        shm: WlShm,
+        cursor_shape_manager: Option<&CursorShapeManager>,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> Self {
+        let shape_device =
+            cursor_shape_manager.map(|manager| manager.get_shape_device(&pointer, queue_handle));
+        Self { pointer, surface, shape_device, shm, themes: Default::default() }
+    }
+
+    pub fn pointer(&self) -> &WlPointer {
+        &self.pointer
+    }
+
+    pub fn surface(&self) -> &WlSurface {
+        &self.surface
+    }
+
+    /// Set the cursor to the given icon, from the compositor or the cursor theme.
+    pub fn set_cursor(
+        &self,
+        connection: &Connection,
+        icon: CursorIcon,
+    ) -> Result<(), PointerThemeError> {
+        let serial = self
+            .pointer
+            .winit_data()
+            .sctk_data
+            .latest_enter_serial()
+            .ok_or(PointerThemeError::MissingEnterSerial)?;
+
+        if let Some(shape_device) = self.shape_device.as_ref() {
+            shape_device.set_shape(serial, cursor_icon_to_shape(icon));
+            return Ok(());
+        }
+
+        let scale = self.surface.data::<SurfaceData>().unwrap().scale_factor();
+        let mut themes = self.themes.lock().unwrap();
+        let theme = match themes.entry(scale as u32) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let name = env::var("XCURSOR_THEME").unwrap_or_else(|_| "default".into());
+                let size =
+                    env::var("XCURSOR_SIZE").ok().and_then(|size| size.parse().ok()).unwrap_or(24);
+                let theme = CursorTheme::load_from_name(
+                    connection,
+                    self.shm.clone(),
+                    &name,
+                    size * scale as u32,
+                )
+                .map_err(PointerThemeError::InvalidId)?;
+                entry.insert(theme)
+            },
+        };
+
+        let name = iter::once(icon.name())
+            .chain(icon.alt_names().iter().copied())
+            .find(|name| theme.get_cursor(name).is_some())
+            .ok_or(PointerThemeError::CursorNotFound)?;
+        let image = &theme.get_cursor(name).unwrap()[0];
+        let (width, height) = image.dimensions();
+        let (hotspot_x, hotspot_y) = image.hotspot();
+
+        self.surface.set_buffer_scale(scale);
+        self.surface.attach(Some(image), 0, 0);
+        if self.surface.version() >= 4 {
+            self.surface.damage_buffer(0, 0, width as i32, height as i32);
+        } else {
+            self.surface.damage(0, 0, width as i32 / scale, height as i32 / scale);
+        }
+        self.surface.commit();
+
+        self.pointer.set_cursor(
+            serial,
+            Some(&self.surface),
+            hotspot_x as i32 / scale,
+            hotspot_y as i32 / scale,
+        );
+
+        Ok(())
+    }
+}
+
+impl Drop for ThemedPointer {
+    fn drop(&mut self) {
+        if let Some(shape_device) = self.shape_device.take() {
+            shape_device.destroy();
+        }
+
+        if self.pointer.version() >= 3 {
+            self.pointer.release();
+        }
+
+        self.surface.destroy();
+    }
+}
+
+fn cursor_icon_to_shape(icon: CursorIcon) -> Shape {
+    match icon {
+        CursorIcon::Default => Shape::Default,
+        CursorIcon::ContextMenu => Shape::ContextMenu,
+        CursorIcon::Help => Shape::Help,
+        CursorIcon::Pointer => Shape::Pointer,
+        CursorIcon::Progress => Shape::Progress,
+        CursorIcon::Wait => Shape::Wait,
+        CursorIcon::Cell => Shape::Cell,
+        CursorIcon::Crosshair => Shape::Crosshair,
+        CursorIcon::Text => Shape::Text,
+        CursorIcon::VerticalText => Shape::VerticalText,
+        CursorIcon::Alias => Shape::Alias,
+        CursorIcon::Copy => Shape::Copy,
+        CursorIcon::Move => Shape::Move,
+        CursorIcon::NoDrop => Shape::NoDrop,
+        CursorIcon::NotAllowed => Shape::NotAllowed,
+        CursorIcon::Grab => Shape::Grab,
+        CursorIcon::Grabbing => Shape::Grabbing,
+        CursorIcon::EResize => Shape::EResize,
+        CursorIcon::NResize => Shape::NResize,
+        CursorIcon::NeResize => Shape::NeResize,
+        CursorIcon::NwResize => Shape::NwResize,
+        CursorIcon::SResize => Shape::SResize,
+        CursorIcon::SeResize => Shape::SeResize,
+        CursorIcon::SwResize => Shape::SwResize,
+        CursorIcon::WResize => Shape::WResize,
+        CursorIcon::EwResize => Shape::EwResize,
+        CursorIcon::NsResize => Shape::NsResize,
+        CursorIcon::NeswResize => Shape::NeswResize,
+        CursorIcon::NwseResize => Shape::NwseResize,
+        CursorIcon::ColResize => Shape::ColResize,
+        CursorIcon::RowResize => Shape::RowResize,
+        CursorIcon::AllScroll => Shape::AllScroll,
+        CursorIcon::ZoomIn => Shape::ZoomIn,
+        CursorIcon::ZoomOut => Shape::ZoomOut,
+        _ => Shape::Default,
     }
 }
 
 delegate_dispatch!(WinitState: [ WlPointer: WinitPointerData] => SeatState);
-delegate_dispatch!(WinitState: [ WpCursorShapeManagerV1: GlobalData] => SeatState);
-delegate_dispatch!(WinitState: [ WpCursorShapeDeviceV1: GlobalData] => SeatState);
+delegate_dispatch!(WinitState: [ WpCursorShapeManagerV1: GlobalData] => CursorShapeManager);
+delegate_dispatch!(WinitState: [ WpCursorShapeDeviceV1: GlobalData] => CursorShapeManager);
 delegate_dispatch!(WinitState: [ZwpPointerConstraintsV1: GlobalData] => PointerConstraintsState);
 delegate_dispatch!(WinitState: [ZwpLockedPointerV1: GlobalData] => PointerConstraintsState);
 delegate_dispatch!(WinitState: [ZwpConfinedPointerV1: GlobalData] => PointerConstraintsState);
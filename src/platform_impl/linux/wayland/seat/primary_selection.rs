@@ -0,0 +1,61 @@
+//! Primary selection handling through `zwp_primary_selection_device_manager_v1`.
+
+use sctk::data_device_manager::WritePipe;
+use sctk::primary_selection::device::PrimarySelectionDeviceHandler;
+use sctk::primary_selection::selection::PrimarySelectionSourceHandler;
+use sctk::reexports::client::{Connection, QueueHandle};
+use sctk::reexports::protocols::wp::primary_selection::zv1::client::zwp_primary_selection_device_v1::ZwpPrimarySelectionDeviceV1;
+use sctk::reexports::protocols::wp::primary_selection::zv1::client::zwp_primary_selection_source_v1::ZwpPrimarySelectionSourceV1;
+
+use super::data_device::{convert_contents, send_data};
+use crate::platform_impl::wayland::state::WinitState;
+
+impl PrimarySelectionDeviceHandler for WinitState {
+    fn selection(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &ZwpPrimarySelectionDeviceV1,
+    ) {
+        // The offer is queried from the device when the primary selection is read.
+    }
+}
+
+impl PrimarySelectionSourceHandler for WinitState {
+    fn send_request(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        source: &ZwpPrimarySelectionSourceV1,
+        mime_type: String,
+        pipe: WritePipe,
+    ) {
+        let data = self
+            .primary_selection_source
+            .as_ref()
+            .filter(|selection| selection.source.inner() == source)
+            .and_then(|selection| convert_contents(&selection.contents, &mime_type));
+
+        // Dropping the pipe without writing anything signals that there's no data.
+        if let Some(data) = data {
+            send_data(self, pipe, data);
+        }
+    }
+
+    fn cancelled(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        source: &ZwpPrimarySelectionSourceV1,
+    ) {
+        if self
+            .primary_selection_source
+            .as_ref()
+            .is_some_and(|selection| selection.source.inner() == source)
+        {
+            self.primary_selection_source = None;
+        }
+    }
+}
+
+sctk::delegate_primary_selection!(WinitState);
@@ -0,0 +1,415 @@
+//! Drawing tablets.
+
+use std::ops::Deref;
+use std::sync::Mutex;
+
+use sctk::globals::GlobalData;
+use sctk::reexports::client::globals::{BindError, GlobalList};
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::{
+    delegate_dispatch, event_created_child, Connection, Dispatch, Proxy, QueueHandle, WEnum,
+};
+use sctk::reexports::protocols::wp::cursor_shape::v1::client::wp_cursor_shape_device_v1::WpCursorShapeDeviceV1;
+use sctk::reexports::protocols::wp::cursor_shape::v1::client::wp_cursor_shape_manager_v1::WpCursorShapeManagerV1;
+use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_manager_v2::ZwpTabletManagerV2;
+use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_pad_v2::ZwpTabletPadV2;
+use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_seat_v2::{
+    self, ZwpTabletSeatV2,
+};
+use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_tool_v2::{
+    self, ButtonState, Capability, Type, ZwpTabletToolV2,
+};
+use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_v2::{self, ZwpTabletV2};
+
+use crate::dpi::LogicalPosition;
+use crate::event::{
+    ButtonSource, DeviceId, DeviceKind, ElementState, Force, PenButton, PenData, PenTilt, PenTool,
+    PointerKind, PointerSource, WindowEvent,
+};
+use crate::platform_impl::wayland::state::WinitState;
+use crate::platform_impl::wayland::{self, WindowId};
+
+mod pad;
+
+use pad::TabletPadData;
+
+/// The maximum value of the pressure and distance axes.
+const AXIS_MAX: f64 = 65535.0;
+
+/// Wrapper around the tablet manager.
+#[derive(Debug)]
+pub struct TabletManagerState {
+    manager: ZwpTabletManagerV2,
+
+    /// The manager of the cursor shapes of the tools, if available.
+    cursor_shape_manager: Option<WpCursorShapeManagerV1>,
+}
+
+impl TabletManagerState {
+    /// Create new tablet manager.
+    pub fn new(
+        globals: &GlobalList,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> Result<Self, BindError> {
+        let manager = globals.bind(queue_handle, 1..=1, GlobalData)?;
+        let cursor_shape_manager = globals.bind(queue_handle, 1..=1, GlobalData).ok();
+        Ok(Self { manager, cursor_shape_manager })
+    }
+}
+
+impl Deref for TabletManagerState {
+    type Target = ZwpTabletManagerV2;
+
+    fn deref(&self) -> &Self::Target {
+        &self.manager
+    }
+}
+
+/// The data of a tablet, gathered until its `done` event.
+#[derive(Debug, Default)]
+pub struct TabletData {
+    inner: Mutex<TabletInner>,
+}
+
+#[derive(Debug, Default)]
+struct TabletInner {
+    name: Option<String>,
+    vendor_product_id: Option<(u16, u16)>,
+}
+
+/// The data of a tablet tool, such as a pen or an eraser.
+#[derive(Debug)]
+pub struct TabletToolData {
+    inner: Mutex<TabletToolInner>,
+}
+
+impl TabletToolData {
+    fn new() -> Self {
+        Self {
+            inner: Mutex::new(TabletToolInner {
+                tool: PenTool::Pen,
+                data: PenData::default(),
+                device_id: None,
+                window_id: None,
+                position: LogicalPosition::new(0., 0.),
+                cursor: TabletToolCursor::default(),
+                frame: TabletToolFrame::default(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TabletToolInner {
+    tool: PenTool,
+
+    /// The current state of the axes, those the tool doesn't have are `None`.
+    data: PenData,
+
+    /// The tablet the tool is in proximity of.
+    device_id: Option<DeviceId>,
+
+    /// The window the tool is in proximity of.
+    window_id: Option<WindowId>,
+
+    /// The surface local position of the tool.
+    position: LogicalPosition<f64>,
+
+    /// The cursor shown for the tool over our windows.
+    cursor: TabletToolCursor,
+
+    /// The changes accumulated until the next `frame` event.
+    frame: TabletToolFrame,
+}
+
+/// The objects showing the cursor of a tool, created on its first proximity.
+#[derive(Debug, Default)]
+struct TabletToolCursor {
+    shape_device: Option<WpCursorShapeDeviceV1>,
+    surface: Option<WlSurface>,
+}
+
+#[derive(Debug, Default)]
+struct TabletToolFrame {
+    /// The surface the tool came in proximity of, along with the serial of the event.
+    proximity_in: Option<(WlSurface, u32)>,
+    proximity_out: bool,
+    moved: bool,
+    buttons: Vec<(PenButton, ElementState)>,
+}
+
+impl WinitState {
+    /// Deliver the events of a tablet tool accumulated until its `frame` event.
+    fn tablet_tool_frame(
+        &mut self,
+        tool: &ZwpTabletToolV2,
+        data: &TabletToolData,
+        queue_handle: &QueueHandle<WinitState>,
+    ) {
+        let mut inner = data.inner.lock().unwrap();
+        let frame = std::mem::take(&mut inner.frame);
+
+        if let Some((surface, _)) = frame.proximity_in.as_ref() {
+            inner.window_id = Some(wayland::make_wid(surface));
+        }
+
+        let Some(window_id) = inner.window_id else {
+            return;
+        };
+
+        if frame.proximity_out {
+            inner.window_id = None;
+        }
+
+        // Tools over other surfaces, like the decorations, are ignored.
+        let Some(window) = self.windows.get_mut().get(&window_id) else {
+            return;
+        };
+        let window = window.lock().unwrap();
+        let scale_factor = window.scale_factor();
+
+        if let Some((_, serial)) = frame.proximity_in.as_ref() {
+            let cursor = &mut inner.cursor;
+            if cursor.shape_device.is_none() {
+                cursor.shape_device = self
+                    .tablet_manager
+                    .as_ref()
+                    .and_then(|manager| manager.cursor_shape_manager.as_ref())
+                    .map(|manager| manager.get_tablet_tool_v2(tool, queue_handle, GlobalData));
+            }
+            let surface = cursor
+                .surface
+                .get_or_insert_with(|| self.compositor_state.create_surface(queue_handle));
+            window.set_tablet_tool_cursor(tool, *serial, cursor.shape_device.as_ref(), surface);
+        }
+        drop(window);
+
+        let position = inner.position.to_physical(scale_factor);
+        let (tool, data, device_id) = (inner.tool, inner.data, inner.device_id);
+        drop(inner);
+
+        if frame.proximity_in.is_some() {
+            self.events_sink.push_window_event(
+                WindowEvent::PointerEntered {
+                    device_id,
+                    primary: true,
+                    position,
+                    kind: PointerKind::Pen(tool),
+                },
+                window_id,
+            );
+        }
+
+        if frame.moved {
+            self.events_sink.push_window_event(
+                WindowEvent::PointerMoved {
+                    device_id,
+                    primary: true,
+                    position,
+                    source: PointerSource::Pen { tool, data },
+                },
+                window_id,
+            );
+        }
+
+        for (button, state) in frame.buttons {
+            self.events_sink.push_window_event(
+                WindowEvent::PointerButton {
+                    device_id,
+                    primary: true,
+                    state,
+                    position,
+                    button: ButtonSource::Pen { tool, button, data },
+                },
+                window_id,
+            );
+        }
+
+        if frame.proximity_out {
+            self.events_sink.push_window_event(
+                WindowEvent::PointerLeft {
+                    device_id,
+                    primary: true,
+                    position: Some(position),
+                    kind: PointerKind::Pen(tool),
+                },
+                window_id,
+            );
+        }
+    }
+}
+
+impl Dispatch<ZwpTabletManagerV2, GlobalData, WinitState> for TabletManagerState {
+    fn event(
+        _state: &mut WinitState,
+        _proxy: &ZwpTabletManagerV2,
+        _event: <ZwpTabletManagerV2 as Proxy>::Event,
+        _data: &GlobalData,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<WinitState>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwpTabletSeatV2, GlobalData, WinitState> for TabletManagerState {
+    event_created_child!(WinitState, ZwpTabletSeatV2, [
+        zwp_tablet_seat_v2::EVT_TABLET_ADDED_OPCODE => (ZwpTabletV2, TabletData::default()),
+        zwp_tablet_seat_v2::EVT_TOOL_ADDED_OPCODE => (ZwpTabletToolV2, TabletToolData::new()),
+        zwp_tablet_seat_v2::EVT_PAD_ADDED_OPCODE => (ZwpTabletPadV2, TabletPadData::default()),
+    ]);
+
+    fn event(
+        _state: &mut WinitState,
+        _proxy: &ZwpTabletSeatV2,
+        _event: <ZwpTabletSeatV2 as Proxy>::Event,
+        _data: &GlobalData,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<WinitState>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwpTabletV2, TabletData, WinitState> for TabletManagerState {
+    fn event(
+        state: &mut WinitState,
+        proxy: &ZwpTabletV2,
+        event: <ZwpTabletV2 as Proxy>::Event,
+        data: &TabletData,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<WinitState>,
+    ) {
+        let mut inner = data.inner.lock().unwrap();
+        match event {
+            zwp_tablet_v2::Event::Name { name } => inner.name = Some(name),
+            zwp_tablet_v2::Event::Id { vid, pid } => {
+                inner.vendor_product_id = Some((vid as u16, pid as u16));
+            },
+            zwp_tablet_v2::Event::Done => {
+                let name = inner.name.clone();
+                let vendor_product_id = inner.vendor_product_id;
+                drop(inner);
+                state.add_device(
+                    wayland::make_did(proxy),
+                    DeviceKind::Tablet,
+                    name,
+                    vendor_product_id,
+                );
+            },
+            zwp_tablet_v2::Event::Removed => {
+                drop(inner);
+                state.remove_device(wayland::make_did(proxy));
+                proxy.destroy();
+            },
+            _ => (),
+        }
+    }
+}
+
+impl Dispatch<ZwpTabletToolV2, TabletToolData, WinitState> for TabletManagerState {
+    fn event(
+        state: &mut WinitState,
+        proxy: &ZwpTabletToolV2,
+        event: <ZwpTabletToolV2 as Proxy>::Event,
+        data: &TabletToolData,
+        _conn: &Connection,
+        qhandle: &QueueHandle<WinitState>,
+    ) {
+        let mut inner = data.inner.lock().unwrap();
+        match event {
+            zwp_tablet_tool_v2::Event::Type { tool_type: WEnum::Value(tool_type) } => {
+                inner.tool = match tool_type {
+                    Type::Eraser => PenTool::Eraser,
+                    _ => PenTool::Pen,
+                };
+            },
+            zwp_tablet_tool_v2::Event::Capability { capability: WEnum::Value(capability) } => {
+                match capability {
+                    Capability::Pressure => inner.data.force = Some(Force::Normalized(0.)),
+                    Capability::Tilt => inner.data.tilt = Some(PenTilt::default()),
+                    Capability::Rotation => inner.data.twist = Some(0.),
+                    Capability::Distance => inner.data.distance = Some(0.),
+                    _ => (),
+                }
+            },
+            zwp_tablet_tool_v2::Event::ProximityIn { serial, tablet, surface } => {
+                inner.device_id = Some(wayland::make_did(&tablet));
+                inner.frame.proximity_in = Some((surface, serial));
+            },
+            zwp_tablet_tool_v2::Event::ProximityOut => {
+                inner.frame.proximity_out = true;
+            },
+            zwp_tablet_tool_v2::Event::Down { .. } => {
+                inner.frame.buttons.push((PenButton::Contact, ElementState::Pressed));
+            },
+            zwp_tablet_tool_v2::Event::Up => {
+                inner.frame.buttons.push((PenButton::Contact, ElementState::Released));
+            },
+            zwp_tablet_tool_v2::Event::Motion { x, y } => {
+                inner.position = LogicalPosition::new(x, y);
+                inner.frame.moved = true;
+            },
+            zwp_tablet_tool_v2::Event::Pressure { pressure } => {
+                inner.data.force = Some(Force::Normalized(pressure as f64 / AXIS_MAX));
+                inner.frame.moved = true;
+            },
+            zwp_tablet_tool_v2::Event::Distance { distance } => {
+                inner.data.distance = Some(distance as f64 / AXIS_MAX);
+                inner.frame.moved = true;
+            },
+            zwp_tablet_tool_v2::Event::Tilt { tilt_x, tilt_y } => {
+                inner.data.tilt = Some(PenTilt { x: tilt_x, y: tilt_y });
+                inner.frame.moved = true;
+            },
+            zwp_tablet_tool_v2::Event::Rotation { degrees } => {
+                inner.data.twist = Some(degrees);
+                inner.frame.moved = true;
+            },
+            zwp_tablet_tool_v2::Event::Button { button, state: WEnum::Value(state), .. } => {
+                let state = match state {
+                    ButtonState::Pressed => ElementState::Pressed,
+                    _ => ElementState::Released,
+                };
+                inner.frame.buttons.push((wayland_pen_button_to_winit(button), state));
+            },
+            zwp_tablet_tool_v2::Event::Frame { .. } => {
+                drop(inner);
+                state.tablet_tool_frame(proxy, data, qhandle);
+            },
+            zwp_tablet_tool_v2::Event::Removed => {
+                // A tool removed while in proximity of a window leaves it.
+                let cursor = std::mem::take(&mut inner.cursor);
+                if inner.window_id.is_some() {
+                    inner.frame = TabletToolFrame { proximity_out: true, ..Default::default() };
+                    drop(inner);
+                    state.tablet_tool_frame(proxy, data, qhandle);
+                }
+
+                if let Some(shape_device) = cursor.shape_device {
+                    shape_device.destroy();
+                }
+                if let Some(surface) = cursor.surface {
+                    surface.destroy();
+                }
+                proxy.destroy();
+            },
+            _ => (),
+        }
+    }
+}
+
+fn wayland_pen_button_to_winit(button: u32) -> PenButton {
+    // These values are coming from <linux/input-event-codes.h>.
+    const BTN_STYLUS: u32 = 0x14b;
+    const BTN_STYLUS2: u32 = 0x14c;
+
+    match button {
+        BTN_STYLUS => PenButton::Barrel,
+        BTN_STYLUS2 => PenButton::SecondaryBarrel,
+        button => PenButton::Other(button as u16),
+    }
+}
+
+delegate_dispatch!(WinitState: [ZwpTabletManagerV2: GlobalData] => TabletManagerState);
+delegate_dispatch!(WinitState: [ZwpTabletSeatV2: GlobalData] => TabletManagerState);
+delegate_dispatch!(WinitState: [ZwpTabletV2: TabletData] => TabletManagerState);
+delegate_dispatch!(WinitState: [ZwpTabletToolV2: TabletToolData] => TabletManagerState);
@@ -0,0 +1,298 @@
+//! Tablet pads, with their buttons, rings and strips.
+
+use std::sync::Mutex;
+
+use sctk::reexports::client::{
+    delegate_dispatch, event_created_child, Connection, Dispatch, Proxy, QueueHandle, WEnum,
+};
+use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_pad_group_v2::{
+    self, ZwpTabletPadGroupV2,
+};
+use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_pad_ring_v2::{
+    self, ZwpTabletPadRingV2,
+};
+use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_pad_strip_v2::{
+    self, ZwpTabletPadStripV2,
+};
+use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_pad_v2::{
+    self, ButtonState, ZwpTabletPadV2,
+};
+
+use super::{TabletManagerState, AXIS_MAX};
+use crate::event::{DeviceEvent, DeviceKind, ElementState};
+use crate::platform_impl::wayland::make_did;
+use crate::platform_impl::wayland::state::WinitState;
+
+/// The data of a tablet pad.
+#[derive(Debug, Default)]
+pub struct TabletPadData {
+    inner: Mutex<TabletPadInner>,
+}
+
+#[derive(Debug, Default)]
+struct TabletPadInner {
+    /// The mode groups of the pad, in the order they were announced.
+    groups: Vec<ZwpTabletPadGroupV2>,
+
+    /// The number of rings announced so far, used to index them.
+    rings: u32,
+
+    /// The number of strips announced so far, used to index them.
+    strips: u32,
+}
+
+/// The data of a mode group of a tablet pad.
+#[derive(Debug, Default)]
+pub struct TabletPadGroupData {
+    inner: Mutex<TabletPadGroupInner>,
+}
+
+#[derive(Debug, Default)]
+struct TabletPadGroupInner {
+    /// The pad of the group, along with the index of the group on it.
+    pad: Option<(ZwpTabletPadV2, u32)>,
+
+    /// The buttons of the pad which belong to the group.
+    buttons: Vec<u32>,
+
+    /// The current mode of the group.
+    mode: u32,
+
+    rings: Vec<ZwpTabletPadRingV2>,
+
+    strips: Vec<ZwpTabletPadStripV2>,
+}
+
+impl TabletPadGroupData {
+    /// Index of the group along with its current mode.
+    fn group_mode(&self) -> Option<(ZwpTabletPadV2, u32, u32)> {
+        let inner = self.inner.lock().unwrap();
+        let (pad, group) = inner.pad.clone()?;
+        Some((pad, group, inner.mode))
+    }
+}
+
+/// The data of a ring or a strip of a tablet pad.
+#[derive(Debug, Default)]
+pub struct TabletPadControlData {
+    inner: Mutex<TabletPadControlInner>,
+}
+
+#[derive(Debug, Default)]
+struct TabletPadControlInner {
+    /// The group of the control, along with the index of the control on the pad.
+    group: Option<(ZwpTabletPadGroupV2, u32)>,
+
+    /// The value of the control, `None` once the finger left it.
+    value: Option<f64>,
+
+    /// Whether the value changed since the latest `frame` event.
+    changed: bool,
+}
+
+impl TabletPadControlData {
+    /// Take the value of the control if it changed, along with the pad, the index of the control,
+    /// the group and its mode.
+    #[allow(clippy::type_complexity)]
+    fn take_change(&self) -> Option<(ZwpTabletPadV2, u32, u32, u32, Option<f64>)> {
+        let mut inner = self.inner.lock().unwrap();
+        if !std::mem::take(&mut inner.changed) {
+            return None;
+        }
+
+        let (group, index) = inner.group.as_ref()?;
+        let (pad, group, mode) = group.data::<TabletPadGroupData>()?.group_mode()?;
+        Some((pad, *index, group, mode, inner.value))
+    }
+
+    fn set_value(&self, value: Option<f64>) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.value = value;
+        inner.changed = true;
+    }
+}
+
+impl Dispatch<ZwpTabletPadV2, TabletPadData, WinitState> for TabletManagerState {
+    event_created_child!(WinitState, ZwpTabletPadV2, [
+        zwp_tablet_pad_v2::EVT_GROUP_OPCODE => (ZwpTabletPadGroupV2, TabletPadGroupData::default()),
+    ]);
+
+    fn event(
+        state: &mut WinitState,
+        proxy: &ZwpTabletPadV2,
+        event: <ZwpTabletPadV2 as Proxy>::Event,
+        data: &TabletPadData,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<WinitState>,
+    ) {
+        let mut inner = data.inner.lock().unwrap();
+        match event {
+            zwp_tablet_pad_v2::Event::Group { pad_group } => {
+                if let Some(group_data) = pad_group.data::<TabletPadGroupData>() {
+                    let index = inner.groups.len() as u32;
+                    group_data.inner.lock().unwrap().pad = Some((proxy.clone(), index));
+                }
+                inner.groups.push(pad_group);
+            },
+            zwp_tablet_pad_v2::Event::Button {
+                button, state: WEnum::Value(button_state), ..
+            } => {
+                let button_state = match button_state {
+                    ButtonState::Pressed => ElementState::Pressed,
+                    _ => ElementState::Released,
+                };
+
+                // Buttons which aren't part of a group are reported in the first one.
+                let (group, mode) = inner
+                    .groups
+                    .iter()
+                    .filter_map(|group| group.data::<TabletPadGroupData>())
+                    .map(|group| group.inner.lock().unwrap())
+                    .enumerate()
+                    .find(|(_, group)| group.buttons.contains(&button))
+                    .map(|(index, group)| (index as u32, group.mode))
+                    .unwrap_or_default();
+
+                state.events_sink.push_device_event(
+                    DeviceEvent::PadButton { button, group, mode, state: button_state },
+                    Some(make_did(proxy)),
+                );
+            },
+            zwp_tablet_pad_v2::Event::Done => {
+                drop(inner);
+                state.add_device(make_did(proxy), DeviceKind::Tablet, None, None);
+            },
+            zwp_tablet_pad_v2::Event::Removed => {
+                state.remove_device(make_did(proxy));
+                for group in inner.groups.drain(..) {
+                    if let Some(group_data) = group.data::<TabletPadGroupData>() {
+                        let mut group_inner = group_data.inner.lock().unwrap();
+                        group_inner.rings.drain(..).for_each(|ring| ring.destroy());
+                        group_inner.strips.drain(..).for_each(|strip| strip.destroy());
+                    }
+                    group.destroy();
+                }
+                proxy.destroy();
+            },
+            _ => (),
+        }
+    }
+}
+
+impl Dispatch<ZwpTabletPadGroupV2, TabletPadGroupData, WinitState> for TabletManagerState {
+    event_created_child!(WinitState, ZwpTabletPadGroupV2, [
+        zwp_tablet_pad_group_v2::EVT_RING_OPCODE => (ZwpTabletPadRingV2, TabletPadControlData::default()),
+        zwp_tablet_pad_group_v2::EVT_STRIP_OPCODE => (ZwpTabletPadStripV2, TabletPadControlData::default()),
+    ]);
+
+    fn event(
+        state: &mut WinitState,
+        proxy: &ZwpTabletPadGroupV2,
+        event: <ZwpTabletPadGroupV2 as Proxy>::Event,
+        data: &TabletPadGroupData,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<WinitState>,
+    ) {
+        let mut inner = data.inner.lock().unwrap();
+        match event {
+            zwp_tablet_pad_group_v2::Event::Buttons { buttons } => {
+                inner.buttons = buttons
+                    .chunks_exact(4)
+                    .map(|button| u32::from_ne_bytes(button.try_into().unwrap()))
+                    .collect();
+            },
+            zwp_tablet_pad_group_v2::Event::Ring { ring } => {
+                let index = inner.pad.as_ref().and_then(|(pad, _)| {
+                    let mut pad_inner = pad.data::<TabletPadData>()?.inner.lock().unwrap();
+                    pad_inner.rings += 1;
+                    Some(pad_inner.rings - 1)
+                });
+                if let Some(ring_data) = ring.data::<TabletPadControlData>() {
+                    ring_data.inner.lock().unwrap().group =
+                        Some((proxy.clone(), index.unwrap_or(0)));
+                }
+                inner.rings.push(ring);
+            },
+            zwp_tablet_pad_group_v2::Event::Strip { strip } => {
+                let index = inner.pad.as_ref().and_then(|(pad, _)| {
+                    let mut pad_inner = pad.data::<TabletPadData>()?.inner.lock().unwrap();
+                    pad_inner.strips += 1;
+                    Some(pad_inner.strips - 1)
+                });
+                if let Some(strip_data) = strip.data::<TabletPadControlData>() {
+                    strip_data.inner.lock().unwrap().group =
+                        Some((proxy.clone(), index.unwrap_or(0)));
+                }
+                inner.strips.push(strip);
+            },
+            zwp_tablet_pad_group_v2::Event::ModeSwitch { mode, .. } => {
+                inner.mode = mode;
+                if let Some((pad, group)) = inner.pad.clone() {
+                    drop(inner);
+                    state.events_sink.push_device_event(
+                        DeviceEvent::PadModeChanged { group, mode },
+                        Some(make_did(&pad)),
+                    );
+                }
+            },
+            _ => (),
+        }
+    }
+}
+
+impl Dispatch<ZwpTabletPadRingV2, TabletPadControlData, WinitState> for TabletManagerState {
+    fn event(
+        state: &mut WinitState,
+        _proxy: &ZwpTabletPadRingV2,
+        event: <ZwpTabletPadRingV2 as Proxy>::Event,
+        data: &TabletPadControlData,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<WinitState>,
+    ) {
+        match event {
+            zwp_tablet_pad_ring_v2::Event::Angle { degrees } => data.set_value(Some(degrees)),
+            zwp_tablet_pad_ring_v2::Event::Stop => data.set_value(None),
+            zwp_tablet_pad_ring_v2::Event::Frame { .. } => {
+                if let Some((pad, ring, group, mode, angle)) = data.take_change() {
+                    state.events_sink.push_device_event(
+                        DeviceEvent::PadRing { ring, group, mode, angle },
+                        Some(make_did(&pad)),
+                    );
+                }
+            },
+            _ => (),
+        }
+    }
+}
+
+impl Dispatch<ZwpTabletPadStripV2, TabletPadControlData, WinitState> for TabletManagerState {
+    fn event(
+        state: &mut WinitState,
+        _proxy: &ZwpTabletPadStripV2,
+        event: <ZwpTabletPadStripV2 as Proxy>::Event,
+        data: &TabletPadControlData,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<WinitState>,
+    ) {
+        match event {
+            zwp_tablet_pad_strip_v2::Event::Position { position } => {
+                data.set_value(Some(position as f64 / AXIS_MAX))
+            },
+            zwp_tablet_pad_strip_v2::Event::Stop => data.set_value(None),
+            zwp_tablet_pad_strip_v2::Event::Frame { .. } => {
+                if let Some((pad, strip, group, mode, position)) = data.take_change() {
+                    state.events_sink.push_device_event(
+                        DeviceEvent::PadStrip { strip, group, mode, position },
+                        Some(make_did(&pad)),
+                    );
+                }
+            },
+            _ => (),
+        }
+    }
+}
+
+delegate_dispatch!(WinitState: [ZwpTabletPadV2: TabletPadData] => TabletManagerState);
+delegate_dispatch!(WinitState: [ZwpTabletPadGroupV2: TabletPadGroupData] => TabletManagerState);
+delegate_dispatch!(WinitState: [ZwpTabletPadRingV2: TabletPadControlData] => TabletManagerState);
+delegate_dispatch!(WinitState: [ZwpTabletPadStripV2: TabletPadControlData] => TabletManagerState);
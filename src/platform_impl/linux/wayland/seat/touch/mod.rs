@@ -52,7 +52,7 @@ impl TouchHandler for WinitState {
 
         self.events_sink.push_window_event(
             WindowEvent::PointerEntered {
-                device_id: None,
+                device_id: Some(wayland::make_did(touch)),
                 primary,
                 position,
                 kind: PointerKind::Touch(finger_id),
@@ -61,7 +61,7 @@ impl TouchHandler for WinitState {
         );
         self.events_sink.push_window_event(
             WindowEvent::PointerButton {
-                device_id: None,
+                device_id: Some(wayland::make_did(touch)),
                 primary,
                 state: ElementState::Pressed,
                 position,
@@ -114,7 +114,7 @@ impl TouchHandler for WinitState {
 
         self.events_sink.push_window_event(
             WindowEvent::PointerButton {
-                device_id: None,
+                device_id: Some(wayland::make_did(touch)),
                 primary,
                 state: ElementState::Released,
                 position,
@@ -124,7 +124,7 @@ impl TouchHandler for WinitState {
         );
         self.events_sink.push_window_event(
             WindowEvent::PointerLeft {
-                device_id: None,
+                device_id: Some(wayland::make_did(touch)),
                 primary,
                 position: Some(position),
                 kind: PointerKind::Touch(finger_id),
@@ -168,7 +168,7 @@ impl TouchHandler for WinitState {
 
         self.events_sink.push_window_event(
             WindowEvent::PointerMoved {
-                device_id: None,
+                device_id: Some(wayland::make_did(touch)),
                 primary,
                 position: touch_point.location.to_physical(scale_factor),
                 source: PointerSource::Touch {
@@ -201,7 +201,7 @@ impl TouchHandler for WinitState {
 
             self.events_sink.push_window_event(
                 WindowEvent::PointerLeft {
-                    device_id: None,
+                    device_id: Some(wayland::make_did(touch)),
                     primary,
                     position: Some(position),
                     kind: PointerKind::Touch(FingerId::from_raw(id as usize)),
@@ -4,7 +4,11 @@ use std::sync::{Arc, Mutex};
 
 use ahash::AHashMap;
 use sctk::compositor::{CompositorHandler, CompositorState};
+use sctk::data_device_manager::data_source::CopyPasteSource;
+use sctk::data_device_manager::DataDeviceManagerState;
 use sctk::output::{OutputHandler, OutputState};
+use sctk::primary_selection::selection::PrimarySelectionSource;
+use sctk::primary_selection::PrimarySelectionManagerState;
 use sctk::reexports::calloop::LoopHandle;
 use sctk::reexports::client::backend::ObjectId;
 use sctk::reexports::client::globals::GlobalList;
@@ -12,8 +16,8 @@ use sctk::reexports::client::protocol::wl_output::WlOutput;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{Connection, Proxy, QueueHandle};
 use sctk::registry::{ProvidesRegistryState, RegistryState};
-use sctk::seat::pointer::ThemedPointer;
-use sctk::seat::SeatState;
+use sctk::shell::wlr_layer::{LayerShell, LayerShellHandler, LayerSurface, LayerSurfaceConfigure};
+use sctk::shell::xdg::popup::{Popup, PopupConfigure, PopupHandler};
 use sctk::shell::xdg::window::{Window, WindowConfigure, WindowHandler};
 use sctk::shell::xdg::XdgShell;
 use sctk::shell::WaylandSurface;
@@ -22,16 +26,20 @@ use sctk::shm::{Shm, ShmHandler};
 use sctk::subcompositor::SubcompositorState;
 
 use crate::error::OsError;
+use crate::event::{DeviceId, DeviceInfo, WindowEvent};
+use crate::keyboard::KeyRepeatInfo;
 use crate::platform_impl::wayland::event_loop::sink::EventSink;
 use crate::platform_impl::wayland::output::MonitorHandle;
 use crate::platform_impl::wayland::seat::{
-    PointerConstraintsState, RelativePointerState, TextInputState, WinitPointerData,
+    ActiveDrag, ActiveDrop, PointerConstraintsState, PointerGesturesState, RelativePointerState,
+    SeatState, SelectionSource, TabletManagerState, TextInputState, ThemedPointer,
     WinitPointerDataExt, WinitSeatState,
 };
 use crate::platform_impl::wayland::types::kwin_blur::KWinBlurManager;
 use crate::platform_impl::wayland::types::wp_fractional_scaling::FractionalScalingManager;
 use crate::platform_impl::wayland::types::wp_viewporter::ViewporterState;
 use crate::platform_impl::wayland::types::xdg_activation::XdgActivationState;
+use crate::platform_impl::wayland::types::xdg_dialog::XdgDialogManager;
 use crate::platform_impl::wayland::window::{WindowRequests, WindowState};
 use crate::platform_impl::wayland::WindowId;
 
@@ -76,12 +84,36 @@ pub struct WinitState {
     /// Currently handled seats.
     pub seats: AHashMap<ObjectId, WinitSeatState>,
 
+    /// The connected input devices.
+    pub devices: AHashMap<DeviceId, DeviceInfo>,
+
+    /// The key repeat of the latest keyboard which reported it.
+    pub key_repeat_info: Option<KeyRepeatInfo>,
+
     /// Currently present cursor surfaces.
-    pub pointer_surfaces: AHashMap<ObjectId, Arc<ThemedPointer<WinitPointerData>>>,
+    pub pointer_surfaces: AHashMap<ObjectId, Arc<ThemedPointer>>,
 
     /// The state of the text input on the client.
     pub text_input_state: Option<TextInputState>,
 
+    /// The data device manager used for the clipboard.
+    pub data_device_manager_state: Option<DataDeviceManagerState>,
+
+    /// The clipboard contents we're currently offering.
+    pub clipboard_source: Option<SelectionSource<CopyPasteSource>>,
+
+    /// The drag started from one of our windows.
+    pub drag_source: Option<ActiveDrag>,
+
+    /// The drag over one of our windows.
+    pub drop_target: Option<ActiveDrop>,
+
+    /// The primary selection manager.
+    pub primary_selection_manager_state: Option<PrimarySelectionManagerState>,
+
+    /// The primary selection contents we're currently offering.
+    pub primary_selection_source: Option<SelectionSource<PrimarySelectionSource>>,
+
     /// Observed monitors.
     pub monitors: Arc<Mutex<Vec<MonitorHandle>>>,
 
@@ -95,6 +127,12 @@ pub struct WinitState {
     /// Relative pointer.
     pub relative_pointer: Option<RelativePointerState>,
 
+    /// Pointer gestures, such as pinching on touchpads.
+    pub pointer_gestures: Option<PointerGesturesState>,
+
+    /// The tablet manager, for pens and other tablet tools.
+    pub tablet_manager: Option<TabletManagerState>,
+
     /// Pointer constraints to handle pointer locking and confining.
     pub pointer_constraints: Option<Arc<PointerConstraintsState>>,
 
@@ -107,6 +145,12 @@ pub struct WinitState {
     /// KWin blur manager.
     pub kwin_blur_manager: Option<KWinBlurManager>,
 
+    /// Xdg dialog manager.
+    pub xdg_dialog_manager: Option<XdgDialogManager>,
+
+    /// The layer shell for the surfaces of the desktop shell.
+    pub layer_shell: Option<LayerShell>,
+
     /// Loop handle to re-register event sources, such as keyboard repeat.
     pub loop_handle: LoopHandle<'static, Self>,
 
@@ -142,7 +186,7 @@ impl WinitState {
         let output_state = OutputState::new(globals, queue_handle);
         let monitors = output_state.outputs().map(MonitorHandle::new).collect();
 
-        let seat_state = SeatState::new(globals, queue_handle);
+        let seat_state = SeatState::new(globals, &registry_state, queue_handle);
 
         let mut seats = AHashMap::default();
         for seat in seat_state.seats() {
@@ -178,11 +222,27 @@ impl WinitState {
             viewporter_state,
             fractional_scaling_manager,
             kwin_blur_manager: KWinBlurManager::new(globals, queue_handle).ok(),
+            xdg_dialog_manager: XdgDialogManager::new(globals, queue_handle).ok(),
+            layer_shell: LayerShell::bind(globals, queue_handle).ok(),
 
             seats,
+            devices: Default::default(),
+            key_repeat_info: None,
             text_input_state: TextInputState::new(globals, queue_handle).ok(),
+            data_device_manager_state: DataDeviceManagerState::bind(globals, queue_handle).ok(),
+            clipboard_source: None,
+            drag_source: None,
+            drop_target: None,
+            primary_selection_manager_state: PrimarySelectionManagerState::bind(
+                globals,
+                queue_handle,
+            )
+            .ok(),
+            primary_selection_source: None,
 
             relative_pointer: RelativePointerState::new(globals, queue_handle).ok(),
+            pointer_gestures: PointerGesturesState::new(globals, queue_handle).ok(),
+            tablet_manager: TabletManagerState::new(globals, queue_handle).ok(),
             pointer_constraints: PointerConstraintsState::new(globals, queue_handle)
                 .map(Arc::new)
                 .ok(),
@@ -275,17 +335,8 @@ impl WindowHandler for WinitState {
     ) {
         let window_id = super::make_wid(window.wl_surface());
 
-        let pos = if let Some(pos) =
-            self.window_compositor_updates.iter().position(|update| update.window_id == window_id)
-        {
-            pos
-        } else {
-            self.window_compositor_updates.push(WindowCompositorUpdate::new(window_id));
-            self.window_compositor_updates.len() - 1
-        };
-
         // Populate the configure to the window.
-        self.window_compositor_updates[pos].resized |= self
+        let resized = self
             .windows
             .get_mut()
             .get_mut(&window_id)
@@ -294,6 +345,80 @@ impl WindowHandler for WinitState {
             .unwrap()
             .configure(configure, &self.shm, &self.subcompositor_state);
 
+        self.window_configured(window_id, resized);
+    }
+}
+
+impl PopupHandler for WinitState {
+    fn configure(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        popup: &Popup,
+        configure: PopupConfigure,
+    ) {
+        let window_id = super::make_wid(popup.wl_surface());
+
+        // The popup could have been dropped already.
+        let Some(window) = self.windows.get_mut().get_mut(&window_id) else {
+            return;
+        };
+        let resized = window.lock().unwrap().configure_popup(configure);
+
+        self.window_configured(window_id, resized);
+    }
+
+    fn done(&mut self, _: &Connection, _: &QueueHandle<Self>, popup: &Popup) {
+        let window_id = super::make_wid(popup.wl_surface());
+        if let Some(window) = self.windows.get_mut().get(&window_id) {
+            window.lock().unwrap().surface_closed();
+        }
+        self.events_sink.push_window_event(WindowEvent::PopupDismissed, window_id);
+    }
+}
+
+impl LayerShellHandler for WinitState {
+    fn closed(&mut self, _: &Connection, _: &QueueHandle<Self>, layer: &LayerSurface) {
+        let window_id = super::make_wid(layer.wl_surface());
+        if let Some(window) = self.windows.get_mut().get(&window_id) {
+            window.lock().unwrap().surface_closed();
+        }
+        Self::queue_close(&mut self.window_compositor_updates, window_id);
+    }
+
+    fn configure(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        layer: &LayerSurface,
+        configure: LayerSurfaceConfigure,
+        _serial: u32,
+    ) {
+        let window_id = super::make_wid(layer.wl_surface());
+
+        // The layer surface could have been dropped already.
+        let Some(window) = self.windows.get_mut().get_mut(&window_id) else {
+            return;
+        };
+        let resized = window.lock().unwrap().configure_layer(configure);
+
+        self.window_configured(window_id, resized);
+    }
+}
+
+impl WinitState {
+    /// Handle the configure of a window, once it was applied.
+    pub fn window_configured(&mut self, window_id: WindowId, resized: bool) {
+        let pos = if let Some(pos) =
+            self.window_compositor_updates.iter().position(|update| update.window_id == window_id)
+        {
+            pos
+        } else {
+            self.window_compositor_updates.push(WindowCompositorUpdate::new(window_id));
+            self.window_compositor_updates.len() - 1
+        };
+        self.window_compositor_updates[pos].resized |= resized;
+
         // NOTE: configure demands wl_surface::commit, however winit doesn't commit on behalf of the
         // users, since it can break a lot of things, thus it'll ask users to redraw instead.
         self.window_requests
@@ -435,3 +560,5 @@ sctk::delegate_registry!(WinitState);
 sctk::delegate_shm!(WinitState);
 sctk::delegate_xdg_shell!(WinitState);
 sctk::delegate_xdg_window!(WinitState);
+sctk::delegate_xdg_popup!(WinitState);
+sctk::delegate_layer!(WinitState);
@@ -3,40 +3,59 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
-use sctk::compositor::{CompositorState, Region, SurfaceData};
+use sctk::compositor::SurfaceData;
+use sctk::globals::ProvidesBoundGlobal;
 use sctk::reexports::client::protocol::wl_display::WlDisplay;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{Proxy, QueueHandle};
 use sctk::reexports::protocols::xdg::activation::v1::client::xdg_activation_v1::XdgActivationV1;
-use sctk::shell::xdg::window::{Window as SctkWindow, WindowDecorations};
+use sctk::reexports::protocols_wlr::layer_shell::v1::client::zwlr_layer_shell_v1::ZwlrLayerShellV1;
+use sctk::shell::wlr_layer::{
+    Anchor as SctkLayerAnchor, KeyboardInteractivity as SctkKeyboardInteractivity,
+    Layer as SctkLayer,
+};
+use sctk::shell::xdg::popup::Popup;
+use sctk::shell::xdg::window::WindowDecorations;
+use sctk::shell::xdg::XdgPositioner;
 use sctk::shell::WaylandSurface;
 use tracing::warn;
 
 use super::event_loop::sink::EventSink;
 use super::output::MonitorHandle;
+use super::seat::{ClipboardRequest, DragRequest, DropRequest, Selection};
 use super::state::WinitState;
 use super::types::xdg_activation::XdgActivationTokenData;
 use super::ActiveEventLoop;
-use crate::dpi::{LogicalSize, PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size};
+use crate::clipboard::ClipboardData;
+use crate::dnd::{DragActions, DragData};
+use crate::dpi::{
+    LogicalPosition, LogicalSize, PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size,
+};
 use crate::error::{NotSupportedError, RequestError};
-use crate::event::{Ime, WindowEvent};
+use crate::event::{DeviceId, Ime, MouseButton, WindowEvent};
 use crate::event_loop::AsyncRequestSerial;
+use crate::keyboard::PhysicalKey;
 use crate::monitor::MonitorHandle as CoreMonitorHandle;
-use crate::platform_impl::{Fullscreen, MonitorHandle as PlatformMonitorHandle};
+use crate::platform::wayland::{KeyboardInteractivity, Layer, LayerAnchor, LayerShellAttributes};
+use crate::platform_impl::{
+    Fullscreen, MonitorHandle as PlatformMonitorHandle, PlatformCustomCursor,
+};
 use crate::window::{
-    Cursor, CursorGrabMode, Fullscreen as CoreFullscreen, ImePurpose, ResizeDirection, Theme,
-    UserAttentionType, Window as CoreWindow, WindowAttributes, WindowButtons, WindowId,
-    WindowLevel,
+    Cursor, CursorGrabMode, CustomCursor as RootCustomCursor, Fullscreen as CoreFullscreen,
+    HitTestZone, ImePurpose, PopupAttributes, Rect, ResizeDirection, Theme, UserAttentionType,
+    Window as CoreWindow, WindowAttributes, WindowButtons, WindowId, WindowLevel,
 };
 
+pub(crate) mod role;
 pub(crate) mod state;
 
+use role::{Subsurface, WindowRole};
 pub use state::WindowState;
 
 /// The Wayland window.
 pub struct Window {
-    /// Reference to the underlying SCTK window.
-    window: SctkWindow,
+    /// Reference to the underlying SCTK window, or popup.
+    window: WindowRole,
 
     /// Window id.
     window_id: WindowId,
@@ -44,9 +63,6 @@ pub struct Window {
     /// The state of the window.
     window_state: Arc<Mutex<WindowState>>,
 
-    /// Compositor to handle WlRegion stuff.
-    compositor: Arc<CompositorState>,
-
     /// The wayland display used solely for raw window handle.
     #[allow(dead_code)]
     display: WlDisplay,
@@ -71,6 +87,15 @@ pub struct Window {
 
     /// The event sink to deliver synthetic events.
     window_events_sink: Arc<Mutex<EventSink>>,
+
+    /// The sender of clipboard requests to the event loop, if the clipboard is available.
+    clipboard_sender: Option<calloop::channel::Sender<ClipboardRequest>>,
+
+    /// The sender of primary selection requests, if the primary selection is available.
+    primary_selection_sender: Option<calloop::channel::Sender<ClipboardRequest>>,
+
+    /// The sender of drags to the event loop, if the data device is available.
+    drag_sender: Option<calloop::channel::Sender<DragRequest>>,
 }
 
 impl Window {
@@ -83,8 +108,37 @@ impl Window {
 
         let monitors = state.monitors.clone();
 
-        let surface = state.compositor_state.create_surface(&queue_handle);
-        let compositor = state.compositor_state.clone();
+        let layer_shell = attributes.platform_specific.layer_shell.as_ref();
+        if layer_shell.is_some()
+            && (attributes.parent_window.is_some() || attributes.popup.is_some())
+        {
+            return Err(
+                NotSupportedError::new("layer surfaces can't be child windows or popups").into()
+            );
+        }
+
+        // Child windows are subsurfaces of their parent.
+        let parent = match attributes.parent_window.as_ref().map(|handle| &handle.0) {
+            Some(rwh_06::RawWindowHandle::Wayland(handle)) => {
+                Some(find_parent_surface(&mut state, handle)?)
+            },
+            Some(_) => {
+                return Err(NotSupportedError::new("the parent isn't a Wayland window").into())
+            },
+            None => None,
+        };
+        let (surface, subsurface) = match parent.as_ref() {
+            Some((parent_surface, _)) => {
+                let subcompositor = state
+                    .subcompositor_state
+                    .as_ref()
+                    .ok_or_else(|| NotSupportedError::new("wl_subcompositor is not available"))?;
+                let (subsurface, surface) =
+                    subcompositor.create_subsurface(parent_surface.clone(), &queue_handle);
+                (surface, Some(subsurface))
+            },
+            None => (state.compositor_state.create_surface(&queue_handle), None),
+        };
         let xdg_activation =
             state.xdg_activation.as_ref().map(|activation_state| activation_state.global().clone());
         let display = event_loop_window_target.handle.connection.display();
@@ -99,8 +153,28 @@ impl Window {
             WindowDecorations::RequestClient
         };
 
-        let window =
-            state.xdg_shell.create_window(surface.clone(), default_decorations, &queue_handle);
+        let window = match (subsurface, attributes.popup.as_ref(), layer_shell) {
+            (Some(subsurface), ..) => {
+                let subsurface = Subsurface::new(subsurface, surface.clone());
+                if let (Some(position), Some((_, scale_factor))) =
+                    (attributes.position, parent.as_ref())
+                {
+                    subsurface.set_position(position.to_logical(*scale_factor));
+                }
+                WindowRole::Subsurface(Arc::new(subsurface))
+            },
+            (None, Some(popup), _) => {
+                create_popup(&mut state, &queue_handle, surface.clone(), popup, size)?
+            },
+            (None, None, Some(layer_shell)) => {
+                create_layer_surface(&state, &queue_handle, surface.clone(), layer_shell, size)?
+            },
+            (None, None, None) => WindowRole::Toplevel(state.xdg_shell.create_window(
+                surface.clone(),
+                default_decorations,
+                &queue_handle,
+            )),
+        };
 
         let mut window_state = WindowState::new(
             event_loop_window_target.handle.clone(),
@@ -111,6 +185,11 @@ impl Window {
             attributes.preferred_theme,
         );
 
+        // Child windows aren't configured by the compositor.
+        if window.subsurface().is_some() {
+            window_state.configure_subsurface();
+        }
+
         // Set transparency hint.
         window_state.set_transparent(attributes.transparent);
 
@@ -120,8 +199,10 @@ impl Window {
         window_state.set_decorate(attributes.decorations);
 
         // Set the app_id.
-        if let Some(name) = attributes.platform_specific.name.map(|name| name.general) {
-            window.set_app_id(name);
+        if let Some((name, toplevel)) =
+            attributes.platform_specific.name.map(|name| name.general).zip(window.toplevel())
+        {
+            toplevel.set_app_id(name);
         }
 
         // Set the window title.
@@ -137,24 +218,45 @@ impl Window {
         // Non-resizable implies that the min and max sizes are set to the same value.
         window_state.set_resizable(attributes.resizable);
 
-        // Set startup mode.
-        match attributes.fullscreen.map(Into::into) {
-            Some(Fullscreen::Exclusive(_)) => {
-                warn!("`Fullscreen::Exclusive` is ignored on Wayland");
-            },
-            #[cfg_attr(not(x11_platform), allow(clippy::bind_instead_of_map))]
-            Some(Fullscreen::Borderless(monitor)) => {
-                let output = monitor.and_then(|monitor| match monitor {
-                    PlatformMonitorHandle::Wayland(monitor) => Some(monitor.proxy),
-                    #[cfg(x11_platform)]
-                    PlatformMonitorHandle::X(_) => None,
-                });
+        // Set startup mode, popups don't have any.
+        if let Some(toplevel) = window.toplevel() {
+            match attributes.fullscreen.map(Into::into) {
+                Some(Fullscreen::Exclusive(_)) => {
+                    warn!("`Fullscreen::Exclusive` is ignored on Wayland");
+                },
+                #[cfg_attr(not(x11_platform), allow(clippy::bind_instead_of_map))]
+                Some(Fullscreen::Borderless(monitor)) => {
+                    let output = monitor.and_then(|monitor| match monitor {
+                        PlatformMonitorHandle::Wayland(monitor) => Some(monitor.proxy),
+                        #[cfg(x11_platform)]
+                        PlatformMonitorHandle::X(_) => None,
+                    });
+
+                    toplevel.set_fullscreen(output.as_ref())
+                },
+                _ if attributes.maximized => toplevel.set_maximized(),
+                _ => (),
+            };
+
+            // Make the window a dialog of its parent.
+            if let Some(parent) = attributes.transient_for {
+                let parent = state
+                    .windows
+                    .get_mut()
+                    .get(&parent)
+                    .and_then(|parent| parent.lock().unwrap().window.toplevel().cloned());
+                match parent {
+                    Some(parent) => toplevel.set_parent(Some(&parent)),
+                    None => warn!("Ignoring the parent of the dialog, which isn't a toplevel"),
+                }
+            }
+        }
 
-                window.set_fullscreen(output.as_ref())
-            },
-            _ if attributes.maximized => window.set_maximized(),
-            _ => (),
-        };
+        if attributes.modal {
+            if let Err(err) = window_state.set_modal(true) {
+                warn!("Failed to make the dialog modal: {err}");
+            }
+        }
 
         match attributes.cursor {
             Cursor::Icon(icon) => window_state.set_cursor(icon),
@@ -179,13 +281,30 @@ impl Window {
         let window_requests = WindowRequests {
             redraw_requested: AtomicBool::new(true),
             closed: AtomicBool::new(false),
+            drop_requests: Mutex::new(Vec::new()),
         };
         let window_requests = Arc::new(window_requests);
         state.window_requests.get_mut().insert(window_id, window_requests.clone());
+        if window.subsurface().is_some() {
+            state.window_configured(window_id, true);
+        }
 
         // Setup the event sync to insert `WindowEvents` right from the window.
         let window_events_sink = state.window_events_sink.clone();
 
+        let clipboard_sender = state
+            .data_device_manager_state
+            .is_some()
+            .then(|| event_loop_window_target.clipboard_sender.clone());
+        let primary_selection_sender = state
+            .primary_selection_manager_state
+            .is_some()
+            .then(|| event_loop_window_target.clipboard_sender.clone());
+        let drag_sender = state
+            .data_device_manager_state
+            .is_some()
+            .then(|| event_loop_window_target.drag_sender.clone());
+
         let mut wayland_source = event_loop_window_target.wayland_dispatcher.as_source_mut();
         let event_queue = wayland_source.queue();
 
@@ -206,7 +325,6 @@ impl Window {
             display,
             monitors,
             window_id,
-            compositor,
             window_state,
             queue_handle,
             xdg_activation,
@@ -214,11 +332,46 @@ impl Window {
             event_loop_awakener,
             window_requests,
             window_events_sink,
+            clipboard_sender,
+            primary_selection_sender,
+            drag_sender,
         })
     }
 }
 
 impl Window {
+    /// The subsurface, for requests which only apply to child windows.
+    fn subsurface(&self) -> Result<&Subsurface, RequestError> {
+        self.window
+            .subsurface()
+            .ok_or_else(|| NotSupportedError::new("the window isn't a child window").into())
+    }
+
+    pub fn set_subsurface_sync(&self, sync: bool) -> Result<(), RequestError> {
+        self.subsurface()?.set_sync(sync);
+        Ok(())
+    }
+
+    pub fn place_subsurface(&self, sibling: &Window, above: bool) -> Result<(), RequestError> {
+        let subsurface = self.subsurface()?;
+
+        // The compositor only accepts the parent or another child of it.
+        let parent_surface = |surface: &WlSurface| {
+            surface.data::<SurfaceData>().and_then(|data| data.parent_surface()).cloned()
+        };
+        let parent = parent_surface(self.window.wl_surface());
+        let sibling_surface = sibling.window.wl_surface();
+        if parent.as_ref() != Some(sibling_surface) && parent_surface(sibling_surface) != parent {
+            return Err(NotSupportedError::new(
+                "the sibling isn't the parent or another child of the parent",
+            )
+            .into());
+        }
+
+        subsurface.place(sibling_surface, above);
+        Ok(())
+    }
+
     pub fn request_activation_token(&self) -> Result<AsyncRequestSerial, RequestError> {
         let xdg_activation = match self.xdg_activation.as_ref() {
             Some(xdg_activation) => xdg_activation,
@@ -235,6 +388,49 @@ impl Window {
         Ok(serial)
     }
 
+    fn send_clipboard_request(&self, request: ClipboardRequest) -> Result<(), RequestError> {
+        let (ClipboardRequest::Set { selection, .. } | ClipboardRequest::Read { selection, .. }) =
+            request;
+        let sender = match selection {
+            Selection::Clipboard => self
+                .clipboard_sender
+                .as_ref()
+                .ok_or_else(|| NotSupportedError::new("wl_data_device_manager is not available"))?,
+            Selection::Primary => self.primary_selection_sender.as_ref().ok_or_else(|| {
+                NotSupportedError::new("zwp_primary_selection_device_manager_v1 is not available")
+            })?,
+        };
+
+        sender.send(request).map_err(|_| RequestError::Ignored)
+    }
+
+    fn request_selection(
+        &self,
+        selection: Selection,
+        mime_types: &[&str],
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        let serial = AsyncRequestSerial::get();
+        let mime_types = mime_types.iter().map(|mime_type| mime_type.to_string()).collect();
+        self.send_clipboard_request(ClipboardRequest::Read {
+            selection,
+            window_id: self.window_id,
+            serial,
+            mime_types,
+        })?;
+        Ok(serial)
+    }
+
+    /// The requests are handled once the application processed the pending events.
+    fn send_drop_request(&self, request: DropRequest) -> Result<(), RequestError> {
+        if self.drag_sender.is_none() {
+            return Err(NotSupportedError::new("wl_data_device_manager is not available").into());
+        }
+
+        self.window_requests.drop_requests.lock().unwrap().push(request);
+        self.event_loop_awakener.ping();
+        Ok(())
+    }
+
     #[inline]
     pub fn surface(&self) -> &WlSurface {
         self.window.wl_surface()
@@ -295,6 +491,82 @@ impl CoreWindow for Window {
         self.window_state.lock().unwrap().title().to_owned()
     }
 
+    fn set_clipboard(&self, data: Vec<ClipboardData>) -> Result<(), RequestError> {
+        self.send_clipboard_request(ClipboardRequest::Set {
+            selection: Selection::Clipboard,
+            contents: data,
+        })
+    }
+
+    fn request_clipboard(&self, mime_types: &[&str]) -> Result<AsyncRequestSerial, RequestError> {
+        self.request_selection(Selection::Clipboard, mime_types)
+    }
+
+    fn set_primary_selection(&self, data: Vec<ClipboardData>) -> Result<(), RequestError> {
+        self.send_clipboard_request(ClipboardRequest::Set {
+            selection: Selection::Primary,
+            contents: data,
+        })
+    }
+
+    fn request_primary_selection(
+        &self,
+        mime_types: &[&str],
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        self.request_selection(Selection::Primary, mime_types)
+    }
+
+    fn start_drag(
+        &self,
+        data: DragData,
+        icon: Option<RootCustomCursor>,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        let drag_sender = self
+            .drag_sender
+            .as_ref()
+            .ok_or_else(|| NotSupportedError::new("wl_data_device_manager is not available"))?;
+
+        let icon = match icon {
+            Some(RootCustomCursor { inner: PlatformCustomCursor::Wayland(cursor) }) => {
+                Some(cursor.0)
+            },
+            #[cfg(x11_platform)]
+            Some(RootCustomCursor { inner: PlatformCustomCursor::X(_) }) => {
+                tracing::error!("passed a X11 cursor to Wayland backend");
+                None
+            },
+            None => None,
+        };
+
+        let serial = AsyncRequestSerial::get();
+        drag_sender
+            .send(DragRequest {
+                window_id: self.window_id,
+                serial,
+                surface: self.surface().clone(),
+                data,
+                icon,
+            })
+            .map_err(|_| RequestError::Ignored)?;
+        Ok(serial)
+    }
+
+    fn accept_drop(&self, mime_type: &str, action: DragActions) -> Result<(), RequestError> {
+        let mime_type = mime_type.to_owned();
+        self.send_drop_request(DropRequest::Accept { mime_type, action })
+    }
+
+    fn reject_drop(&self) -> Result<(), RequestError> {
+        self.send_drop_request(DropRequest::Reject)
+    }
+
+    fn request_drop_data(&self, mime_types: &[&str]) -> Result<AsyncRequestSerial, RequestError> {
+        let serial = AsyncRequestSerial::get();
+        let mime_types = mime_types.iter().map(|mime_type| mime_type.to_string()).collect();
+        self.send_drop_request(DropRequest::Read { serial, mime_types })?;
+        Ok(serial)
+    }
+
     fn pre_present_notify(&self) {
         self.window_state.lock().unwrap().request_frame_callback();
     }
@@ -308,12 +580,21 @@ impl CoreWindow for Window {
     }
 
     fn outer_position(&self) -> Result<PhysicalPosition<i32>, RequestError> {
-        Err(NotSupportedError::new("window position information is not available on Wayland")
-            .into())
+        // Child windows are placed relative to their parent.
+        match self.window.subsurface() {
+            Some(subsurface) => Ok(subsurface.position().to_physical(self.scale_factor())),
+            None => Err(NotSupportedError::new(
+                "window position information is not available on Wayland",
+            )
+            .into()),
+        }
     }
 
-    fn set_outer_position(&self, _position: Position) {
-        // Not possible.
+    fn set_outer_position(&self, position: Position) {
+        // Only possible for child windows.
+        if let Some(subsurface) = self.window.subsurface() {
+            subsurface.set_position(position.to_logical(self.scale_factor()));
+        }
     }
 
     fn surface_size(&self) -> PhysicalSize<u32> {
@@ -410,7 +691,9 @@ impl CoreWindow for Window {
             return;
         }
 
-        self.window.set_minimized();
+        if let Some(window) = self.window.toplevel() {
+            window.set_minimized();
+        }
     }
 
     fn is_minimized(&self) -> Option<bool> {
@@ -419,10 +702,14 @@ impl CoreWindow for Window {
     }
 
     fn set_maximized(&self, maximized: bool) {
+        let Some(window) = self.window.toplevel() else {
+            return;
+        };
+
         if maximized {
-            self.window.set_maximized()
+            window.set_maximized()
         } else {
-            self.window.unset_maximized()
+            window.unset_maximized()
         }
     }
 
@@ -437,6 +724,10 @@ impl CoreWindow for Window {
     }
 
     fn set_fullscreen(&self, fullscreen: Option<CoreFullscreen>) {
+        let Some(window) = self.window.toplevel() else {
+            return;
+        };
+
         match fullscreen {
             Some(CoreFullscreen::Exclusive(_)) => {
                 warn!("`Fullscreen::Exclusive` is ignored on Wayland");
@@ -449,9 +740,9 @@ impl CoreWindow for Window {
                     PlatformMonitorHandle::X(_) => None,
                 });
 
-                self.window.set_fullscreen(output.as_ref())
+                window.set_fullscreen(output.as_ref())
             },
-            None => self.window.unset_fullscreen(),
+            None => window.unset_fullscreen(),
         }
     }
 
@@ -495,6 +786,29 @@ impl CoreWindow for Window {
 
     fn set_window_level(&self, _level: WindowLevel) {}
 
+    fn set_transient_for(&self, parent: Option<&dyn CoreWindow>) -> Result<(), RequestError> {
+        let toplevel = self
+            .window
+            .toplevel()
+            .ok_or_else(|| NotSupportedError::new("the request only applies to toplevels"))?;
+        let parent = match parent {
+            Some(parent) => Some(
+                parent
+                    .as_any()
+                    .downcast_ref::<Window>()
+                    .and_then(|parent| parent.window.toplevel())
+                    .ok_or_else(|| NotSupportedError::new("the parent isn't a toplevel"))?,
+            ),
+            None => None,
+        };
+        toplevel.set_parent(parent);
+        Ok(())
+    }
+
+    fn set_modal(&self, modal: bool) -> Result<(), RequestError> {
+        self.window_state.lock().unwrap().set_modal(modal)
+    }
+
     fn set_window_icon(&self, _window_icon: Option<crate::window::Icon>) {}
 
     #[inline]
@@ -530,6 +844,14 @@ impl CoreWindow for Window {
         self.window_state.lock().unwrap().has_focus()
     }
 
+    fn pressed_keys(&self) -> Box<dyn Iterator<Item = PhysicalKey>> {
+        Box::new(self.window_state.lock().unwrap().pressed_keys().into_iter())
+    }
+
+    fn pressed_buttons(&self) -> Box<dyn Iterator<Item = MouseButton>> {
+        Box::new(self.window_state.lock().unwrap().pressed_buttons().into_iter())
+    }
+
     fn request_user_attention(&self, request_type: Option<UserAttentionType>) {
         let xdg_activation = match self.xdg_activation.as_ref() {
             Some(xdg_activation) => xdg_activation,
@@ -575,6 +897,17 @@ impl CoreWindow for Window {
         }
     }
 
+    fn pointer_position(&self) -> Option<PhysicalPosition<f64>> {
+        let window_state = self.window_state.lock().unwrap();
+        let position = window_state.pointer_position()?;
+        Some(position.to_physical(window_state.scale_factor()))
+    }
+
+    fn global_pointer_position(&self) -> Result<PhysicalPosition<f64>, RequestError> {
+        // Surfaces have no position on Wayland.
+        Err(NotSupportedError::new("global_pointer_position is not supported").into())
+    }
+
     fn set_cursor_position(&self, position: Position) -> Result<(), RequestError> {
         let scale_factor = self.scale_factor();
         let position = position.to_logical(scale_factor);
@@ -590,6 +923,23 @@ impl CoreWindow for Window {
         self.window_state.lock().unwrap().set_cursor_grab(mode)
     }
 
+    fn set_cursor_confine_region(&self, region: Option<&[Rect]>) -> Result<(), RequestError> {
+        self.window_state.lock().unwrap().set_cursor_confine_region(region.map(<[Rect]>::to_vec))
+    }
+
+    fn set_pointer_capture(
+        &self,
+        _device_id: Option<DeviceId>,
+        capture: bool,
+    ) -> Result<(), RequestError> {
+        // The compositor keeps sending the events of a pointer while one of its buttons is pressed.
+        if capture && self.window_state.lock().unwrap().pressed_buttons().is_empty() {
+            return Err(RequestError::Ignored);
+        }
+
+        Ok(())
+    }
+
     fn set_cursor_visible(&self, visible: bool) {
         self.window_state.lock().unwrap().set_cursor_visible(visible);
     }
@@ -608,18 +958,17 @@ impl CoreWindow for Window {
         self.window_state.lock().unwrap().show_window_menu(position);
     }
 
+    fn set_hit_test_zones(&self, zones: &[(Rect, HitTestZone)]) -> Result<(), RequestError> {
+        self.window_state.lock().unwrap().set_hit_test_zones(zones);
+        Ok(())
+    }
+
     fn set_cursor_hittest(&self, hittest: bool) -> Result<(), RequestError> {
-        let surface = self.window.wl_surface();
+        self.set_input_region(if hittest { None } else { Some(&[]) })
+    }
 
-        if hittest {
-            surface.set_input_region(None);
-            Ok(())
-        } else {
-            let region = Region::new(&*self.compositor).map_err(|err| os_error!(err))?;
-            region.add(0, 0, 0, 0);
-            surface.set_input_region(Some(region.wl_region()));
-            Ok(())
-        }
+    fn set_input_region(&self, region: Option<&[Rect]>) -> Result<(), RequestError> {
+        self.window_state.lock().unwrap().set_input_region(region.map(<[Rect]>::to_vec))
     }
 
     fn current_monitor(&self) -> Option<CoreMonitorHandle> {
@@ -659,6 +1008,172 @@ impl CoreWindow for Window {
     }
 }
 
+/// Find the surface of the parent of a child window, along with its scale factor.
+fn find_parent_surface(
+    state: &mut WinitState,
+    handle: &rwh_06::WaylandWindowHandle,
+) -> Result<(WlSurface, f64), RequestError> {
+    state
+        .windows
+        .get_mut()
+        .values()
+        .find_map(|window| {
+            let window = window.lock().unwrap();
+            let surface = window.window.wl_surface();
+            (surface.id().as_ptr() as *mut _ == handle.surface.as_ptr())
+                .then(|| (surface.clone(), window.scale_factor()))
+        })
+        .ok_or_else(|| NotSupportedError::new("the parent isn't a window").into())
+}
+
+/// Create a popup placed relative to its parent window.
+fn create_popup(
+    state: &mut WinitState,
+    queue_handle: &QueueHandle<WinitState>,
+    surface: WlSurface,
+    attributes: &PopupAttributes,
+    size: Size,
+) -> Result<WindowRole, RequestError> {
+    let parent = state
+        .windows
+        .get_mut()
+        .get(&attributes.parent)
+        .ok_or_else(|| NotSupportedError::new("the parent of the popup isn't a window"))?;
+    let parent = parent.lock().unwrap();
+    let parent_xdg_surface = parent
+        .window
+        .xdg_surface()
+        .ok_or_else(|| NotSupportedError::new("the parent of the popup can't have popups"))?
+        .clone();
+
+    // The popup is most likely shown at the scale of its parent.
+    let scale_factor = parent.scale_factor();
+    let (anchor_position, anchor_size) = attributes.anchor_rect.to_logical(scale_factor);
+    let geometry_position = parent.window_geometry_position();
+    drop(parent);
+
+    let positioner = XdgPositioner::new(&state.xdg_shell).map_err(|err| os_error!(err))?;
+    role::configure_positioner(
+        &positioner,
+        attributes,
+        size.to_logical(scale_factor),
+        LogicalPosition::new(
+            anchor_position.x - geometry_position.x,
+            anchor_position.y - geometry_position.y,
+        ),
+        anchor_size,
+    );
+
+    let popup = Popup::from_surface(
+        Some(&parent_xdg_surface),
+        &positioner,
+        queue_handle,
+        surface,
+        &state.xdg_shell,
+    )
+    .map_err(|err| os_error!(err))?;
+
+    // The grab must be requested before the initial commit.
+    if attributes.grab {
+        match state.latest_input_seat() {
+            Some((seat, serial)) => popup.xdg_popup().grab(&seat, serial),
+            None => warn!("Ignoring the grab of a popup without any prior input"),
+        }
+    }
+
+    Ok(WindowRole::Popup { popup, positioner: Arc::new(positioner) })
+}
+
+fn create_layer_surface(
+    state: &WinitState,
+    queue_handle: &QueueHandle<WinitState>,
+    surface: WlSurface,
+    attributes: &LayerShellAttributes,
+    size: Size,
+) -> Result<WindowRole, RequestError> {
+    let layer_shell = state
+        .layer_shell
+        .as_ref()
+        .ok_or_else(|| NotSupportedError::new("zwlr_layer_shell_v1 is not available"))?;
+
+    // Older compositors raise a protocol error on the unknown value.
+    if attributes.keyboard_interactivity == KeyboardInteractivity::OnDemand
+        && ProvidesBoundGlobal::<ZwlrLayerShellV1, 4>::bound_global(layer_shell)
+            .map_or(true, |layer_shell| layer_shell.version() < 4)
+    {
+        return Err(NotSupportedError::new(
+            "on demand keyboard interactivity needs zwlr_layer_shell_v1 version 4",
+        )
+        .into());
+    }
+
+    #[cfg_attr(not(x11_platform), allow(clippy::bind_instead_of_map))]
+    let output = attributes.output.as_ref().and_then(|monitor| match &monitor.inner {
+        PlatformMonitorHandle::Wayland(monitor) => Some(monitor.proxy.clone()),
+        #[cfg(x11_platform)]
+        PlatformMonitorHandle::X(_) => None,
+    });
+
+    let layer = layer_shell.create_layer_surface(
+        queue_handle,
+        surface,
+        attributes.layer.into(),
+        Some(attributes.namespace.clone()),
+        output.as_ref(),
+    );
+
+    // The surface is most likely shown at the scale of its output.
+    let scale_factor = attributes.output.as_ref().map_or(1., CoreMonitorHandle::scale_factor);
+    let anchor = attributes.anchor.into();
+    let (width, height) = role::layer_size(anchor, size.to_logical(scale_factor));
+    let (top, right, bottom, left) = attributes.margin;
+    layer.set_size(width, height);
+    layer.set_anchor(anchor);
+    layer.set_exclusive_zone(attributes.exclusive_zone);
+    layer.set_margin(top, right, bottom, left);
+    layer.set_keyboard_interactivity(attributes.keyboard_interactivity.into());
+
+    Ok(WindowRole::Layer { layer, anchor })
+}
+
+impl From<Layer> for SctkLayer {
+    fn from(layer: Layer) -> Self {
+        match layer {
+            Layer::Background => SctkLayer::Background,
+            Layer::Bottom => SctkLayer::Bottom,
+            Layer::Top => SctkLayer::Top,
+            Layer::Overlay => SctkLayer::Overlay,
+        }
+    }
+}
+
+impl From<LayerAnchor> for SctkLayerAnchor {
+    fn from(anchor: LayerAnchor) -> Self {
+        let mut layer_anchor = SctkLayerAnchor::empty();
+        for (flag, value) in [
+            (LayerAnchor::TOP, SctkLayerAnchor::TOP),
+            (LayerAnchor::BOTTOM, SctkLayerAnchor::BOTTOM),
+            (LayerAnchor::LEFT, SctkLayerAnchor::LEFT),
+            (LayerAnchor::RIGHT, SctkLayerAnchor::RIGHT),
+        ] {
+            if anchor.contains(flag) {
+                layer_anchor |= value;
+            }
+        }
+        layer_anchor
+    }
+}
+
+impl From<KeyboardInteractivity> for SctkKeyboardInteractivity {
+    fn from(keyboard_interactivity: KeyboardInteractivity) -> Self {
+        match keyboard_interactivity {
+            KeyboardInteractivity::None => SctkKeyboardInteractivity::None,
+            KeyboardInteractivity::Exclusive => SctkKeyboardInteractivity::Exclusive,
+            KeyboardInteractivity::OnDemand => SctkKeyboardInteractivity::OnDemand,
+        }
+    }
+}
+
 /// The request from the window to the event loop.
 #[derive(Debug)]
 pub struct WindowRequests {
@@ -667,6 +1182,9 @@ pub struct WindowRequests {
 
     /// Redraw Requested.
     pub redraw_requested: AtomicBool,
+
+    /// The responses to the drag over the window.
+    pub drop_requests: Mutex<Vec<DropRequest>>,
 }
 
 impl WindowRequests {
@@ -677,6 +1195,10 @@ impl WindowRequests {
     pub fn take_redraw_requested(&self) -> bool {
         self.redraw_requested.swap(false, Ordering::Relaxed)
     }
+
+    pub fn take_drop_requests(&self) -> Vec<DropRequest> {
+        std::mem::take(&mut self.drop_requests.lock().unwrap())
+    }
 }
 
 impl TryFrom<&str> for Theme {
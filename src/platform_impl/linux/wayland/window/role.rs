@@ -0,0 +1,273 @@
+//! The role of the surface of a window.
+
+use std::sync::{Arc, Mutex};
+
+use sctk::reexports::client::protocol::wl_subsurface::WlSubsurface;
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::Proxy;
+use sctk::reexports::protocols::xdg::shell::client::xdg_positioner::{
+    Anchor, ConstraintAdjustment, Gravity,
+};
+use sctk::reexports::protocols::xdg::shell::client::xdg_surface::XdgSurface;
+use sctk::shell::wlr_layer::{Anchor as LayerAnchor, LayerSurface};
+use sctk::shell::xdg::popup::Popup;
+use sctk::shell::xdg::window::Window;
+use sctk::shell::xdg::{XdgPositioner, XdgSurface as _};
+use sctk::shell::WaylandSurface;
+
+use crate::dpi::{LogicalPosition, LogicalSize};
+use crate::window::{PopupAdjustment, PopupAnchor, PopupAttributes};
+
+/// The role of the surface of a window.
+#[derive(Debug, Clone)]
+pub enum WindowRole {
+    /// A regular window.
+    Toplevel(Window),
+
+    /// A popup, along with its positioner to place it again when it's resized.
+    Popup { popup: Popup, positioner: Arc<XdgPositioner> },
+
+    /// A child window, drawn as a part of the surface of its parent.
+    Subsurface(Arc<Subsurface>),
+
+    /// A surface of the desktop shell, such as a panel, along with the edges it's anchored to.
+    Layer { layer: LayerSurface, anchor: LayerAnchor },
+}
+
+impl WindowRole {
+    /// The toplevel, if the window is one.
+    pub fn toplevel(&self) -> Option<&Window> {
+        match self {
+            Self::Toplevel(window) => Some(window),
+            Self::Popup { .. } | Self::Subsurface(_) | Self::Layer { .. } => None,
+        }
+    }
+
+    /// The xdg surface of the window, which popups could be placed against.
+    pub fn xdg_surface(&self) -> Option<&XdgSurface> {
+        match self {
+            Self::Toplevel(window) => Some(window.xdg_surface()),
+            Self::Popup { popup, .. } => Some(popup.xdg_surface()),
+            Self::Subsurface(_) | Self::Layer { .. } => None,
+        }
+    }
+
+    /// The subsurface, if the window is a child window.
+    pub fn subsurface(&self) -> Option<&Subsurface> {
+        match self {
+            Self::Subsurface(subsurface) => Some(subsurface),
+            Self::Toplevel(_) | Self::Popup { .. } | Self::Layer { .. } => None,
+        }
+    }
+
+    /// Request a new size for the layer surface, which is applied with its next configure.
+    ///
+    /// Returns `false` when the window isn't a layer surface.
+    pub fn request_layer_size(&self, size: LogicalSize<u32>) -> bool {
+        let Self::Layer { layer, anchor } = self else {
+            return false;
+        };
+
+        let (width, height) = layer_size(*anchor, size);
+        layer.set_size(width, height);
+        true
+    }
+
+    /// Request a new size for the popup, which is placed again by the compositor.
+    ///
+    /// Returns `false` when the window isn't a popup or the compositor can't place it again.
+    pub fn reposition_popup(&self, size: LogicalSize<u32>) -> bool {
+        let Self::Popup { popup, positioner } = self else {
+            return false;
+        };
+
+        if popup.xdg_popup().version() < 3 {
+            return false;
+        }
+
+        positioner.set_size(size.width.max(1) as i32, size.height.max(1) as i32);
+        popup.reposition(positioner, 0);
+        true
+    }
+}
+
+impl WaylandSurface for WindowRole {
+    fn wl_surface(&self) -> &WlSurface {
+        match self {
+            Self::Toplevel(window) => window.wl_surface(),
+            Self::Popup { popup, .. } => popup.wl_surface(),
+            Self::Subsurface(subsurface) => &subsurface.surface,
+            Self::Layer { layer, .. } => layer.wl_surface(),
+        }
+    }
+}
+
+/// The role of the surface of a child window.
+#[derive(Debug)]
+pub struct Subsurface {
+    subsurface: WlSubsurface,
+    surface: WlSurface,
+
+    /// The position relative to the surface of the parent.
+    position: Mutex<LogicalPosition<i32>>,
+}
+
+impl Subsurface {
+    pub fn new(subsurface: WlSubsurface, surface: WlSurface) -> Self {
+        Self { subsurface, surface, position: Mutex::new(LogicalPosition::new(0, 0)) }
+    }
+
+    pub fn position(&self) -> LogicalPosition<i32> {
+        *self.position.lock().unwrap()
+    }
+
+    /// Move the subsurface, which takes effect with the next commit of the parent.
+    pub fn set_position(&self, position: LogicalPosition<i32>) {
+        *self.position.lock().unwrap() = position;
+        self.subsurface.set_position(position.x, position.y);
+    }
+
+    /// Set whether the state of the subsurface is applied along with its parent's.
+    pub fn set_sync(&self, sync: bool) {
+        if sync {
+            self.subsurface.set_sync();
+        } else {
+            self.subsurface.set_desync();
+        }
+    }
+
+    /// Place the subsurface above or below a sibling, or its parent.
+    pub fn place(&self, sibling: &WlSurface, above: bool) {
+        if above {
+            self.subsurface.place_above(sibling);
+        } else {
+            self.subsurface.place_below(sibling);
+        }
+    }
+}
+
+impl Drop for Subsurface {
+    fn drop(&mut self) {
+        self.subsurface.destroy();
+        self.surface.destroy();
+    }
+}
+
+/// The size requested for a layer surface, leaving the axes it's stretched along to the compositor.
+pub fn layer_size(anchor: LayerAnchor, size: LogicalSize<u32>) -> (u32, u32) {
+    let stretched = |edges: LayerAnchor, length: u32| {
+        if anchor.contains(edges) {
+            0
+        } else {
+            length.max(1)
+        }
+    };
+    (
+        stretched(LayerAnchor::LEFT | LayerAnchor::RIGHT, size.width),
+        stretched(LayerAnchor::TOP | LayerAnchor::BOTTOM, size.height),
+    )
+}
+
+/// Configure the positioner of a popup.
+///
+/// The anchor rectangle is relative to the window geometry of the parent.
+pub fn configure_positioner(
+    positioner: &XdgPositioner,
+    attributes: &PopupAttributes,
+    size: LogicalSize<u32>,
+    anchor_position: LogicalPosition<i32>,
+    anchor_size: LogicalSize<u32>,
+) {
+    positioner.set_size(size.width.max(1) as i32, size.height.max(1) as i32);
+    positioner.set_anchor_rect(
+        anchor_position.x,
+        anchor_position.y,
+        anchor_size.width as i32,
+        anchor_size.height as i32,
+    );
+    positioner.set_anchor(attributes.anchor.into());
+    positioner.set_gravity(attributes.gravity.into());
+    positioner.set_constraint_adjustment(attributes.adjustment.into());
+
+    // Place the popup again when the parent moves or resizes.
+    if positioner.version() >= 3 {
+        positioner.set_reactive();
+    }
+}
+
+impl From<PopupAnchor> for Anchor {
+    fn from(anchor: PopupAnchor) -> Self {
+        match anchor {
+            PopupAnchor::Center => Anchor::None,
+            PopupAnchor::Top => Anchor::Top,
+            PopupAnchor::Bottom => Anchor::Bottom,
+            PopupAnchor::Left => Anchor::Left,
+            PopupAnchor::Right => Anchor::Right,
+            PopupAnchor::TopLeft => Anchor::TopLeft,
+            PopupAnchor::TopRight => Anchor::TopRight,
+            PopupAnchor::BottomLeft => Anchor::BottomLeft,
+            PopupAnchor::BottomRight => Anchor::BottomRight,
+        }
+    }
+}
+
+impl From<PopupAnchor> for Gravity {
+    fn from(gravity: PopupAnchor) -> Self {
+        match gravity {
+            PopupAnchor::Center => Gravity::None,
+            PopupAnchor::Top => Gravity::Top,
+            PopupAnchor::Bottom => Gravity::Bottom,
+            PopupAnchor::Left => Gravity::Left,
+            PopupAnchor::Right => Gravity::Right,
+            PopupAnchor::TopLeft => Gravity::TopLeft,
+            PopupAnchor::TopRight => Gravity::TopRight,
+            PopupAnchor::BottomLeft => Gravity::BottomLeft,
+            PopupAnchor::BottomRight => Gravity::BottomRight,
+        }
+    }
+}
+
+impl From<PopupAdjustment> for ConstraintAdjustment {
+    fn from(adjustment: PopupAdjustment) -> Self {
+        let mut constraint_adjustment = ConstraintAdjustment::None;
+        for (flag, value) in [
+            (PopupAdjustment::SLIDE_X, ConstraintAdjustment::SlideX),
+            (PopupAdjustment::SLIDE_Y, ConstraintAdjustment::SlideY),
+            (PopupAdjustment::FLIP_X, ConstraintAdjustment::FlipX),
+            (PopupAdjustment::FLIP_Y, ConstraintAdjustment::FlipY),
+            (PopupAdjustment::RESIZE_X, ConstraintAdjustment::ResizeX),
+            (PopupAdjustment::RESIZE_Y, ConstraintAdjustment::ResizeY),
+        ] {
+            if adjustment.contains(flag) {
+                constraint_adjustment |= value;
+            }
+        }
+        constraint_adjustment
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layer_size_stretched() {
+        let size = LogicalSize::new(200, 30);
+        assert_eq!(layer_size(LayerAnchor::empty(), size), (200, 30));
+        assert_eq!(layer_size(LayerAnchor::TOP | LayerAnchor::LEFT, size), (200, 30));
+        assert_eq!(
+            layer_size(LayerAnchor::TOP | LayerAnchor::LEFT | LayerAnchor::RIGHT, size),
+            (0, 30)
+        );
+        assert_eq!(layer_size(LayerAnchor::all(), size), (0, 0));
+    }
+
+    #[test]
+    fn layer_size_not_empty() {
+        // A zero size is only allowed along the stretched axes.
+        assert_eq!(
+            layer_size(LayerAnchor::LEFT | LayerAnchor::RIGHT, LogicalSize::new(0, 0)),
+            (0, 1)
+        );
+    }
+}
@@ -4,23 +4,26 @@ use std::num::NonZeroU32;
 use std::sync::{Arc, Mutex, Weak};
 use std::time::Duration;
 
-use ahash::HashSet;
+use ahash::HashMap;
 use sctk::compositor::{CompositorState, Region, SurfaceData, SurfaceDataExt};
 use sctk::reexports::client::backend::ObjectId;
 use sctk::reexports::client::protocol::wl_seat::WlSeat;
-use sctk::reexports::client::protocol::wl_shm::WlShm;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{Proxy, QueueHandle};
 use sctk::reexports::csd_frame::{
     DecorationsFrame, FrameAction, FrameClick, ResizeEdge, WindowState as XdgWindowState,
 };
+use sctk::reexports::protocols::wp::cursor_shape::v1::client::wp_cursor_shape_device_v1::WpCursorShapeDeviceV1;
 use sctk::reexports::protocols::wp::fractional_scale::v1::client::wp_fractional_scale_v1::WpFractionalScaleV1;
+use sctk::reexports::protocols::wp::tablet::zv2::client::zwp_tablet_tool_v2::ZwpTabletToolV2;
 use sctk::reexports::protocols::wp::text_input::zv3::client::zwp_text_input_v3::ZwpTextInputV3;
 use sctk::reexports::protocols::wp::viewporter::client::wp_viewport::WpViewport;
+use sctk::reexports::protocols::xdg::dialog::v1::client::xdg_dialog_v1::XdgDialogV1;
 use sctk::reexports::protocols::xdg::shell::client::xdg_toplevel::ResizeEdge as XdgResizeEdge;
-use sctk::seat::pointer::{PointerDataExt, ThemedPointer};
+use sctk::seat::pointer::PointerDataExt;
+use sctk::shell::wlr_layer::LayerSurfaceConfigure;
+use sctk::shell::xdg::popup::{ConfigureKind, PopupConfigure};
 use sctk::shell::xdg::window::{DecorationMode, Window, WindowConfigure};
-use sctk::shell::xdg::XdgSurface;
 use sctk::shell::WaylandSurface;
 use sctk::shm::slot::SlotPool;
 use sctk::shm::Shm;
@@ -31,16 +34,26 @@ use wayland_protocols_plasma::blur::client::org_kde_kwin_blur::OrgKdeKwinBlur;
 use crate::cursor::CustomCursor as RootCustomCursor;
 use crate::dpi::{LogicalPosition, LogicalSize, PhysicalSize, Size};
 use crate::error::{NotSupportedError, RequestError};
+use crate::event::{ElementState, MouseButton};
+use crate::keyboard::PhysicalKey;
+use crate::platform_impl::common::hit_test::{HitTestAction, HitTestZones};
 use crate::platform_impl::wayland::event_loop::OwnedDisplayHandle;
-use crate::platform_impl::wayland::logical_to_physical_rounded;
 use crate::platform_impl::wayland::seat::{
-    PointerConstraintsState, WinitPointerData, WinitPointerDataExt, ZwpTextInputV3Ext,
+    PointerConstraintsState, ThemedPointer, WinitPointerData, WinitPointerDataExt,
+    ZwpTextInputV3Ext,
 };
 use crate::platform_impl::wayland::state::{WindowCompositorUpdate, WinitState};
-use crate::platform_impl::wayland::types::cursor::{CustomCursor, SelectedCursor};
+use crate::platform_impl::wayland::types::cursor::{
+    cursor_icon_to_shape, CustomCursor, SelectedCursor,
+};
 use crate::platform_impl::wayland::types::kwin_blur::KWinBlurManager;
+use crate::platform_impl::wayland::types::xdg_dialog::XdgDialogManager;
+use crate::platform_impl::wayland::window::role::WindowRole;
+use crate::platform_impl::wayland::{self, logical_to_physical_rounded};
 use crate::platform_impl::PlatformCustomCursor;
-use crate::window::{CursorGrabMode, CursorIcon, ImePurpose, ResizeDirection, Theme, WindowId};
+use crate::window::{
+    CursorGrabMode, CursorIcon, HitTestZone, ImePurpose, Rect, ResizeDirection, Theme, WindowId,
+};
 
 #[cfg(feature = "sctk-adwaita")]
 pub type WinitFrame = sctk_adwaita::AdwaitaFrame<WinitState>;
@@ -55,17 +68,17 @@ pub struct WindowState {
     /// The connection to Wayland server.
     pub handle: Arc<OwnedDisplayHandle>,
 
-    /// The `Shm` to set cursor.
-    pub shm: WlShm,
-
     // A shared pool where to allocate custom cursors.
     custom_cursor_pool: Arc<Mutex<SlotPool>>,
 
-    /// The last received configure.
+    /// The last received configure, for toplevels.
     pub last_configure: Option<WindowConfigure>,
 
+    /// Whether the window received the initial configure.
+    configured: bool,
+
     /// The pointers observed on the window.
-    pub pointers: Vec<Weak<ThemedPointer<WinitPointerData>>>,
+    pub pointers: Vec<Weak<ThemedPointer>>,
 
     selected_cursor: SelectedCursor,
 
@@ -89,8 +102,8 @@ pub struct WindowState {
 
     // NOTE: we can't use simple counter, since it's racy when seat getting destroyed and new
     // is created, since add/removed stuff could be delivered a bit out of order.
-    /// Seats that has keyboard focus on that window.
-    seat_focus: HashSet<ObjectId>,
+    /// Seats that has keyboard focus on that window, with the keys pressed on them.
+    seat_focus: HashMap<ObjectId, Vec<PhysicalKey>>,
 
     /// The scale factor of the window.
     scale_factor: f64,
@@ -142,14 +155,22 @@ pub struct WindowState {
     fractional_scale: Option<WpFractionalScaleV1>,
     blur: Option<OrgKdeKwinBlur>,
     blur_manager: Option<KWinBlurManager>,
+    dialog: Option<XdgDialogV1>,
+    dialog_manager: Option<XdgDialogManager>,
 
     /// Whether the client side decorations have pending move operations.
     ///
     /// The value is the serial of the event triggered moved.
     has_pending_move: Option<u32>,
 
-    /// The underlying SCTK window.
-    pub window: Window,
+    /// The zones of the window acting like decorations.
+    hit_test_zones: HitTestZones,
+
+    /// The region of the surface receiving input, the whole surface when `None`.
+    input_region: Option<Vec<Rect>>,
+
+    /// The underlying SCTK window, or popup.
+    pub window: WindowRole,
 
     // NOTE: The spec says that destroying parent(`window` in our case), will unmap the
     // subsurfaces. Thus to achieve atomic unmap of the client, drop the decorations
@@ -166,7 +187,7 @@ impl WindowState {
         queue_handle: &QueueHandle<WinitState>,
         winit_state: &WinitState,
         initial_size: Size,
-        window: Window,
+        window: WindowRole,
         theme: Option<Theme>,
     ) -> Self {
         let compositor = winit_state.compositor_state.clone();
@@ -183,7 +204,10 @@ impl WindowState {
         Self {
             blur: None,
             blur_manager: winit_state.kwin_blur_manager.clone(),
+            dialog: None,
+            dialog_manager: winit_state.xdg_dialog_manager.clone(),
             compositor,
+            configured: false,
             handle,
             csd_fails: false,
             cursor_grab_mode: GrabState::new(),
@@ -195,8 +219,10 @@ impl WindowState {
             frame_callback_state: FrameCallbackState::None,
             seat_focus: Default::default(),
             has_pending_move: None,
+            hit_test_zones: HitTestZones::default(),
             ime_allowed: false,
             ime_purpose: ImePurpose::Normal,
+            input_region: None,
             last_configure: None,
             max_surface_size: None,
             min_surface_size: MIN_WINDOW_SIZE,
@@ -205,7 +231,6 @@ impl WindowState {
             queue_handle: queue_handle.clone(),
             resizable: true,
             scale_factor: 1.,
-            shm: winit_state.shm.wl_shm().clone(),
             custom_cursor_pool: winit_state.custom_cursor_pool.clone(),
             size: initial_size.to_logical(1.),
             stateless_size: initial_size.to_logical(1.),
@@ -220,10 +245,7 @@ impl WindowState {
     }
 
     /// Apply closure on the given pointer.
-    fn apply_on_pointer<F: Fn(&ThemedPointer<WinitPointerData>, &WinitPointerData)>(
-        &self,
-        callback: F,
-    ) {
+    fn apply_on_pointer<F: Fn(&ThemedPointer, &WinitPointerData)>(&self, callback: F) {
         self.pointers.iter().filter_map(Weak::upgrade).for_each(|pointer| {
             let data = pointer.pointer().winit_data();
             callback(pointer.as_ref(), data);
@@ -271,13 +293,14 @@ impl WindowState {
             self.stateless_size = self.size;
         }
 
-        if let Some(subcompositor) = subcompositor.as_ref().filter(|_| {
+        let frame_subcompositor = subcompositor.as_ref().filter(|_| {
             configure.decoration_mode == DecorationMode::Client
                 && self.frame.is_none()
                 && !self.csd_fails
-        }) {
+        });
+        if let (Some(subcompositor), Some(window)) = (frame_subcompositor, self.window.toplevel()) {
             match WinitFrame::new(
-                &self.window,
+                window,
                 shm,
                 #[cfg(feature = "sctk-adwaita")]
                 self.compositor.clone(),
@@ -353,6 +376,7 @@ impl WindowState {
 
         // NOTE: Set the configure before doing a resize, since we query it during it.
         self.last_configure = Some(configure);
+        self.configured = true;
 
         if state_change_requires_resize || new_size != self.surface_size() {
             self.resize(new_size);
@@ -362,6 +386,58 @@ impl WindowState {
         }
     }
 
+    /// Apply the configure of a popup, returning whether it was resized.
+    pub fn configure_popup(&mut self, configure: PopupConfigure) -> bool {
+        // The compositor picks the size of popups from the positioner.
+        self.initial_size = None;
+        self.configured = true;
+
+        let new_size =
+            LogicalSize::new(configure.width.max(1) as u32, configure.height.max(1) as u32);
+        if matches!(configure.kind, ConfigureKind::Initial) || new_size != self.surface_size() {
+            self.resize(new_size);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Apply the configure of a layer surface, returning whether it was resized.
+    pub fn configure_layer(&mut self, configure: LayerSurfaceConfigure) -> bool {
+        let initial = !self.configured;
+        if let Some(initial_size) = self.initial_size.take() {
+            self.size = initial_size.to_logical(self.scale_factor());
+        }
+        self.configured = true;
+
+        // The compositor leaves the size of the axes the surface isn't stretched along to us.
+        let (width, height) = configure.new_size;
+        let new_size = LogicalSize::new(
+            if width == 0 { self.size.width } else { width },
+            if height == 0 { self.size.height } else { height },
+        );
+        if initial || new_size != self.surface_size() {
+            self.resize(new_size);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The compositor closed the popup or the layer surface, possibly before it was ever
+    /// configured.
+    pub fn surface_closed(&mut self) {
+        self.configured = true;
+    }
+
+    /// Apply the initial size of a child window, which the compositor doesn't configure.
+    pub fn configure_subsurface(&mut self) {
+        self.configured = true;
+        if let Some(initial_size) = self.initial_size.take() {
+            self.resize(initial_size.to_logical(self.scale_factor()));
+        }
+    }
+
     /// Compute the bounds for the surface size of the surface.
     fn surface_size_bounds(
         &self,
@@ -390,7 +466,7 @@ impl WindowState {
 
     /// Start interacting drag resize.
     pub fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), RequestError> {
-        let xdg_toplevel = self.window.xdg_toplevel();
+        let xdg_toplevel = self.toplevel()?.xdg_toplevel();
 
         // TODO(kchibisov) handle touch serials.
         self.apply_on_pointer(|_, data| {
@@ -404,7 +480,7 @@ impl WindowState {
 
     /// Start the window drag.
     pub fn drag_window(&self) -> Result<(), RequestError> {
-        let xdg_toplevel = self.window.xdg_toplevel();
+        let xdg_toplevel = self.toplevel()?.xdg_toplevel();
         // TODO(kchibisov) handle touch serials.
         self.apply_on_pointer(|_, data| {
             let serial = data.latest_button_serial();
@@ -427,10 +503,11 @@ impl WindowState {
         window_id: WindowId,
         updates: &mut Vec<WindowCompositorUpdate>,
     ) -> Option<bool> {
+        let window = self.window.toplevel()?;
         match self.frame.as_mut()?.on_click(timestamp, click, pressed)? {
-            FrameAction::Minimize => self.window.set_minimized(),
-            FrameAction::Maximize => self.window.set_maximized(),
-            FrameAction::UnMaximize => self.window.unset_maximized(),
+            FrameAction::Minimize => window.set_minimized(),
+            FrameAction::Maximize => window.set_maximized(),
+            FrameAction::UnMaximize => window.unset_maximized(),
             FrameAction::Close => WinitState::queue_close(updates, window_id),
             FrameAction::Move => self.has_pending_move = Some(serial),
             FrameAction::Resize(edge) => {
@@ -446,15 +523,57 @@ impl WindowState {
                     ResizeEdge::BottomRight => XdgResizeEdge::BottomRight,
                     _ => return None,
                 };
-                self.window.resize(seat, serial, edge);
+                window.resize(seat, serial, edge);
             },
-            FrameAction::ShowMenu(x, y) => self.window.show_window_menu(seat, serial, (x, y)),
+            FrameAction::ShowMenu(x, y) => window.show_window_menu(seat, serial, (x, y)),
             _ => (),
         };
 
         Some(false)
     }
 
+    /// Set the zones of the window acting like decorations.
+    pub fn set_hit_test_zones(&mut self, zones: &[(Rect, HitTestZone)]) {
+        self.hit_test_zones.set(zones);
+    }
+
+    /// Perform the action of a click in the hit-test zones of the window.
+    pub fn hit_test_click(
+        &mut self,
+        button: MouseButton,
+        state: ElementState,
+        position: LogicalPosition<f64>,
+        seat: &WlSeat,
+        serial: u32,
+        timestamp: Duration,
+    ) {
+        let scale_factor = self.scale_factor();
+        let physical_position = position.to_physical(scale_factor);
+        let Some(window) = self.window.toplevel() else {
+            return;
+        };
+        let Some(action) =
+            self.hit_test_zones.button(button, state, physical_position, scale_factor, timestamp)
+        else {
+            return;
+        };
+
+        match action {
+            HitTestAction::Move => window.move_(seat, serial),
+            HitTestAction::Resize(direction) => window.resize(seat, serial, direction.into()),
+            HitTestAction::ToggleMaximized => {
+                if self.last_configure.as_ref().is_some_and(WindowConfigure::is_maximized) {
+                    window.unset_maximized();
+                } else {
+                    window.set_maximized();
+                }
+            },
+            HitTestAction::ShowMenu => {
+                window.show_window_menu(seat, serial, (position.x as i32, position.y as i32))
+            },
+        }
+    }
+
     pub fn frame_point_left(&mut self) {
         if let Some(frame) = self.frame.as_mut() {
             frame.click_point_left();
@@ -477,8 +596,10 @@ impl WindowState {
             let cursor = frame.click_point_moved(timestamp, &surface.id(), x, y);
             // If we have a cursor change, that means that cursor is over the decorations,
             // so try to apply move.
-            if let Some(serial) = cursor.is_some().then_some(serial).flatten() {
-                self.window.move_(seat, serial);
+            if let Some((serial, window)) =
+                cursor.is_some().then_some(serial).flatten().zip(self.window.toplevel())
+            {
+                window.move_(seat, serial);
                 None
             } else {
                 cursor
@@ -541,7 +662,7 @@ impl WindowState {
     /// Whether the window received initial configure event from the compositor.
     #[inline]
     pub fn is_configured(&self) -> bool {
-        self.last_configure.is_some()
+        self.configured
     }
 
     #[inline]
@@ -569,7 +690,7 @@ impl WindowState {
     }
 
     /// Register pointer on the top-level.
-    pub fn pointer_entered(&mut self, added: Weak<ThemedPointer<WinitPointerData>>) {
+    pub fn pointer_entered(&mut self, added: Weak<ThemedPointer>) {
         self.pointers.push(added);
         self.reload_cursor_style();
 
@@ -578,7 +699,7 @@ impl WindowState {
     }
 
     /// Pointer has left the top-level.
-    pub fn pointer_left(&mut self, removed: Weak<ThemedPointer<WinitPointerData>>) {
+    pub fn pointer_left(&mut self, removed: Weak<ThemedPointer>) {
         let mut new_pointers = Vec::new();
         for pointer in self.pointers.drain(..) {
             if let Some(pointer) = pointer.upgrade() {
@@ -630,8 +751,20 @@ impl WindowState {
 
     /// Try to resize the window when the user can do so.
     pub fn request_surface_size(&mut self, surface_size: Size) -> PhysicalSize<u32> {
-        if self.last_configure.as_ref().map(Self::is_stateless).unwrap_or(true) {
-            self.resize(surface_size.to_logical(self.scale_factor()))
+        let surface_size = surface_size.to_logical(self.scale_factor());
+        match self.window {
+            // The size of popups is applied once the compositor placed them again.
+            WindowRole::Popup { .. } => {
+                self.window.reposition_popup(surface_size);
+            },
+            // The size of layer surfaces is applied with their next configure.
+            WindowRole::Layer { .. } => {
+                self.window.request_layer_size(surface_size);
+            },
+            _ if self.last_configure.as_ref().map(Self::is_stateless).unwrap_or(true) => {
+                self.resize(surface_size)
+            },
+            _ => (),
         }
 
         logical_to_physical_rounded(self.surface_size(), self.scale_factor())
@@ -665,12 +798,14 @@ impl WindowState {
         self.reload_transparency_hint();
 
         // Set the window geometry.
-        self.window.xdg_surface().set_window_geometry(
-            x,
-            y,
-            outer_size.width as i32,
-            outer_size.height as i32,
-        );
+        if let Some(xdg_surface) = self.window.xdg_surface() {
+            xdg_surface.set_window_geometry(
+                x,
+                y,
+                outer_size.width as i32,
+                outer_size.height as i32,
+            );
+        }
 
         // Update the target viewport, this is used if and only if fractional scaling is in use.
         if let Some(viewport) = self.viewport.as_ref() {
@@ -726,17 +861,7 @@ impl WindowState {
     fn apply_custom_cursor(&self, cursor: &CustomCursor) {
         self.apply_on_pointer(|pointer, _| {
             let surface = pointer.surface();
-
-            let scale = surface.data::<SurfaceData>().unwrap().surface_data().scale_factor();
-
-            surface.set_buffer_scale(scale);
-            surface.attach(Some(cursor.buffer.wl_buffer()), 0, 0);
-            if surface.version() >= 4 {
-                surface.damage_buffer(0, 0, cursor.w, cursor.h);
-            } else {
-                surface.damage(0, 0, cursor.w / scale, cursor.h / scale);
-            }
-            surface.commit();
+            let scale = commit_custom_cursor(surface, cursor);
 
             let serial = pointer
                 .pointer()
@@ -753,6 +878,40 @@ impl WindowState {
         });
     }
 
+    /// Set the cursor of the window on a tablet tool in proximity of it.
+    ///
+    /// Named cursors need `wp_cursor_shape_v1`, without it the compositor picks the cursor of the
+    /// tool.
+    pub fn set_tablet_tool_cursor(
+        &self,
+        tool: &ZwpTabletToolV2,
+        serial: u32,
+        shape_device: Option<&WpCursorShapeDeviceV1>,
+        surface: &WlSurface,
+    ) {
+        if !self.cursor_visible {
+            tool.set_cursor(serial, None, 0, 0);
+            return;
+        }
+
+        match &self.selected_cursor {
+            SelectedCursor::Named(icon) => {
+                if let Some(shape_device) = shape_device {
+                    shape_device.set_shape(serial, cursor_icon_to_shape(*icon));
+                }
+            },
+            SelectedCursor::Custom(cursor) => {
+                let scale = commit_custom_cursor(surface, cursor);
+                tool.set_cursor(
+                    serial,
+                    Some(surface),
+                    cursor.hotspot_x / scale,
+                    cursor.hotspot_y / scale,
+                );
+            },
+        }
+    }
+
     /// Set maximum inner window size.
     pub fn set_min_surface_size(&mut self, size: Option<LogicalSize<u32>>) {
         // Ensure that the window has the right minimum size.
@@ -768,7 +927,9 @@ impl WindowState {
             .unwrap_or(size);
 
         self.min_surface_size = size;
-        self.window.set_min_size(Some(size.into()));
+        if let Some(window) = self.window.toplevel() {
+            window.set_min_size(Some(size.into()));
+        }
     }
 
     /// Set maximum inner window size.
@@ -781,7 +942,9 @@ impl WindowState {
         });
 
         self.max_surface_size = size;
-        self.window.set_max_size(size.map(Into::into));
+        if let Some(window) = self.window.toplevel() {
+            window.set_max_size(size.map(Into::into));
+        }
     }
 
     /// Set the CSD theme.
@@ -848,10 +1011,20 @@ impl WindowState {
                 let pointer = pointer.pointer();
                 data.lock_pointer(pointer_constraints, surface, pointer, &self.queue_handle)
             }),
-            CursorGrabMode::Confined => self.apply_on_pointer(|pointer, data| {
-                let pointer = pointer.pointer();
-                data.confine_pointer(pointer_constraints, surface, pointer, &self.queue_handle)
-            }),
+            CursorGrabMode::Confined => {
+                let region = self.cursor_confine_region();
+                let region = region.as_ref().map(Region::wl_region);
+                self.apply_on_pointer(|pointer, data| {
+                    let pointer = pointer.pointer();
+                    data.confine_pointer(
+                        pointer_constraints,
+                        surface,
+                        pointer,
+                        region,
+                        &self.queue_handle,
+                    )
+                })
+            },
             CursorGrabMode::None => {
                 // Current lock/confine was already removed.
             },
@@ -860,12 +1033,79 @@ impl WindowState {
         Ok(())
     }
 
+    /// Set the region of the surface the cursor is confined to.
+    pub fn set_cursor_confine_region(
+        &mut self,
+        region: Option<Vec<Rect>>,
+    ) -> Result<(), RequestError> {
+        if self.pointer_constraints.is_none() {
+            return Err(NotSupportedError::new("zwp_pointer_constraints is not available").into());
+        }
+
+        self.cursor_grab_mode.confine_region = region;
+        self.reload_cursor_confine_region();
+        Ok(())
+    }
+
+    /// Confine the cursor again when the region it's confined to changed.
+    fn reload_cursor_confine_region(&mut self) {
+        if self.cursor_grab_mode.current_grab_mode == CursorGrabMode::Confined {
+            let _ = self.set_cursor_grab_inner(CursorGrabMode::Confined);
+        }
+    }
+
+    /// Set the region of the surface receiving input, the whole surface when `None`.
+    pub fn set_input_region(&mut self, region: Option<Vec<Rect>>) -> Result<(), RequestError> {
+        self.input_region = region;
+        self.reload_input_region()
+    }
+
+    /// Set the input region of the surface at the current scale.
+    fn reload_input_region(&self) -> Result<(), RequestError> {
+        let surface = self.window.wl_surface();
+        let Some(rects) = self.input_region.as_ref() else {
+            surface.set_input_region(None);
+            return Ok(());
+        };
+
+        let region = Region::new(&*self.compositor).map_err(|err| os_error!(err))?;
+        for rect in rects {
+            let (position, size) = rect.to_logical(self.scale_factor());
+            region.add(position.x, position.y, size.width as i32, size.height as i32);
+        }
+        surface.set_input_region(Some(region.wl_region()));
+        Ok(())
+    }
+
+    /// The region the cursor is confined to, `None` for the whole surface.
+    fn cursor_confine_region(&self) -> Option<Region> {
+        let rects = self.cursor_grab_mode.confine_region.as_ref()?;
+        let region = match Region::new(&*self.compositor) {
+            Ok(region) => region,
+            Err(err) => {
+                warn!("Failed to create the cursor confine region: {err}");
+                return None;
+            },
+        };
+
+        for rect in rects {
+            let (position, size) = rect.to_logical(self.scale_factor());
+            region.add(position.x, position.y, size.width as i32, size.height as i32);
+        }
+
+        Some(region)
+    }
+
     pub fn show_window_menu(&self, position: LogicalPosition<u32>) {
+        let Some(window) = self.window.toplevel() else {
+            return;
+        };
+
         // TODO(kchibisov) handle touch serials.
         self.apply_on_pointer(|_, data| {
             let serial = data.latest_button_serial();
             let seat = data.seat();
-            self.window.show_window_menu(seat, serial, position.into());
+            window.show_window_menu(seat, serial, position.into());
         });
     }
 
@@ -917,12 +1157,15 @@ impl WindowState {
 
         self.decorate = decorate;
 
+        let Some(window) = self.window.toplevel() else {
+            return;
+        };
         match self.last_configure.as_ref().map(|configure| configure.decoration_mode) {
             Some(DecorationMode::Server) if !self.decorate => {
                 // To disable decorations we should request client and hide the frame.
-                self.window.request_decoration_mode(Some(DecorationMode::Client))
+                window.request_decoration_mode(Some(DecorationMode::Client))
             },
-            _ if self.decorate => self.window.request_decoration_mode(Some(DecorationMode::Server)),
+            _ if self.decorate => window.request_decoration_mode(Some(DecorationMode::Server)),
             _ => (),
         }
 
@@ -933,10 +1176,49 @@ impl WindowState {
         }
     }
 
-    /// Add seat focus for the window.
+    /// Add seat focus for the window, along with the keys pressed on the seat.
     #[inline]
-    pub fn add_seat_focus(&mut self, seat: ObjectId) {
-        self.seat_focus.insert(seat);
+    pub fn add_seat_focus(&mut self, seat: ObjectId, pressed_keys: Vec<PhysicalKey>) {
+        self.seat_focus.insert(seat, pressed_keys);
+    }
+
+    /// Update the keys pressed on a seat which has focus on the window.
+    pub fn update_pressed_key(&mut self, seat: &ObjectId, key: PhysicalKey, state: ElementState) {
+        if let Some(pressed_keys) = self.seat_focus.get_mut(seat) {
+            wayland::update_pressed(pressed_keys, key, state);
+        }
+    }
+
+    /// The keys pressed on the seats which have focus on the window.
+    pub fn pressed_keys(&self) -> Vec<PhysicalKey> {
+        let mut pressed_keys = Vec::new();
+        for key in self.seat_focus.values().flatten() {
+            if !pressed_keys.contains(key) {
+                pressed_keys.push(*key);
+            }
+        }
+        pressed_keys
+    }
+
+    /// The surface local position of the first pointer over the window.
+    pub fn pointer_position(&self) -> Option<LogicalPosition<f64>> {
+        self.pointers
+            .iter()
+            .filter_map(Weak::upgrade)
+            .find_map(|pointer| pointer.pointer().winit_data().position())
+    }
+
+    /// The buttons pressed on the pointers over the window.
+    pub fn pressed_buttons(&self) -> Vec<MouseButton> {
+        let mut pressed_buttons = Vec::new();
+        for pointer in self.pointers.iter().filter_map(Weak::upgrade) {
+            for button in pointer.pointer().winit_data().pressed_buttons() {
+                if !pressed_buttons.contains(&button) {
+                    pressed_buttons.push(button);
+                }
+            }
+        }
+        pressed_buttons
     }
 
     /// Remove seat focus from the window.
@@ -1005,6 +1287,14 @@ impl WindowState {
         if let Some(frame) = self.frame.as_mut() {
             frame.set_scaling_factor(scale_factor);
         }
+
+        // Rectangles in physical pixels cover another part of the surface at the new scale.
+        if self.cursor_grab_mode.confine_region.is_some() {
+            self.reload_cursor_confine_region();
+        }
+        if self.input_region.is_some() {
+            let _ = self.reload_input_region();
+        }
     }
 
     /// Make window background blurred
@@ -1024,6 +1314,27 @@ impl WindowState {
         }
     }
 
+    /// Set whether the dialog is modal.
+    pub fn set_modal(&mut self, modal: bool) -> Result<(), RequestError> {
+        if self.dialog.is_none() {
+            let toplevel = self.toplevel()?;
+            let dialog_manager = self
+                .dialog_manager
+                .as_ref()
+                .ok_or_else(|| NotSupportedError::new("xdg_wm_dialog_v1 is not available"))?;
+            self.dialog = Some(dialog_manager.dialog(toplevel.xdg_toplevel(), &self.queue_handle));
+        }
+
+        let dialog = self.dialog.as_ref().unwrap();
+        if modal {
+            dialog.set_modal();
+        } else {
+            dialog.unset_modal();
+        }
+
+        Ok(())
+    }
+
     /// Set the window title to a new value.
     ///
     /// This will automatically truncate the title to something meaningful.
@@ -1043,7 +1354,9 @@ impl WindowState {
             frame.set_title(&title);
         }
 
-        self.window.set_title(&title);
+        if let Some(window) = self.window.toplevel() {
+            window.set_title(&title);
+        }
         self.title = title;
     }
 
@@ -1070,6 +1383,18 @@ impl WindowState {
         }
     }
 
+    /// The toplevel, for requests which don't apply to popups.
+    fn toplevel(&self) -> Result<&Window, RequestError> {
+        self.window
+            .toplevel()
+            .ok_or_else(|| NotSupportedError::new("the request only applies to toplevels").into())
+    }
+
+    /// The position of the window geometry on the surface, which popups are placed relative to.
+    pub fn window_geometry_position(&self) -> LogicalPosition<i32> {
+        self.frame.as_ref().map_or((0, 0), |frame| frame.location()).into()
+    }
+
     /// Get the cached title.
     #[inline]
     pub fn title(&self) -> &str {
@@ -1083,6 +1408,10 @@ impl Drop for WindowState {
             blur.release();
         }
 
+        if let Some(dialog) = self.dialog.take() {
+            dialog.destroy();
+        }
+
         if let Some(fs) = self.fractional_scale.take() {
             fs.destroy();
         }
@@ -1097,18 +1426,25 @@ impl Drop for WindowState {
 }
 
 /// The state of the cursor grabs.
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 struct GrabState {
     /// The grab mode requested by the user.
     user_grab_mode: CursorGrabMode,
 
     /// The current grab mode.
     current_grab_mode: CursorGrabMode,
+
+    /// The region the cursor is confined to, the whole surface when `None`.
+    confine_region: Option<Vec<Rect>>,
 }
 
 impl GrabState {
     fn new() -> Self {
-        Self { user_grab_mode: CursorGrabMode::None, current_grab_mode: CursorGrabMode::None }
+        Self {
+            user_grab_mode: CursorGrabMode::None,
+            current_grab_mode: CursorGrabMode::None,
+            confine_region: None,
+        }
     }
 }
 
@@ -1139,6 +1475,21 @@ impl From<ResizeDirection> for XdgResizeEdge {
     }
 }
 
+/// Attach the image of a custom cursor to its surface, returning the scale of the surface.
+fn commit_custom_cursor(surface: &WlSurface, cursor: &CustomCursor) -> i32 {
+    let scale = surface.data::<SurfaceData>().unwrap().surface_data().scale_factor();
+
+    surface.set_buffer_scale(scale);
+    surface.attach(Some(cursor.buffer.wl_buffer()), 0, 0);
+    if surface.version() >= 4 {
+        surface.damage_buffer(0, 0, cursor.w, cursor.h);
+    } else {
+        surface.damage(0, 0, cursor.w / scale, cursor.h / scale);
+    }
+    surface.commit();
+    scale
+}
+
 // NOTE: Rust doesn't allow `From<Option<Theme>>`.
 #[cfg(feature = "sctk-adwaita")]
 fn into_sctk_adwaita_config(theme: Option<Theme>) -> sctk_adwaita::FrameConfig {
@@ -13,7 +13,7 @@ macro_rules! atom_manager {
 
         /// Indices into the `Atoms` struct.
         #[derive(Copy, Clone, Debug)]
-        #[allow(non_camel_case_types)]
+        #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
         pub enum AtomName {
             $($name,)*
         }
@@ -57,6 +57,7 @@ atom_manager! {
     _NET_WM_STATE_HIDDEN,
     _NET_WM_STATE_MAXIMIZED_HORZ,
     _NET_WM_STATE_MAXIMIZED_VERT,
+    _NET_WM_STATE_MODAL,
     _NET_WM_WINDOW_TYPE,
 
     // Activation atoms.
@@ -87,13 +88,31 @@ atom_manager! {
     XdndDrop,
     XdndPosition,
     XdndStatus,
-    XdndActionPrivate,
+    XdndActionCopy,
+    XdndActionList,
+    XdndActionLink,
+    XdndActionMove,
     XdndSelection,
     XdndFinished,
     XdndTypeList,
     TextUriList: b"text/uri-list",
     None: b"None",
 
+    // Selection Atoms
+    ATOM_PAIR,
+    CLIPBOARD,
+    CLIPBOARD_MANAGER,
+    INCR,
+    MULTIPLE,
+    PRIMARY,
+    NULL,
+    SAVE_TARGETS,
+    TARGETS,
+    TEXT,
+    TextPlain: b"text/plain",
+    TextPlainUtf8: b"text/plain;charset=utf-8",
+    _WINIT_SELECTION,
+
     // Miscellaneous Atoms
     _GTK_THEME_VARIANT,
     _MOTIF_WM_HINTS,
@@ -103,7 +122,21 @@ atom_manager! {
     _NET_SUPPORTED,
     _NET_SUPPORTING_WM_CHECK,
     _XEMBED,
-    _XSETTINGS_SETTINGS
+    _XSETTINGS_SETTINGS,
+
+    // Valuator Labels
+    AbsPressure: b"Abs Pressure",
+    AbsTiltX: b"Abs Tilt X",
+    AbsTiltY: b"Abs Tilt Y",
+    AbsDistance: b"Abs Distance",
+    AbsRotationZ: b"Abs Rotation Z",
+    AbsRotationX: b"Abs Rotation X",
+    AbsRotationY: b"Abs Rotation Y",
+    AbsWheel: b"Abs Wheel",
+    AbsThrottle: b"Abs Throttle",
+
+    // Input Device Properties
+    DeviceProductId: b"Device Product ID"
 }
 
 impl Index<AtomName> for Atoms {
@@ -0,0 +1,914 @@
+//! Clipboard and primary selection support on top of X11 selections.
+//!
+//! Selections are owned by a hidden window, so the contents outlive the window that set them and
+//! can be handed over to the clipboard manager when the event loop exits. Transfers are fully
+//! asynchronous: conversions are requested from the selection owner and the data is picked up
+//! when the corresponding `SelectionNotify` and `PropertyNotify` events arrive.
+//!
+//! The atoms of MIME types are interned ahead of the requests needing them, so their replies have
+//! already arrived once the owner answers and picking them up doesn't take a round trip.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use x11rb::connection::{Connection, DiscardMode, RequestConnection, RequestKind, SequenceNumber};
+use x11rb::cookie::Cookie;
+use x11rb::protocol::xproto::{self, ConnectionExt};
+use x11rb::x11_utils::TryParse;
+
+use super::atoms::*;
+use super::{CookieResultExt, X11Error, XConnection};
+use crate::clipboard::{self, ClipboardData, ClipboardError};
+use crate::event_loop::AsyncRequestSerial;
+use crate::window::WindowId;
+
+/// Upper bound of a single property write, larger data is sent with the `INCR` protocol.
+const MAX_CHUNK_SIZE: usize = 256 * 1024;
+
+/// The time the owner of a selection has to answer a conversion, or to send the next chunk of an
+/// `INCR` transfer, before the read fails.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A request sent from a window to the event loop.
+#[derive(Debug)]
+pub enum ClipboardRequest {
+    Set {
+        selection: xproto::Atom,
+        data: Vec<ClipboardData>,
+    },
+    Read {
+        window_id: WindowId,
+        serial: AsyncRequestSerial,
+        selection: xproto::Atom,
+        mime_types: Vec<String>,
+    },
+}
+
+/// A finished read which should be delivered as [`WindowEvent::ClipboardDone`].
+///
+/// [`WindowEvent::ClipboardDone`]: crate::event::WindowEvent::ClipboardDone
+pub type ClipboardDone = (WindowId, AsyncRequestSerial, Result<ClipboardData, ClipboardError>);
+
+#[derive(Debug)]
+struct PendingRead {
+    window_id: WindowId,
+    serial: AsyncRequestSerial,
+    selection: xproto::Atom,
+    mime_types: Vec<String>,
+    /// The targets offered by the owner, when they're known without asking for `TARGETS`.
+    targets: Option<Vec<xproto::Atom>>,
+    stage: ReadStage,
+    /// When the read fails if the owner doesn't answer, set while waiting for it.
+    deadline: Option<Instant>,
+}
+
+#[derive(Debug)]
+enum ReadStage {
+    /// The conversion has not been requested yet.
+    Queued,
+    /// Waiting for the list of targets offered by the owner.
+    Targets,
+    /// Waiting for the data converted to `target`, which is delivered as `mime_type`.
+    Data { mime_type: String, target: xproto::Atom },
+    /// Accumulating the chunks of an `INCR` transfer.
+    Incr { mime_type: String, data: Vec<u8> },
+}
+
+/// A reply requested ahead of the time it's needed.
+#[derive(Debug)]
+enum Prefetched<T> {
+    Pending(SequenceNumber),
+    Ready(Option<T>),
+}
+
+impl<T> Prefetched<T> {
+    fn pending(&self) -> Option<SequenceNumber> {
+        match *self {
+            Self::Pending(sequence) => Some(sequence),
+            Self::Ready(_) => None,
+        }
+    }
+}
+
+/// An outgoing `INCR` transfer to another client.
+#[derive(Debug)]
+struct IncrTransfer {
+    requestor: xproto::Window,
+    property: xproto::Atom,
+    target: xproto::Atom,
+    data: Vec<u8>,
+    offset: usize,
+}
+
+pub struct Clipboard {
+    xconn: Arc<XConnection>,
+    /// The hidden window owning our selections.
+    window: xproto::Window,
+    /// The contents of the selections that we currently own.
+    owned: HashMap<xproto::Atom, Vec<ClipboardData>>,
+    /// Reads are processed one at a time, since they share the transfer property.
+    reads: VecDeque<PendingRead>,
+    transfers: Vec<IncrTransfer>,
+    completed: Vec<ClipboardDone>,
+    /// Whether we're waiting for the clipboard manager to take over the contents.
+    saving: bool,
+    atoms: HashMap<String, Prefetched<xproto::Atom>>,
+    atom_names: HashMap<xproto::Atom, Prefetched<String>>,
+}
+
+impl Clipboard {
+    pub fn new(xconn: Arc<XConnection>) -> Result<Self, X11Error> {
+        let screen = xconn.default_root();
+        let window = xconn.xcb_connection().generate_id()?;
+        xconn
+            .xcb_connection()
+            .create_window(
+                x11rb::COPY_DEPTH_FROM_PARENT,
+                window,
+                screen.root,
+                0,
+                0,
+                1,
+                1,
+                0,
+                xproto::WindowClass::INPUT_ONLY,
+                x11rb::COPY_FROM_PARENT,
+                &xproto::CreateWindowAux::new().event_mask(xproto::EventMask::PROPERTY_CHANGE),
+            )?
+            .check()?;
+
+        Ok(Self {
+            xconn,
+            window,
+            owned: HashMap::new(),
+            reads: VecDeque::new(),
+            transfers: Vec::new(),
+            completed: Vec::new(),
+            saving: false,
+            atoms: HashMap::new(),
+            atom_names: HashMap::new(),
+        })
+    }
+
+    /// The hidden window used for all selection traffic.
+    pub fn window(&self) -> xproto::Window {
+        self.window
+    }
+
+    pub fn handle_request(&mut self, request: ClipboardRequest) {
+        match request {
+            ClipboardRequest::Set { selection, data } => self.set_selection(selection, data),
+            ClipboardRequest::Read { window_id, serial, selection, mime_types } => {
+                self.queue_read(window_id, serial, selection, mime_types, None)
+            },
+        }
+    }
+
+    /// Read a selection whose targets are already known, like the `XdndSelection` of which the
+    /// targets are announced by the drag source.
+    pub fn read_offer(
+        &mut self,
+        window_id: WindowId,
+        serial: AsyncRequestSerial,
+        selection: xproto::Atom,
+        mime_types: Vec<String>,
+        targets: Vec<xproto::Atom>,
+    ) {
+        self.queue_read(window_id, serial, selection, mime_types, Some(targets));
+    }
+
+    fn queue_read(
+        &mut self,
+        window_id: WindowId,
+        serial: AsyncRequestSerial,
+        selection: xproto::Atom,
+        mime_types: Vec<String>,
+        targets: Option<Vec<xproto::Atom>>,
+    ) {
+        self.prefetch_atoms(mime_types.iter().map(String::as_str));
+        self.reads.push_back(PendingRead {
+            window_id,
+            serial,
+            selection,
+            mime_types,
+            targets,
+            stage: ReadStage::Queued,
+            deadline: None,
+        });
+        self.start_next_read();
+    }
+
+    /// Whether a read of the selection is in progress.
+    pub fn is_reading(&self, selection: xproto::Atom) -> bool {
+        self.reads.iter().any(|read| read.selection == selection)
+    }
+
+    /// Takes the reads that finished since the last call.
+    pub fn take_completed(&mut self) -> Vec<ClipboardDone> {
+        std::mem::take(&mut self.completed)
+    }
+
+    /// Whether reads finished since the last call to [`Self::take_completed`].
+    pub fn has_completed(&self) -> bool {
+        !self.completed.is_empty()
+    }
+
+    /// When the current read fails if its owner doesn't answer.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.reads.front().and_then(|read| read.deadline)
+    }
+
+    /// Fail the current read if its owner didn't answer in time, and move on to the next one.
+    pub fn fail_expired_read(&mut self, now: Instant) {
+        if self.next_deadline().is_some_and(|deadline| deadline <= now) {
+            tracing::warn!("Timed out while reading the selection");
+            let atoms = self.xconn.atoms();
+            self.xconn
+                .xcb_connection()
+                .delete_property(self.window, atoms[_WINIT_SELECTION])
+                .expect_then_ignore_error("Failed to delete the selection property");
+            self.finish_read(Err(ClipboardError::TransferFailed));
+        }
+    }
+
+    fn set_selection(&mut self, selection: xproto::Atom, data: Vec<ClipboardData>) {
+        // Intern the atoms now, so they're known by the time other clients ask for the contents.
+        self.prefetch_atoms(data.iter().map(|entry| entry.mime_type.as_str()));
+
+        // Ownership isn't confirmed with a round trip. With the time of the latest event, taking
+        // the selection only fails when another client took it in the meantime, and losing it
+        // later is reported with `SelectionClear` anyway.
+        let owner = if data.is_empty() { x11rb::NONE } else { self.window };
+        let time = self.xconn.timestamp();
+        self.xconn
+            .xcb_connection()
+            .set_selection_owner(owner, selection, time)
+            .expect_then_ignore_error("Failed to set the selection owner");
+        self.flush();
+
+        if data.is_empty() {
+            self.owned.remove(&selection);
+        } else {
+            self.owned.insert(selection, data);
+        }
+    }
+
+    /// Another client took over the selection.
+    pub fn handle_selection_clear(&mut self, selection: xproto::Atom) {
+        self.owned.remove(&selection);
+    }
+
+    /// Another client wants the contents of a selection that we own.
+    pub fn handle_selection_request(
+        &mut self,
+        requestor: xproto::Window,
+        selection: xproto::Atom,
+        target: xproto::Atom,
+        property: xproto::Atom,
+        time: xproto::Timestamp,
+    ) {
+        // Obsolete clients don't specify a property.
+        let property = if property == x11rb::NONE { target } else { property };
+
+        let atoms = self.xconn.atoms();
+        let converted = if target == atoms[MULTIPLE] {
+            self.convert_multiple(requestor, selection, property)
+        } else {
+            self.convert(requestor, selection, target, property)
+        };
+
+        let event = xproto::SelectionNotifyEvent {
+            response_type: xproto::SELECTION_NOTIFY_EVENT,
+            sequence: 0,
+            time,
+            requestor,
+            selection,
+            target,
+            property: if converted { property } else { x11rb::NONE },
+        };
+        self.xconn
+            .xcb_connection()
+            .send_event(false, requestor, xproto::EventMask::NO_EVENT, event)
+            .expect_then_ignore_error("Failed to send the SelectionNotify event");
+        self.flush();
+    }
+
+    /// Handle a `MULTIPLE` request, which holds a list of target and property pairs.
+    fn convert_multiple(
+        &mut self,
+        requestor: xproto::Window,
+        selection: xproto::Atom,
+        property: xproto::Atom,
+    ) -> bool {
+        let atom_pair = self.xconn.atoms()[ATOM_PAIR];
+        let mut pairs =
+            match self.xconn.get_property::<xproto::Atom>(requestor, property, atom_pair) {
+                Ok(pairs) => pairs,
+                Err(_) => return false,
+            };
+
+        for pair in pairs.chunks_exact_mut(2) {
+            if !self.convert(requestor, selection, pair[0], pair[1]) {
+                pair[1] = x11rb::NONE;
+            }
+        }
+
+        self.xconn
+            .change_property(requestor, property, atom_pair, xproto::PropMode::REPLACE, &pairs)
+            .expect_then_ignore_error("Failed to update the MULTIPLE property");
+        true
+    }
+
+    /// Write the selection converted to `target` into `property` of the requestor.
+    fn convert(
+        &mut self,
+        requestor: xproto::Window,
+        selection: xproto::Atom,
+        target: xproto::Atom,
+        property: xproto::Atom,
+    ) -> bool {
+        if !self.owned.contains_key(&selection) {
+            return false;
+        }
+
+        let xconn = Arc::clone(&self.xconn);
+        let atoms = xconn.atoms();
+        if target == atoms[TARGETS] {
+            let mut targets = vec![atoms[TARGETS], atoms[MULTIPLE], atoms[SAVE_TARGETS]];
+            targets.extend(self.data_targets(selection));
+
+            return self
+                .xconn
+                .change_property(
+                    requestor,
+                    property,
+                    xproto::AtomEnum::ATOM.into(),
+                    xproto::PropMode::REPLACE,
+                    &targets,
+                )
+                .map(|cookie| cookie.ignore_error())
+                .is_ok();
+        }
+
+        if target == atoms[SAVE_TARGETS] {
+            // The clipboard manager acknowledges the request with an empty property.
+            return self
+                .xconn
+                .change_property::<u8>(
+                    requestor,
+                    property,
+                    atoms[NULL],
+                    xproto::PropMode::REPLACE,
+                    &[],
+                )
+                .map(|cookie| cookie.ignore_error())
+                .is_ok();
+        }
+
+        let mime_types: Vec<String> =
+            self.owned[&selection].iter().map(|entry| entry.mime_type.clone()).collect();
+        let name = mime_types.into_iter().find(|mime_type| self.atom(mime_type) == Some(target));
+        let name = match name {
+            Some(name) => name,
+            // Text is offered in several encodings.
+            None if [
+                atoms[UTF8_STRING],
+                atoms[TEXT],
+                atoms[TextPlainUtf8],
+                atoms[TextPlain],
+                xproto::AtomEnum::STRING.into(),
+            ]
+            .contains(&target) =>
+            {
+                String::from(ClipboardData::TEXT_MIME_TYPE)
+            },
+            None => return false,
+        };
+        let Some(entry) = clipboard::find_data(&self.owned[&selection], &name) else {
+            return false;
+        };
+
+        // `TEXT` lets the owner pick the encoding, we always answer with UTF-8.
+        let (data, property_type) = if target == xproto::AtomEnum::STRING.into() {
+            (clipboard::utf8_to_latin1(&entry.data), target)
+        } else if target == atoms[TEXT] {
+            (entry.data.clone(), atoms[UTF8_STRING])
+        } else {
+            (entry.data.clone(), target)
+        };
+
+        if data.len() > self.chunk_size() {
+            self.start_incr_transfer(requestor, property, property_type, data)
+        } else {
+            self.xconn
+                .change_property(
+                    requestor,
+                    property,
+                    property_type,
+                    xproto::PropMode::REPLACE,
+                    &data,
+                )
+                .map(|cookie| cookie.ignore_error())
+                .is_ok()
+        }
+    }
+
+    /// The targets the contents of an owned selection can be converted to.
+    pub fn data_targets(&mut self, selection: xproto::Atom) -> Vec<xproto::Atom> {
+        let mime_types: Vec<String> = match self.owned.get(&selection) {
+            Some(contents) => contents.iter().map(|d| d.mime_type.clone()).collect(),
+            None => return Vec::new(),
+        };
+
+        let mut targets: Vec<_> =
+            mime_types.iter().filter_map(|mime_type| self.atom(mime_type)).collect();
+        if mime_types.iter().any(|mime_type| clipboard::is_text_mime_type(mime_type)) {
+            let atoms = self.xconn.atoms();
+            targets.extend([
+                atoms[UTF8_STRING],
+                atoms[TEXT],
+                xproto::AtomEnum::STRING.into(),
+                atoms[TextPlain],
+            ]);
+        }
+
+        let mut seen = Vec::with_capacity(targets.len());
+        targets.retain(|target| {
+            let new = !seen.contains(target);
+            seen.push(*target);
+            new
+        });
+        targets
+    }
+
+    fn start_incr_transfer(
+        &mut self,
+        requestor: xproto::Window,
+        property: xproto::Atom,
+        target: xproto::Atom,
+        data: Vec<u8>,
+    ) -> bool {
+        let atoms = self.xconn.atoms();
+
+        // We need to know when the requestor deletes the property to send the next chunk.
+        let aux =
+            xproto::ChangeWindowAttributesAux::new().event_mask(xproto::EventMask::PROPERTY_CHANGE);
+        if self.xconn.xcb_connection().change_window_attributes(requestor, &aux).is_err() {
+            return false;
+        }
+
+        let len = u32::try_from(data.len()).unwrap_or(u32::MAX);
+        if self
+            .xconn
+            .change_property(requestor, property, atoms[INCR], xproto::PropMode::REPLACE, &[len])
+            .is_err()
+        {
+            return false;
+        }
+
+        self.transfers.retain(|t| t.requestor != requestor || t.property != property);
+        self.transfers.push(IncrTransfer { requestor, property, target, data, offset: 0 });
+        true
+    }
+
+    /// A property changed on our hidden window or on a requestor of an `INCR` transfer.
+    pub fn handle_property_notify(
+        &mut self,
+        window: xproto::Window,
+        property: xproto::Atom,
+        deleted: bool,
+    ) {
+        if window == self.window {
+            if !deleted && property == self.xconn.atoms()[_WINIT_SELECTION] {
+                self.continue_incr_read();
+            }
+            return;
+        }
+
+        if !deleted {
+            return;
+        }
+
+        let chunk_size = self.chunk_size();
+        let Some(index) =
+            self.transfers.iter().position(|t| t.requestor == window && t.property == property)
+        else {
+            return;
+        };
+
+        let transfer = &mut self.transfers[index];
+        let end = (transfer.offset + chunk_size).min(transfer.data.len());
+        let chunk = &transfer.data[transfer.offset..end];
+        let finished = chunk.is_empty();
+        self.xconn
+            .change_property(
+                transfer.requestor,
+                transfer.property,
+                transfer.target,
+                xproto::PropMode::REPLACE,
+                chunk,
+            )
+            .expect_then_ignore_error("Failed to send the clipboard contents");
+        transfer.offset = end;
+
+        // The empty chunk terminates the transfer.
+        if finished {
+            let transfer = self.transfers.swap_remove(index);
+            let aux =
+                xproto::ChangeWindowAttributesAux::new().event_mask(xproto::EventMask::NO_EVENT);
+            self.xconn
+                .xcb_connection()
+                .change_window_attributes(transfer.requestor, &aux)
+                .expect_then_ignore_error("Failed to reset the event mask of the requestor");
+        }
+        self.flush();
+    }
+
+    /// The owner of a selection answered a conversion request.
+    pub fn handle_selection_notify(
+        &mut self,
+        requestor: xproto::Window,
+        selection: xproto::Atom,
+        target: xproto::Atom,
+        property: xproto::Atom,
+    ) {
+        if requestor != self.window {
+            return;
+        }
+
+        let atoms = self.xconn.atoms();
+        if selection == atoms[CLIPBOARD_MANAGER] {
+            self.saving = false;
+            return;
+        }
+
+        let Some(read) = self.reads.front() else {
+            return;
+        };
+        // Answers to reads which timed out are ignored.
+        let requested = match read.stage {
+            ReadStage::Targets => atoms[TARGETS],
+            ReadStage::Data { target, .. } => target,
+            _ => return,
+        };
+        if read.selection != selection || requested != target {
+            return;
+        }
+
+        match read.stage {
+            // Without an owner, the server refuses the conversion itself.
+            ReadStage::Targets if property == x11rb::NONE => {
+                self.finish_read(Err(ClipboardError::Empty))
+            },
+            ReadStage::Targets => {
+                match self.take_property::<xproto::Atom>(xproto::AtomEnum::ATOM.into()) {
+                    Some(targets) => self.select_target(targets),
+                    None => self.finish_read(Err(ClipboardError::TransferFailed)),
+                }
+            },
+            _ if property == x11rb::NONE => self.finish_read(Err(ClipboardError::TransferFailed)),
+            _ => self.read_data(),
+        }
+    }
+
+    fn start_next_read(&mut self) {
+        let Some(read) = self.reads.front_mut() else {
+            return;
+        };
+        if !matches!(read.stage, ReadStage::Queued) {
+            return;
+        }
+
+        if read.mime_types.is_empty() {
+            self.finish_read(Err(ClipboardError::NoMatchingMimeType));
+            return;
+        }
+
+        // Reading our own selection doesn't need a round trip through the server.
+        if let Some(contents) = self.owned.get(&read.selection) {
+            let result = read
+                .mime_types
+                .iter()
+                .find_map(|mime_type| {
+                    clipboard::find_data(contents, mime_type)
+                        .map(|entry| ClipboardData::new(mime_type.clone(), entry.data.clone()))
+                })
+                .ok_or(ClipboardError::NoMatchingMimeType);
+            self.finish_read(result);
+            return;
+        }
+
+        match read.targets.take() {
+            // Nothing is offered outside of a drag.
+            Some(targets) if targets.is_empty() => {
+                self.finish_read(Err(ClipboardError::Empty));
+                return;
+            },
+            Some(targets) => {
+                self.select_target(targets);
+                return;
+            },
+            None => (),
+        }
+
+        let selection = read.selection;
+        read.stage = ReadStage::Targets;
+        read.deadline = Some(Instant::now() + READ_TIMEOUT);
+        let atoms = self.xconn.atoms();
+        self.convert_selection(selection, atoms[TARGETS]);
+    }
+
+    /// Pick the best target offered by the owner and request the conversion.
+    fn select_target(&mut self, targets: Vec<xproto::Atom>) {
+        let atoms = self.xconn.atoms();
+
+        let text_targets = [
+            atoms[UTF8_STRING],
+            atoms[TextPlainUtf8],
+            atoms[TextPlain],
+            xproto::AtomEnum::STRING.into(),
+        ];
+        let mime_types = self.reads[0].mime_types.clone();
+        let selected = mime_types.into_iter().find_map(|mime_type| {
+            let atom = self.atom(&mime_type)?;
+            if targets.contains(&atom) {
+                return Some((mime_type, atom));
+            }
+
+            if clipboard::is_text_mime_type(&mime_type) {
+                let target = text_targets.into_iter().find(|target| targets.contains(target))?;
+                return Some((mime_type, target));
+            }
+
+            None
+        });
+
+        let Some((mime_type, target)) = selected else {
+            self.finish_read(Err(ClipboardError::NoMatchingMimeType));
+            return;
+        };
+
+        let read = &mut self.reads[0];
+        let selection = read.selection;
+        read.stage = ReadStage::Data { mime_type, target };
+        read.deadline = Some(Instant::now() + READ_TIMEOUT);
+        self.convert_selection(selection, target);
+    }
+
+    fn read_data(&mut self) {
+        let atoms = self.xconn.atoms();
+        let reply = self
+            .xconn
+            .xcb_connection()
+            .get_property(
+                true,
+                self.window,
+                atoms[_WINIT_SELECTION],
+                xproto::AtomEnum::ANY,
+                0,
+                u32::MAX / 4,
+            )
+            .ok()
+            .and_then(|cookie| cookie.reply().ok());
+        let Some(reply) = reply else {
+            self.finish_read(Err(ClipboardError::TransferFailed));
+            return;
+        };
+
+        let read = &mut self.reads[0];
+        let ReadStage::Data { mime_type, .. } = &mut read.stage else {
+            return;
+        };
+
+        if reply.type_ == atoms[INCR] {
+            // Deleting the property above tells the owner to start sending chunks.
+            read.stage = ReadStage::Incr { mime_type: std::mem::take(mime_type), data: Vec::new() };
+            read.deadline = Some(Instant::now() + READ_TIMEOUT);
+            self.flush();
+            return;
+        }
+
+        let data = decode(reply.type_, reply.value, mime_type);
+        let mime_type = std::mem::take(mime_type);
+        self.finish_read(Ok(ClipboardData::new(mime_type, data)));
+    }
+
+    fn continue_incr_read(&mut self) {
+        if !matches!(self.reads.front(), Some(PendingRead { stage: ReadStage::Incr { .. }, .. })) {
+            return;
+        }
+
+        let atoms = self.xconn.atoms();
+        let reply = self
+            .xconn
+            .xcb_connection()
+            .get_property(
+                true,
+                self.window,
+                atoms[_WINIT_SELECTION],
+                xproto::AtomEnum::ANY,
+                0,
+                u32::MAX / 4,
+            )
+            .ok()
+            .and_then(|cookie| cookie.reply().ok());
+        let Some(reply) = reply else {
+            self.finish_read(Err(ClipboardError::TransferFailed));
+            return;
+        };
+
+        let read = &mut self.reads[0];
+        let ReadStage::Incr { mime_type, data, .. } = &mut read.stage else {
+            return;
+        };
+
+        // The empty chunk marks the end of the transfer.
+        if reply.value.is_empty() {
+            let data = decode(reply.type_, std::mem::take(data), mime_type);
+            let mime_type = std::mem::take(mime_type);
+            self.finish_read(Ok(ClipboardData::new(mime_type, data)));
+        } else {
+            data.extend_from_slice(&reply.value);
+            read.deadline = Some(Instant::now() + READ_TIMEOUT);
+            self.flush();
+        }
+    }
+
+    fn finish_read(&mut self, result: Result<ClipboardData, ClipboardError>) {
+        if let Some(read) = self.reads.pop_front() {
+            self.completed.push((read.window_id, read.serial, result));
+        }
+        self.start_next_read();
+    }
+
+    fn convert_selection(&self, selection: xproto::Atom, target: xproto::Atom) {
+        let atoms = self.xconn.atoms();
+        self.xconn
+            .xcb_connection()
+            .convert_selection(
+                self.window,
+                selection,
+                target,
+                atoms[_WINIT_SELECTION],
+                self.xconn.timestamp(),
+            )
+            .expect_then_ignore_error("Failed to request the selection conversion");
+        self.flush();
+    }
+
+    /// Read and delete the transfer property of the hidden window.
+    fn take_property<T: bytemuck::Pod>(&self, property_type: xproto::Atom) -> Option<Vec<T>> {
+        let atoms = self.xconn.atoms();
+        let data = self.xconn.get_property(self.window, atoms[_WINIT_SELECTION], property_type);
+        self.xconn
+            .xcb_connection()
+            .delete_property(self.window, atoms[_WINIT_SELECTION])
+            .expect_then_ignore_error("Failed to delete the selection property");
+        data.ok()
+    }
+
+    /// Ask the clipboard manager to take over the contents of the `CLIPBOARD` selection.
+    ///
+    /// Returns whether the handover was started, completion is reported by
+    /// [`Self::is_saving`].
+    pub fn save_to_manager(&mut self) -> bool {
+        let atoms = self.xconn.atoms();
+        if !self.owned.contains_key(&atoms[CLIPBOARD]) {
+            return false;
+        }
+
+        // Without a clipboard manager, the server refuses the conversion right away.
+        self.saving = true;
+        self.convert_selection(atoms[CLIPBOARD_MANAGER], atoms[SAVE_TARGETS]);
+        true
+    }
+
+    pub fn is_saving(&self) -> bool {
+        self.saving
+    }
+
+    /// Request the atoms of the names, without waiting for the replies.
+    fn prefetch_atoms<'a>(&mut self, names: impl IntoIterator<Item = &'a str>) {
+        for name in names {
+            if self.atoms.contains_key(name) {
+                continue;
+            }
+
+            let atom = match self.xconn.xcb_connection().intern_atom(false, name.as_bytes()) {
+                Ok(cookie) => Prefetched::Pending(keep_reply(cookie)),
+                Err(_) => Prefetched::Ready(None),
+            };
+            self.atoms.insert(name.to_owned(), atom);
+        }
+    }
+
+    /// Request the names of the atoms, without waiting for the replies.
+    pub fn prefetch_atom_names(&mut self, atoms: &[xproto::Atom]) {
+        for &atom in atoms {
+            if self.atom_names.contains_key(&atom) {
+                continue;
+            }
+
+            let name = match self.xconn.xcb_connection().get_atom_name(atom) {
+                Ok(cookie) => Prefetched::Pending(keep_reply(cookie)),
+                Err(_) => Prefetched::Ready(None),
+            };
+            self.atom_names.insert(atom, name);
+        }
+    }
+
+    /// The atom of the name, which only takes a round trip when it wasn't prefetched.
+    fn atom(&mut self, name: &str) -> Option<xproto::Atom> {
+        self.prefetch_atoms([name]);
+        let atom = self.atoms.get_mut(name)?;
+        let atom = resolve(&self.xconn, atom, |reply: xproto::InternAtomReply| Some(reply.atom));
+        if let Some(atom) = atom {
+            self.atom_names.entry(atom).or_insert_with(|| Prefetched::Ready(Some(name.to_owned())));
+        }
+        atom
+    }
+
+    /// The name of the atom, which only takes a round trip when it wasn't prefetched.
+    pub fn atom_name(&mut self, atom: xproto::Atom) -> Option<String> {
+        self.prefetch_atom_names(&[atom]);
+        let name = self.atom_names.get_mut(&atom)?;
+        resolve(&self.xconn, name, |reply: xproto::GetAtomNameReply| {
+            String::from_utf8(reply.name).ok()
+        })
+    }
+
+    fn chunk_size(&self) -> usize {
+        // Leave some room for the request header.
+        let max_request = self.xconn.xcb_connection().maximum_request_bytes() - 64;
+        max_request.min(MAX_CHUNK_SIZE)
+    }
+
+    pub fn flush(&self) {
+        if let Err(err) = self.xconn.xcb_connection().flush() {
+            tracing::warn!("Failed to flush the X11 connection: {err}");
+        }
+    }
+}
+
+impl Drop for Clipboard {
+    fn drop(&mut self) {
+        // Replies which were never picked up would stay around in the connection.
+        let conn = self.xconn.xcb_connection();
+        let atoms = self.atoms.values().filter_map(Prefetched::pending);
+        let names = self.atom_names.values().filter_map(Prefetched::pending);
+        for sequence in atoms.chain(names) {
+            conn.discard_reply(
+                sequence,
+                RequestKind::HasResponse,
+                DiscardMode::DiscardReplyAndError,
+            );
+        }
+
+        self.xconn
+            .xcb_connection()
+            .destroy_window(self.window)
+            .expect_then_ignore_error("Failed to destroy the clipboard window");
+    }
+}
+
+/// Keep the reply of a request around, to pick it up later with [`resolve`].
+fn keep_reply<C: RequestConnection, R: TryParse>(cookie: Cookie<'_, C, R>) -> SequenceNumber {
+    let sequence = cookie.sequence_number();
+    // Dropping the cookie would discard the reply.
+    std::mem::forget(cookie);
+    sequence
+}
+
+/// Pick up the reply of a prefetched request, which only blocks when it hasn't arrived yet.
+fn resolve<R: TryParse, T: Clone>(
+    xconn: &XConnection,
+    prefetched: &mut Prefetched<T>,
+    map: impl FnOnce(R) -> Option<T>,
+) -> Option<T> {
+    if let Prefetched::Pending(sequence) = *prefetched {
+        let value = xconn
+            .xcb_connection()
+            .wait_for_reply_or_error(sequence)
+            .ok()
+            .and_then(|reply| R::try_parse(&reply).ok().map(|(reply, _)| reply))
+            .and_then(map);
+        *prefetched = Prefetched::Ready(value);
+    }
+
+    match prefetched {
+        Prefetched::Ready(value) => value.clone(),
+        Prefetched::Pending(_) => unreachable!(),
+    }
+}
+
+/// Convert Latin-1 encoded `STRING` data to UTF-8 when text was requested.
+fn decode(property_type: xproto::Atom, data: Vec<u8>, mime_type: &str) -> Vec<u8> {
+    if property_type == xproto::Atom::from(xproto::AtomEnum::STRING)
+        && clipboard::is_text_mime_type(mime_type)
+    {
+        clipboard::latin1_to_utf8(&data)
+    } else {
+        data
+    }
+}
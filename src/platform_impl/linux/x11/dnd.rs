@@ -1,41 +1,29 @@
-use std::io;
 use std::os::raw::*;
-use std::path::{Path, PathBuf};
-use std::str::Utf8Error;
+use std::path::PathBuf;
 use std::sync::Arc;
 
-use percent_encoding::percent_decode;
 use x11rb::protocol::xproto::{self, ConnectionExt};
 
 use super::atoms::AtomName::None as DndNone;
 use super::atoms::*;
 use super::{util, CookieResultExt, X11Error, XConnection};
+use crate::dnd::DragActions;
+use crate::dpi::PhysicalPosition;
+use crate::event_loop::AsyncRequestSerial;
+use crate::platform_impl::common::dnd::DndDataParseError;
 
-#[derive(Debug, Clone, Copy)]
-pub enum DndState {
-    Accepted,
-    Rejected,
-}
-
+/// A request from a window about the drag over it.
 #[derive(Debug)]
-pub enum DndDataParseError {
-    EmptyData,
-    InvalidUtf8(#[allow(dead_code)] Utf8Error),
-    HostnameSpecified(#[allow(dead_code)] String),
-    UnexpectedProtocol(#[allow(dead_code)] String),
-    UnresolvablePath(#[allow(dead_code)] io::Error),
+pub enum DropRequest {
+    Accept { window: xproto::Window, mime_type: String, action: DragActions },
+    Reject { window: xproto::Window },
+    Read { window: xproto::Window, serial: AsyncRequestSerial, mime_types: Vec<String> },
 }
 
-impl From<Utf8Error> for DndDataParseError {
-    fn from(e: Utf8Error) -> Self {
-        DndDataParseError::InvalidUtf8(e)
-    }
-}
-
-impl From<io::Error> for DndDataParseError {
-    fn from(e: io::Error) -> Self {
-        DndDataParseError::UnresolvablePath(e)
-    }
+#[derive(Debug, Clone, Copy)]
+pub enum DndState {
+    Accepted(DragActions),
+    Rejected,
 }
 
 pub struct Dnd {
@@ -43,25 +31,82 @@ pub struct Dnd {
     // Populated by XdndEnter event handler
     pub version: Option<c_long>,
     pub type_list: Option<Vec<xproto::Atom>>,
-    // Populated by XdndPosition event handler
     pub source_window: Option<xproto::Window>,
+    /// The actions in the `XdndActionList` of the source.
+    pub action_list: DragActions,
+    /// The position of the window the drag entered, in root window coordinates.
+    pub window_origin: Option<(i16, i16)>,
+    // Populated by XdndPosition event handler
+    /// The window the drag is over.
+    pub target_window: Option<xproto::Window>,
+    pub position: PhysicalPosition<f64>,
+    /// The MIME type and action the target window accepts the drop with.
+    pub response: Option<(String, DragActions)>,
+    /// Whether the source is waiting for an `XdndStatus`.
+    pub status_pending: bool,
+    // Populated by XdndDrop event handler
+    /// Whether `XdndFinished` is sent once the requested data has been transferred.
+    pub dropped: bool,
     // Populated by SelectionNotify event handler (triggered by XdndPosition event handler)
     pub result: Option<Result<Vec<PathBuf>, DndDataParseError>>,
 }
 
 impl Dnd {
     pub fn new(xconn: Arc<XConnection>) -> Result<Self, X11Error> {
-        Ok(Dnd { xconn, version: None, type_list: None, source_window: None, result: None })
+        Ok(Dnd {
+            xconn,
+            version: None,
+            type_list: None,
+            source_window: None,
+            action_list: DragActions::empty(),
+            window_origin: None,
+            target_window: None,
+            position: PhysicalPosition::default(),
+            response: None,
+            status_pending: false,
+            dropped: false,
+            result: None,
+        })
     }
 
     pub fn reset(&mut self) {
         self.version = None;
         self.type_list = None;
         self.source_window = None;
+        self.action_list = DragActions::empty();
+        self.window_origin = None;
+        self.target_window = None;
+        self.position = PhysicalPosition::default();
+        self.response = None;
+        self.status_pending = false;
+        self.dropped = false;
         self.result = None;
     }
 
-    pub unsafe fn send_status(
+    pub fn action_atom(&self, action: DragActions) -> xproto::Atom {
+        let atoms = self.xconn.atoms();
+        if action.contains(DragActions::MOVE) {
+            atoms[XdndActionMove]
+        } else if action.contains(DragActions::LINK) {
+            atoms[XdndActionLink]
+        } else {
+            atoms[XdndActionCopy]
+        }
+    }
+
+    /// The action described by `atom`, `XdndActionAsk` and private actions are treated as copy.
+    pub fn atom_action(&self, atom: xproto::Atom) -> DragActions {
+        let atoms = self.xconn.atoms();
+        if atom == atoms[XdndActionMove] {
+            DragActions::MOVE
+        } else if atom == atoms[XdndActionLink] {
+            DragActions::LINK
+        } else {
+            DragActions::COPY
+        }
+    }
+
+    pub fn send_status(
         &self,
         this_window: xproto::Window,
         target_window: xproto::Window,
@@ -69,7 +114,7 @@ impl Dnd {
     ) -> Result<(), X11Error> {
         let atoms = self.xconn.atoms();
         let (accepted, action) = match state {
-            DndState::Accepted => (1, atoms[XdndActionPrivate]),
+            DndState::Accepted(action) => (1, self.action_atom(action)),
             DndState::Rejected => (0, atoms[DndNone]),
         };
         self.xconn
@@ -85,7 +130,7 @@ impl Dnd {
         Ok(())
     }
 
-    pub unsafe fn send_finished(
+    pub fn send_finished(
         &self,
         this_window: xproto::Window,
         target_window: xproto::Window,
@@ -93,7 +138,7 @@ impl Dnd {
     ) -> Result<(), X11Error> {
         let atoms = self.xconn.atoms();
         let (accepted, action) = match state {
-            DndState::Accepted => (1, atoms[XdndActionPrivate]),
+            DndState::Accepted(action) => (1, self.action_atom(action)),
             DndState::Rejected => (0, atoms[DndNone]),
         };
         self.xconn
@@ -121,6 +166,21 @@ impl Dnd {
         )
     }
 
+    /// The actions the source offers in addition to the one proposed with `XdndPosition`.
+    pub fn get_action_list(&self, source_window: xproto::Window) -> DragActions {
+        let atoms = self.xconn.atoms();
+        self.xconn
+            .get_property::<xproto::Atom>(
+                source_window,
+                atoms[XdndActionList],
+                xproto::Atom::from(xproto::AtomEnum::ATOM),
+            )
+            .unwrap_or_default()
+            .into_iter()
+            .map(|atom| self.atom_action(atom))
+            .collect()
+    }
+
     pub unsafe fn convert_selection(&self, window: xproto::Window, time: xproto::Timestamp) {
         let atoms = self.xconn.atoms();
         self.xconn
@@ -142,33 +202,4 @@ impl Dnd {
         let atoms = self.xconn.atoms();
         self.xconn.get_property(window, atoms[XdndSelection], atoms[TextUriList])
     }
-
-    pub fn parse_data(&self, data: &mut [c_uchar]) -> Result<Vec<PathBuf>, DndDataParseError> {
-        if !data.is_empty() {
-            let mut path_list = Vec::new();
-            let decoded = percent_decode(data).decode_utf8()?.into_owned();
-            for uri in decoded.split("\r\n").filter(|u| !u.is_empty()) {
-                // The format is specified as protocol://host/path
-                // However, it's typically simply protocol:///path
-                let path_str = if uri.starts_with("file://") {
-                    let path_str = uri.replace("file://", "");
-                    if !path_str.starts_with('/') {
-                        // A hostname is specified
-                        // Supporting this case is beyond the scope of my mental health
-                        return Err(DndDataParseError::HostnameSpecified(path_str));
-                    }
-                    path_str
-                } else {
-                    // Only the file protocol is supported
-                    return Err(DndDataParseError::UnexpectedProtocol(uri.to_owned()));
-                };
-
-                let path = Path::new(&path_str).canonicalize()?;
-                path_list.push(path);
-            }
-            Ok(path_list)
-        } else {
-            Err(DndDataParseError::EmptyData)
-        }
-    }
 }
@@ -0,0 +1,414 @@
+//! The source side of XDND, used to drag data out of our windows.
+//!
+//! The data is offered through the `XdndSelection`, owned by the hidden clipboard window, which
+//! also acts as the XDND source window. The pointer is grabbed for the duration of the drag, so
+//! its motion is reported to us no matter which window it is over.
+
+use std::sync::Arc;
+
+use x11rb::connection::Connection;
+use x11rb::protocol::xinput::{self, ConnectionExt as _};
+use x11rb::protocol::xproto::{self, ConnectionExt as _};
+
+use super::atoms::*;
+use super::{util, CookieResultExt, X11Error, XConnection};
+use crate::dnd::{DragActions, DragData, DragSourceEvent};
+use crate::event_loop::AsyncRequestSerial;
+use crate::window::{CursorIcon, WindowId};
+
+/// The XDND version we implement.
+const XDND_VERSION: u32 = 5;
+
+/// A request from a window to start a drag.
+#[derive(Debug)]
+pub struct DragRequest {
+    pub window: xproto::Window,
+    pub serial: AsyncRequestSerial,
+    pub data: DragData,
+    pub icon: Option<util::CustomCursor>,
+}
+
+/// The window under the pointer which understands XDND.
+#[derive(Debug)]
+struct Target {
+    window: xproto::Window,
+    version: u32,
+    /// Whether we're waiting for an `XdndStatus` in response to an `XdndPosition`.
+    awaiting_status: bool,
+    /// The latest position that couldn't be sent yet because we're awaiting the status.
+    pending_position: Option<(i16, i16, xproto::Timestamp)>,
+    /// The action the target would perform, empty if it doesn't accept the data.
+    action: DragActions,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Stage {
+    /// The pointer button is still held.
+    Dragging,
+    /// The button was released while awaiting the status of the target.
+    DropPending(xproto::Timestamp),
+    /// `XdndDrop` was sent, waiting for `XdndFinished`.
+    Dropped,
+    /// The drag is over.
+    Done,
+}
+
+#[derive(Debug)]
+pub struct DragSource {
+    xconn: Arc<XConnection>,
+    /// The window the drag was started from, which receives the events.
+    window: xproto::Window,
+    serial: AsyncRequestSerial,
+    /// The window acting as the XDND source.
+    source_window: xproto::Window,
+    device: xinput::DeviceId,
+    actions: DragActions,
+    types: Vec<xproto::Atom>,
+    target: Option<Target>,
+    stage: Stage,
+    events: Vec<DragSourceEvent>,
+    /// Kept alive while it's used for the grab.
+    _icon: Option<util::CustomCursor>,
+}
+
+impl DragSource {
+    /// Grab the pointer and start the drag.
+    ///
+    /// `types` are the targets the `XdndSelection` can be converted to, the selection must already
+    /// be owned by `source_window`.
+    pub fn start(
+        xconn: Arc<XConnection>,
+        request: DragRequest,
+        source_window: xproto::Window,
+        types: Vec<xproto::Atom>,
+    ) -> Result<Self, X11Error> {
+        let atoms = xconn.atoms();
+        xconn
+            .change_property(
+                source_window,
+                atoms[XdndTypeList],
+                xproto::AtomEnum::ATOM.into(),
+                xproto::PropMode::REPLACE,
+                &types,
+            )?
+            .ignore_error();
+
+        let cursor = match request.icon.as_ref() {
+            Some(icon) => icon.cursor(),
+            None => xconn.cached_cursor(Some(CursorIcon::Grabbing))?,
+        };
+
+        let device =
+            xconn.xcb_connection().xinput_xi_get_client_pointer(request.window)?.reply()?;
+        let mask = xinput::XIEventMask::MOTION
+            | xinput::XIEventMask::BUTTON_PRESS
+            | xinput::XIEventMask::BUTTON_RELEASE;
+        let status = xconn
+            .xcb_connection()
+            .xinput_xi_grab_device(
+                request.window,
+                xconn.timestamp(),
+                cursor,
+                device.deviceid,
+                xproto::GrabMode::ASYNC,
+                xproto::GrabMode::ASYNC,
+                xinput::GrabOwner::OWNER,
+                &[mask.into()],
+            )?
+            .reply()?
+            .status;
+        // The implicit grab of the pressed button still reports the motion, but not the cursor.
+        if status != xproto::GrabStatus::SUCCESS {
+            tracing::warn!("Failed to grab the pointer for the drag: {status:?}");
+        }
+
+        Ok(Self {
+            xconn,
+            window: request.window,
+            serial: request.serial,
+            source_window,
+            device: device.deviceid,
+            actions: request.data.actions,
+            types,
+            target: None,
+            stage: Stage::Dragging,
+            events: Vec::new(),
+            _icon: request.icon,
+        })
+    }
+
+    pub fn window_id(&self) -> WindowId {
+        WindowId::from_raw(self.window as _)
+    }
+
+    pub fn serial(&self) -> AsyncRequestSerial {
+        self.serial
+    }
+
+    /// Whether the drag is over, after its last event was taken.
+    pub fn is_done(&self) -> bool {
+        self.stage == Stage::Done && self.events.is_empty()
+    }
+
+    pub fn take_events(&mut self) -> Vec<DragSourceEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// The pointer moved, in root window coordinates.
+    pub fn motion(&mut self, x: i16, y: i16, time: xproto::Timestamp) {
+        if self.stage != Stage::Dragging {
+            return;
+        }
+
+        let target = self.find_target(x, y);
+        if target.map(|(window, _)| window) != self.target.as_ref().map(|target| target.window) {
+            self.leave();
+            if let Some((window, version)) = target {
+                self.enter(window, version);
+            }
+        }
+
+        let Some(target) = self.target.as_mut() else {
+            return;
+        };
+
+        if target.awaiting_status {
+            target.pending_position = Some((x, y, time));
+        } else {
+            self.send_position(x, y, time);
+        }
+    }
+
+    /// The pointer button was released, drop the data on the current target.
+    pub fn release(&mut self, time: xproto::Timestamp) {
+        if self.stage != Stage::Dragging {
+            return;
+        }
+
+        self.ungrab(time);
+        match self.target.as_ref() {
+            Some(target) if target.awaiting_status => self.stage = Stage::DropPending(time),
+            _ => self.drop(time),
+        }
+    }
+
+    /// Cancel the drag, e.g. because a new one is started.
+    pub fn cancel(&mut self) {
+        if self.stage == Stage::Done {
+            return;
+        }
+
+        if self.stage == Stage::Dragging {
+            self.ungrab(self.xconn.timestamp());
+        }
+
+        if self.stage != Stage::Dropped {
+            self.leave();
+        }
+
+        self.finish(DragSourceEvent::Cancelled);
+    }
+
+    /// Handle the `XdndStatus` message of a target.
+    pub fn handle_status(&mut self, data: [u32; 5]) {
+        let Some(target) = self.target.as_mut().filter(|target| target.window == data[0]) else {
+            return;
+        };
+
+        let atoms = self.xconn.atoms();
+        let action = if data[1] & 1 == 0 {
+            DragActions::empty()
+        } else if data[4] == atoms[XdndActionMove] {
+            DragActions::MOVE
+        } else if data[4] == atoms[XdndActionLink] {
+            DragActions::LINK
+        } else {
+            // Targets before version 2 don't report the action.
+            DragActions::COPY
+        };
+
+        target.awaiting_status = false;
+        if target.action != action {
+            target.action = action;
+            self.events.push(DragSourceEvent::Action(action));
+        }
+
+        match self.stage {
+            Stage::Dragging => {
+                if let Some((x, y, time)) = self.target.as_mut().unwrap().pending_position.take() {
+                    self.send_position(x, y, time);
+                }
+            },
+            Stage::DropPending(time) => self.drop(time),
+            Stage::Dropped | Stage::Done => (),
+        }
+    }
+
+    /// Handle the `XdndFinished` message of a target.
+    pub fn handle_finished(&mut self, data: [u32; 5]) {
+        let Some(target) = self.target.as_ref().filter(|target| target.window == data[0]) else {
+            return;
+        };
+
+        if self.stage != Stage::Dropped {
+            return;
+        }
+
+        let atoms = self.xconn.atoms();
+        let action = if target.version < 5 {
+            target.action
+        } else if data[1] & 1 == 0 {
+            DragActions::empty()
+        } else if data[2] == atoms[XdndActionMove] {
+            DragActions::MOVE
+        } else if data[2] == atoms[XdndActionLink] {
+            DragActions::LINK
+        } else {
+            DragActions::COPY
+        };
+
+        self.finish(DragSourceEvent::Finished(action));
+    }
+
+    /// Find the XDND aware window at the given root coordinates.
+    fn find_target(&self, x: i16, y: i16) -> Option<(xproto::Window, u32)> {
+        let root = self.xconn.default_root().root;
+        let mut window = root;
+        loop {
+            let child = self
+                .xconn
+                .xcb_connection()
+                .translate_coordinates(root, window, x, y)
+                .ok()?
+                .reply()
+                .ok()?
+                .child;
+            if child == x11rb::NONE {
+                return None;
+            }
+
+            if let Some(version) = self.xdnd_version(child) {
+                return Some((child, version));
+            }
+
+            window = child;
+        }
+    }
+
+    fn xdnd_version(&self, window: xproto::Window) -> Option<u32> {
+        let atoms = self.xconn.atoms();
+        let version = *self
+            .xconn
+            .get_property::<u32>(window, atoms[XdndAware], xproto::AtomEnum::ATOM.into())
+            .ok()?
+            .first()?;
+        Some(version.min(XDND_VERSION))
+    }
+
+    fn enter(&mut self, window: xproto::Window, version: u32) {
+        let atoms = self.xconn.atoms();
+        let more_types = (self.types.len() > 3) as u32;
+        let mut data = [self.source_window, (version << 24) | more_types, 0, 0, 0];
+        for (slot, &atom) in data[2..].iter_mut().zip(&self.types) {
+            *slot = atom;
+        }
+
+        self.send_message(window, atoms[XdndEnter], data);
+        self.target = Some(Target {
+            window,
+            version,
+            awaiting_status: false,
+            pending_position: None,
+            action: DragActions::empty(),
+        });
+    }
+
+    fn leave(&mut self) {
+        let Some(target) = self.target.take() else {
+            return;
+        };
+
+        let atoms = self.xconn.atoms();
+        self.send_message(target.window, atoms[XdndLeave], [self.source_window, 0, 0, 0, 0]);
+        if !target.action.is_empty() {
+            self.events.push(DragSourceEvent::Action(DragActions::empty()));
+        }
+    }
+
+    fn send_position(&mut self, x: i16, y: i16, time: xproto::Timestamp) {
+        let atoms = self.xconn.atoms();
+        let action = if self.actions.contains(DragActions::COPY) || self.actions.is_empty() {
+            atoms[XdndActionCopy]
+        } else if self.actions.contains(DragActions::MOVE) {
+            atoms[XdndActionMove]
+        } else {
+            atoms[XdndActionLink]
+        };
+
+        let target = self.target.as_mut().unwrap();
+        target.awaiting_status = true;
+        let window = target.window;
+        let position = ((x as u16 as u32) << 16) | y as u16 as u32;
+        self.send_message(window, atoms[XdndPosition], [
+            self.source_window,
+            0,
+            position,
+            time,
+            action,
+        ]);
+    }
+
+    fn drop(&mut self, time: xproto::Timestamp) {
+        let accepted = self.target.as_ref().is_some_and(|target| !target.action.is_empty());
+        if !accepted {
+            self.leave();
+            self.finish(DragSourceEvent::Cancelled);
+            return;
+        }
+
+        let atoms = self.xconn.atoms();
+        let window = self.target.as_ref().unwrap().window;
+        self.send_message(window, atoms[XdndDrop], [self.source_window, 0, time, 0, 0]);
+        self.stage = Stage::Dropped;
+        self.events.push(DragSourceEvent::Dropped);
+    }
+
+    fn finish(&mut self, event: DragSourceEvent) {
+        self.stage = Stage::Done;
+        self.events.push(event);
+
+        let atoms = self.xconn.atoms();
+        self.xconn
+            .xcb_connection()
+            .delete_property(self.source_window, atoms[XdndTypeList])
+            .expect_then_ignore_error("Failed to delete the XDND type list");
+        self.flush();
+    }
+
+    fn ungrab(&self, time: xproto::Timestamp) {
+        self.xconn
+            .xcb_connection()
+            .xinput_xi_ungrab_device(time, self.device)
+            .expect_then_ignore_error("Failed to ungrab the pointer");
+        self.flush();
+    }
+
+    fn send_message(&self, window: xproto::Window, message_type: xproto::Atom, data: [u32; 5]) {
+        self.xconn
+            .send_client_msg(window, window, message_type, None, data)
+            .expect_then_ignore_error("Failed to send an XDND message");
+        self.flush();
+    }
+
+    fn flush(&self) {
+        if let Err(err) = self.xconn.xcb_connection().flush() {
+            tracing::warn!("Failed to flush the X11 connection: {err}");
+        }
+    }
+}
+
+impl Drop for DragSource {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
@@ -1,8 +1,10 @@
 use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, VecDeque};
+use std::mem::MaybeUninit;
 use std::os::raw::{c_char, c_int, c_long, c_ulong};
 use std::slice;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
 
 use x11_dl::xinput2::{
     self, XIDeviceEvent, XIEnterEvent, XIFocusInEvent, XIFocusOutEvent, XIHierarchyEvent,
@@ -11,7 +13,8 @@ use x11_dl::xinput2::{
 use x11_dl::xlib::{
     self, Display as XDisplay, Window as XWindow, XAnyEvent, XClientMessageEvent, XConfigureEvent,
     XDestroyWindowEvent, XEvent, XExposeEvent, XKeyEvent, XMapEvent, XPropertyEvent,
-    XReparentEvent, XSelectionEvent, XVisibilityEvent, XkbAnyEvent, XkbStateRec,
+    XReparentEvent, XSelectionClearEvent, XSelectionEvent, XSelectionRequestEvent,
+    XVisibilityEvent, XkbAnyEvent, XkbStateRec,
 };
 use x11rb::protocol::sync::{ConnectionExt, Int64};
 use x11rb::protocol::xinput;
@@ -20,13 +23,16 @@ use x11rb::protocol::xproto::{self, ConnectionExt as _, ModMask};
 use x11rb::x11_utils::{ExtensionInformation, Serialize};
 use xkbcommon_dl::xkb_mod_mask_t;
 
+use crate::dnd::{DragActions, DragSourceEvent};
 use crate::dpi::{PhysicalPosition, PhysicalSize};
 use crate::event::{
-    ButtonSource, DeviceEvent, DeviceId, ElementState, Event, FingerId, Ime, MouseButton,
-    MouseScrollDelta, PointerKind, PointerSource, RawKeyEvent, SurfaceSizeWriter, TouchPhase,
-    WindowEvent,
+    ButtonSource, DeviceEvent, DeviceId, ElementState, Event, FingerId, Ime, Modifiers,
+    MouseButton, MouseScrollDelta, PenButton, PenData, PenTool, PointerKind, PointerSource,
+    RawKeyEvent, ScrollSource, SurfaceSizeWriter, TouchPhase, WindowEvent,
 };
-use crate::keyboard::ModifiersState;
+use crate::event_loop::AsyncRequestSerial;
+use crate::keyboard::KeyRepeatInfo;
+use crate::platform_impl::common::dnd;
 use crate::platform_impl::common::xkb::{self, XkbState};
 use crate::platform_impl::platform::common::xkb::Context;
 use crate::platform_impl::platform::x11::ime::{ImeEvent, ImeEventReceiver, ImeRequest};
@@ -34,18 +40,24 @@ use crate::platform_impl::platform::x11::ActiveEventLoop;
 use crate::platform_impl::x11::atoms::*;
 use crate::platform_impl::x11::util::cookie::GenericEventCookie;
 use crate::platform_impl::x11::{
-    mkdid, mkwid, util, CookieResultExt, Device, DeviceInfo, Dnd, DndState, ImeReceiver,
-    ScrollOrientation, UnownedWindow, WindowId,
+    ffi, mkdid, mkwid, util, Clipboard, ClipboardRequest, CookieResultExt, Device, DeviceInfo, Dnd,
+    DndState, DragRequest, DragSource, DropRequest, ImeReceiver, ScrollOrientation, UnownedWindow,
+    WindowId,
 };
 
 /// The maximum amount of X modifiers to replay.
 pub const MAX_MOD_REPLAY_LEN: usize = 32;
 
 /// The X11 documentation states: "Keycodes lie in the inclusive range `[8, 255]`".
-const KEYCODE_OFFSET: u8 = 8;
+pub(super) const KEYCODE_OFFSET: u8 = 8;
 
 pub struct EventProcessor {
     pub dnd: Dnd,
+    pub clipboard: Clipboard,
+    /// The drag started from one of our windows.
+    pub drag: Option<DragSource>,
+    /// Events of drags that are already over.
+    pub pending_drag_events: Vec<(WindowId, AsyncRequestSerial, DragSourceEvent)>,
     pub ime_receiver: ImeReceiver,
     pub ime_event_receiver: ImeEventReceiver,
     pub randr_event_offset: u8,
@@ -62,10 +74,17 @@ pub struct EventProcessor {
     // Used to detect key repeats.
     pub held_key_press: Option<u32>,
     pub first_touch: Option<u32>,
+    /// The window the pen is over, along with its device, since XInput 2 doesn't report when it
+    /// enters or leaves proximity.
+    pub pen_proximity: Cell<Option<(xproto::Window, xinput::DeviceId, PenTool)>>,
+    /// The scale of the pinch gesture in progress, XInput 2 reports it since the gesture began.
+    pub pinch_scale: Cell<f64>,
+    /// The latest key repeat reported to the application.
+    pub key_repeat_info: Option<KeyRepeatInfo>,
     // Currently focused window belonging to this process
     pub active_window: Option<xproto::Window>,
     /// Latest modifiers we've sent for the user to trigger change in event.
-    pub modifiers: Cell<ModifiersState>,
+    pub modifiers: Cell<Modifiers>,
     pub xfiltered_modifiers: VecDeque<c_ulong>,
     pub xmodmap: util::ModifierKeymap,
     pub is_composing: bool,
@@ -122,6 +141,165 @@ impl EventProcessor {
 
             callback(&self.target, Event::WindowEvent { window_id, event });
         }
+
+        self.drain_clipboard_events(&mut callback);
+        self.drain_drag_events(&mut callback);
+    }
+
+    /// Deliver the clipboard reads which finished.
+    pub fn drain_clipboard_events<F>(&mut self, mut callback: F)
+    where
+        F: FnMut(&ActiveEventLoop, Event),
+    {
+        for (window_id, serial, data) in self.clipboard.take_completed() {
+            let event = WindowEvent::ClipboardDone { serial, data };
+            callback(&self.target, Event::WindowEvent { window_id, event });
+        }
+    }
+
+    /// Start a drag, cancelling the current one.
+    pub fn start_drag(&mut self, request: DragRequest) {
+        if let Some(mut drag) = self.drag.take() {
+            drag.cancel();
+            let (window_id, serial) = (drag.window_id(), drag.serial());
+            let events = drag.take_events().into_iter().map(|event| (window_id, serial, event));
+            self.pending_drag_events.extend(events);
+        }
+
+        let xconn = Arc::clone(&self.target.xconn);
+        let selection = xconn.atoms()[XdndSelection];
+        let (window_id, serial) = (mkwid(request.window), request.serial);
+        let contents = request.data.contents.clone();
+        self.clipboard.handle_request(ClipboardRequest::Set { selection, data: contents });
+        let types = self.clipboard.data_targets(selection);
+
+        match DragSource::start(xconn, request, self.clipboard.window(), types) {
+            Ok(drag) => self.drag = Some(drag),
+            Err(err) => {
+                tracing::warn!("Failed to start the drag: {err}");
+                self.pending_drag_events.push((window_id, serial, DragSourceEvent::Cancelled));
+            },
+        }
+    }
+
+    /// Deliver the progress of the current drag.
+    pub fn drain_drag_events<F>(&mut self, mut callback: F)
+    where
+        F: FnMut(&ActiveEventLoop, Event),
+    {
+        let mut events = std::mem::take(&mut self.pending_drag_events);
+        if let Some(drag) = self.drag.as_mut() {
+            let (window_id, serial) = (drag.window_id(), drag.serial());
+            events.extend(drag.take_events().into_iter().map(|event| (window_id, serial, event)));
+            if drag.is_done() {
+                self.drag = None;
+            }
+        }
+
+        for (window_id, serial, event) in events {
+            let event = WindowEvent::DragSource { serial, event };
+            callback(&self.target, Event::WindowEvent { window_id, event });
+        }
+    }
+
+    /// Handle the response of a window to the drag over it.
+    pub fn handle_drop_request(&mut self, request: DropRequest) {
+        match request {
+            DropRequest::Accept { window, mime_type, action } => {
+                if self.dnd.target_window == Some(window) && !self.dnd.dropped {
+                    self.dnd.response = Some((mime_type, action));
+                }
+            },
+            DropRequest::Reject { window } => {
+                if self.dnd.target_window == Some(window) && !self.dnd.dropped {
+                    self.dnd.response = None;
+                }
+            },
+            DropRequest::Read { window, serial, mime_types } => {
+                let targets = if self.dnd.target_window == Some(window) {
+                    self.dnd.type_list.clone().unwrap_or_default()
+                } else {
+                    Vec::new()
+                };
+                let selection = self.target.xconn.atoms()[XdndSelection];
+                self.clipboard.read_offer(mkwid(window), serial, selection, mime_types, targets);
+            },
+        }
+    }
+
+    /// Reply to the source of the drag over our window.
+    ///
+    /// Called after the application responded to the last drag event.
+    pub fn update_drop(&mut self) {
+        let (Some(window), Some(source_window)) = (self.dnd.target_window, self.dnd.source_window)
+        else {
+            return;
+        };
+
+        let state = match self.dnd.response {
+            Some((_, action)) => DndState::Accepted(action),
+            None => DndState::Rejected,
+        };
+
+        if self.dnd.dropped {
+            // Wait for the transfers of the drop data.
+            let selection = self.target.xconn.atoms()[XdndSelection];
+            if self.clipboard.is_reading(selection) {
+                return;
+            }
+
+            self.dnd
+                .send_finished(window, source_window, state)
+                .expect("Failed to send `XdndFinished` message.");
+            self.dnd.reset();
+        } else if std::mem::take(&mut self.dnd.status_pending) {
+            self.dnd
+                .send_status(window, source_window, state)
+                .expect("Failed to send `XdndStatus` message.");
+        }
+    }
+
+    /// Process only the events related to selections, leaving everything else queued.
+    ///
+    /// Used while exiting, where other events can't be delivered anymore.
+    pub fn drain_selection_events(&mut self) {
+        unsafe extern "C" fn predicate(
+            _display: *mut XDisplay,
+            event: *mut XEvent,
+            _arg: *mut c_char,
+        ) -> c_int {
+            let event_type = unsafe { (*event).get_type() };
+            matches!(
+                event_type,
+                xlib::SelectionNotify
+                    | xlib::SelectionRequest
+                    | xlib::SelectionClear
+                    | xlib::PropertyNotify
+            ) as c_int
+        }
+
+        let mut xev = MaybeUninit::<XEvent>::uninit();
+        loop {
+            let found = unsafe {
+                (self.target.xconn.xlib.XCheckIfEvent)(
+                    self.target.xconn.display,
+                    xev.as_mut_ptr(),
+                    Some(predicate),
+                    std::ptr::null_mut(),
+                )
+            };
+            if found != xlib::True {
+                break;
+            }
+
+            let xev = unsafe { xev.assume_init_ref() };
+            match xev.get_type() {
+                xlib::SelectionNotify => self.clipboard_selection_notify(xev.as_ref()),
+                xlib::SelectionRequest => self.selection_request(xev.as_ref()),
+                xlib::SelectionClear => self.selection_clear(xev.as_ref()),
+                _ => self.clipboard_property_notify(xev.as_ref()),
+            }
+        }
     }
 
     /// XFilterEvent tells us when an event has been discarded by the input method.
@@ -174,6 +352,8 @@ impl EventProcessor {
         match event_type {
             xlib::ClientMessage => self.client_message(xev.as_ref(), &mut callback),
             xlib::SelectionNotify => self.selection_notify(xev.as_ref(), &mut callback),
+            xlib::SelectionRequest => self.selection_request(xev.as_ref()),
+            xlib::SelectionClear => self.selection_clear(xev.as_ref()),
             xlib::ConfigureNotify => self.configure_notify(xev.as_ref(), &mut callback),
             xlib::ReparentNotify => self.reparent_notify(xev.as_ref()),
             xlib::MapNotify => self.map_notify(xev.as_ref(), &mut callback),
@@ -209,6 +389,12 @@ impl EventProcessor {
                         };
 
                         let xev: &XIDeviceEvent = unsafe { xev.as_event() };
+                        if let Some(drag) =
+                            self.drag.as_mut().filter(|_| ty == xinput2::XI_ButtonRelease)
+                        {
+                            drag.release(xev.time as xproto::Timestamp);
+                        }
+
                         self.update_mods_from_xinput2_event(
                             &xev.mods,
                             &xev.group,
@@ -216,9 +402,21 @@ impl EventProcessor {
                             &mut callback,
                         );
                         self.xinput2_button_input(xev, state, &mut callback);
+
+                        if ty == xinput2::XI_ButtonRelease && is_last_button_release(xev) {
+                            let device = xev.deviceid as xinput::DeviceId;
+                            self.with_window(xev.event as xproto::Window, |window| {
+                                window.release_pointer_capture(device)
+                            });
+                        }
                     },
                     xinput2::XI_Motion => {
                         let xev: &XIDeviceEvent = unsafe { xev.as_event() };
+                        if let Some(drag) = self.drag.as_mut() {
+                            let (x, y) = (xev.root_x as i16, xev.root_y as i16);
+                            drag.motion(x, y, xev.time as xproto::Timestamp);
+                        }
+
                         self.update_mods_from_xinput2_event(
                             &xev.mods,
                             &xev.group,
@@ -253,6 +451,18 @@ impl EventProcessor {
                         let xev: &XIDeviceEvent = unsafe { xev.as_event() };
                         self.xinput2_touch(xev, evtype, &mut callback);
                     },
+                    ffi::XI_GesturePinchBegin
+                    | ffi::XI_GesturePinchUpdate
+                    | ffi::XI_GesturePinchEnd => {
+                        let xev: &ffi::XIGesturePinchEvent = unsafe { xev.as_event() };
+                        self.xinput2_gesture_pinch(xev, &mut callback);
+                    },
+                    ffi::XI_GestureSwipeBegin
+                    | ffi::XI_GestureSwipeUpdate
+                    | ffi::XI_GestureSwipeEnd => {
+                        let xev: &ffi::XIGestureSwipeEvent = unsafe { xev.as_event() };
+                        self.xinput2_gesture_swipe(xev, &mut callback);
+                    },
                     xinput2::XI_RawButtonPress | xinput2::XI_RawButtonRelease => {
                         let state = match evtype {
                             xinput2::XI_RawButtonPress => ElementState::Pressed,
@@ -280,7 +490,7 @@ impl EventProcessor {
 
                     xinput2::XI_HierarchyChanged => {
                         let xev: &XIHierarchyEvent = unsafe { xev.as_event() };
-                        self.xinput2_hierarchy_changed(xev);
+                        self.xinput2_hierarchy_changed(xev, &mut callback);
                     },
                     _ => {},
                 }
@@ -331,7 +541,10 @@ impl EventProcessor {
         let mut devices = self.devices.borrow_mut();
         if let Some(info) = DeviceInfo::get(&self.target.xconn, device as _) {
             for info in info.iter() {
-                devices.insert(mkdid(info.deviceid as xinput::DeviceId), Device::new(info));
+                devices.insert(
+                    mkdid(info.deviceid as xinput::DeviceId),
+                    Device::new(&self.target.xconn, info),
+                );
             }
         }
     }
@@ -371,6 +584,22 @@ impl EventProcessor {
         let window = xev.window as xproto::Window;
         let window_id = mkwid(window);
 
+        // Replies of drop targets to our drag.
+        if window == self.clipboard.window() {
+            let Some(drag) = self.drag.as_mut() else {
+                return;
+            };
+
+            let [a, b, c, d, e]: [c_long; 5] = xev.data.as_longs().try_into().unwrap();
+            let data = [a as u32, b as u32, c as u32, d as u32, e as u32];
+            if xev.message_type == atoms[XdndStatus] as c_ulong {
+                drag.handle_status(data);
+            } else if xev.message_type == atoms[XdndFinished] as c_ulong {
+                drag.handle_finished(data);
+            }
+            return;
+        }
+
         if xev.data.get_long(0) as xproto::Atom == self.target.wm_delete_window {
             let event = Event::WindowEvent { window_id, event: WindowEvent::CloseRequested };
             callback(&self.target, event);
@@ -435,7 +664,9 @@ impl EventProcessor {
             let source_window = xev.data.get_long(0) as xproto::Window;
             let flags = xev.data.get_long(1);
             let version = flags >> 24;
+            self.dnd.reset();
             self.dnd.version = Some(version);
+            self.dnd.source_window = Some(source_window);
             let has_more_types = flags - (flags & (c_long::MAX - 1)) == 1;
             if !has_more_types {
                 let type_list = vec![
@@ -447,110 +678,152 @@ impl EventProcessor {
             } else if let Ok(more_types) = unsafe { self.dnd.get_type_list(source_window) } {
                 self.dnd.type_list = Some(more_types);
             }
+            self.dnd.action_list = self.dnd.get_action_list(source_window);
+
+            // The coordinates of `XdndPosition` are placed in the window with this, so moving the
+            // drag doesn't need a round trip.
+            self.dnd.window_origin = self
+                .target
+                .xconn
+                .xcb_connection()
+                .translate_coordinates(window, self.target.root, 0, 0)
+                .ok()
+                .and_then(|cookie| cookie.reply().ok())
+                .map(|reply| (reply.dst_x, reply.dst_y));
+
+            // The names are picked up without a round trip with the first `XdndPosition`.
+            if let Some(type_list) = self.dnd.type_list.as_ref() {
+                self.clipboard.prefetch_atom_names(type_list);
+                self.clipboard.flush();
+            }
             return;
         }
 
         if xev.message_type == atoms[XdndPosition] as c_ulong {
-            // This event occurs every time the mouse moves while a file's being dragged
-            // over our window. We emit HoveredFile in response; while the macOS backend
-            // does that upon a drag entering, XDND doesn't have access to the actual drop
-            // data until this event. For parity with other platforms, we only emit
-            // `HoveredFile` the first time, though if winit's API is later extended to
-            // supply position updates with `HoveredFile` or another event, implementing
-            // that here would be trivial.
+            // This event occurs every time the mouse moves while data is being dragged over our
+            // window. The first one is reported as `DragEntered`, since XDND doesn't tell us the
+            // position with `XdndEnter`. The `XdndStatus` reply is sent once the application had
+            // the chance to respond, see `update_drop`.
+            //
+            // `HoveredFile` is emitted once the `text/uri-list` is transferred, since XDND
+            // doesn't have access to the actual drop data until this event.
 
-            let source_window = xev.data.get_long(0) as xproto::Window;
-
-            // Equivalent to `(x << shift) | y`
-            // where `shift = mem::size_of::<c_short>() * 8`
-            // Note that coordinates are in "desktop space", not "window space"
-            // (in X11 parlance, they're root window coordinates)
-            // let packed_coordinates = xev.data.get_long(2);
-            // let shift = mem::size_of::<libc::c_short>() * 8;
-            // let x = packed_coordinates >> shift;
-            // let y = packed_coordinates & !(x << shift);
+            self.dnd.source_window = Some(xev.data.get_long(0) as xproto::Window);
 
             // By our own state flow, `version` should never be `None` at this point.
             let version = self.dnd.version.unwrap_or(5);
 
-            // Action is specified in versions 2 and up, though we don't need it anyway.
-            // let action = xev.data.get_long(4);
-
-            let accepted = if let Some(ref type_list) = self.dnd.type_list {
-                type_list.contains(&atoms[TextUriList])
+            let time = if version >= 1 {
+                xev.data.get_long(3) as xproto::Timestamp
             } else {
-                false
+                // In version 0, time isn't specified
+                x11rb::CURRENT_TIME
             };
 
-            if !accepted {
-                unsafe {
-                    self.dnd
-                        .send_status(window, source_window, DndState::Rejected)
-                        .expect("Failed to send `XdndStatus` message.");
-                }
-                self.dnd.reset();
-                return;
-            }
-
-            self.dnd.source_window = Some(source_window);
-            if self.dnd.result.is_none() {
-                let time = if version >= 1 {
-                    xev.data.get_long(3) as xproto::Timestamp
-                } else {
-                    // In version 0, time isn't specified
-                    x11rb::CURRENT_TIME
-                };
+            // Log this timestamp.
+            self.target.xconn.set_timestamp(time);
+
+            // The coordinates are packed as `(x << 16) | y`, in root window coordinates.
+            let packed_coordinates = xev.data.get_long(2) as u32;
+            let (root_x, root_y) = ((packed_coordinates >> 16) as i16, packed_coordinates as i16);
+            let position = self
+                .dnd
+                .window_origin
+                .map(|(x, y)| {
+                    PhysicalPosition::new(
+                        f64::from(root_x) - f64::from(x),
+                        f64::from(root_y) - f64::from(y),
+                    )
+                })
+                .unwrap_or(self.dnd.position);
+            self.dnd.position = position;
+
+            // The action is specified in versions 2 and up.
+            let action = if version >= 2 {
+                self.dnd.atom_action(xev.data.get_long(4) as xproto::Atom)
+            } else {
+                DragActions::COPY
+            };
+            let actions = self.dnd.action_list | action;
 
-                // Log this timestamp.
-                self.target.xconn.set_timestamp(time);
+            self.dnd.status_pending = true;
+            let event = if self.dnd.target_window.replace(window).is_none() {
+                let type_list = self.dnd.type_list.clone().unwrap_or_default();
+                if type_list.contains(&atoms[TextUriList]) {
+                    self.dnd.response = Some((String::from("text/uri-list"), DragActions::COPY));
 
-                // This results in the `SelectionNotify` event below
-                unsafe {
-                    self.dnd.convert_selection(window, time);
+                    // This results in the `SelectionNotify` event below
+                    unsafe {
+                        self.dnd.convert_selection(window, time);
+                    }
                 }
-            }
 
-            unsafe {
-                self.dnd
-                    .send_status(window, source_window, DndState::Accepted)
-                    .expect("Failed to send `XdndStatus` message.");
-            }
+                let mime_types =
+                    type_list.iter().filter_map(|&atom| self.clipboard.atom_name(atom)).collect();
+                WindowEvent::DragEntered { mime_types, actions, position }
+            } else {
+                WindowEvent::DragMoved { actions, position }
+            };
+            callback(&self.target, Event::WindowEvent { window_id, event });
             return;
         }
 
         if xev.message_type == atoms[XdndDrop] as c_ulong {
-            let (source_window, state) = if let Some(source_window) = self.dnd.source_window {
-                if let Some(Ok(ref path_list)) = self.dnd.result {
-                    for path in path_list {
-                        let event = Event::WindowEvent {
-                            window_id,
-                            event: WindowEvent::DroppedFile(path.clone()),
-                        };
-                        callback(&self.target, event);
-                    }
-                }
-                (source_window, DndState::Accepted)
-            } else {
-                // `source_window` won't be part of our DND state if we already rejected the drop in
-                // our `XdndPosition` handler.
+            if self.dnd.target_window != Some(window) || self.dnd.response.is_none() {
+                // We already rejected the drop in our `XdndPosition` handler.
                 let source_window = xev.data.get_long(0) as xproto::Window;
-                (source_window, DndState::Rejected)
-            };
-
-            unsafe {
                 self.dnd
-                    .send_finished(window, source_window, state)
+                    .send_finished(window, source_window, DndState::Rejected)
                     .expect("Failed to send `XdndFinished` message.");
+
+                let entered = self.dnd.target_window.is_some();
+                self.dnd.reset();
+                if entered {
+                    callback(&self.target, Event::WindowEvent {
+                        window_id,
+                        event: WindowEvent::DragLeft,
+                    });
+                }
+                return;
             }
 
-            self.dnd.reset();
+            if self.dnd.version.unwrap_or(5) >= 1 {
+                self.target.xconn.set_timestamp(xev.data.get_long(2) as xproto::Timestamp);
+            }
+
+            let accepted_files = self
+                .dnd
+                .response
+                .as_ref()
+                .is_some_and(|(mime_type, _)| mime_type == "text/uri-list");
+            if let (true, Some(Ok(path_list))) = (accepted_files, self.dnd.result.as_ref()) {
+                for path in path_list {
+                    let event = Event::WindowEvent {
+                        window_id,
+                        event: WindowEvent::DroppedFile(path.clone()),
+                    };
+                    callback(&self.target, event);
+                }
+            }
+
+            // `XdndFinished` is sent by `update_drop` once the requested data is transferred.
+            self.dnd.dropped = true;
+            let event = WindowEvent::DragDropped { position: self.dnd.position };
+            callback(&self.target, Event::WindowEvent { window_id, event });
             return;
         }
 
         if xev.message_type == atoms[XdndLeave] as c_ulong {
+            let entered = self.dnd.target_window.is_some();
             self.dnd.reset();
             let event = Event::WindowEvent { window_id, event: WindowEvent::HoveredFileCancelled };
             callback(&self.target, event);
+            if entered {
+                callback(&self.target, Event::WindowEvent {
+                    window_id,
+                    event: WindowEvent::DragLeft,
+                });
+            }
         }
     }
 
@@ -566,14 +839,19 @@ impl EventProcessor {
         // Set the timestamp.
         self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
 
+        if window == self.clipboard.window() {
+            self.clipboard_selection_notify(xev);
+            return;
+        }
+
         if xev.property != atoms[XdndSelection] as c_ulong {
             return;
         }
 
         // This is where we receive data from drag and drop
         self.dnd.result = None;
-        if let Ok(mut data) = unsafe { self.dnd.read_data(window) } {
-            let parse_result = self.dnd.parse_data(&mut data);
+        if let Ok(data) = unsafe { self.dnd.read_data(window) } {
+            let parse_result = dnd::parse_uri_list(&data);
             if let Ok(ref path_list) = parse_result {
                 for path in path_list {
                     let event = Event::WindowEvent {
@@ -587,6 +865,29 @@ impl EventProcessor {
         }
     }
 
+    fn clipboard_selection_notify(&mut self, xev: &XSelectionEvent) {
+        self.clipboard.handle_selection_notify(
+            xev.requestor as xproto::Window,
+            xev.selection as xproto::Atom,
+            xev.target as xproto::Atom,
+            xev.property as xproto::Atom,
+        );
+    }
+
+    fn selection_request(&mut self, xev: &XSelectionRequestEvent) {
+        self.clipboard.handle_selection_request(
+            xev.requestor as xproto::Window,
+            xev.selection as xproto::Atom,
+            xev.target as xproto::Atom,
+            xev.property as xproto::Atom,
+            xev.time as xproto::Timestamp,
+        );
+    }
+
+    fn selection_clear(&mut self, xev: &XSelectionClearEvent) {
+        self.clipboard.handle_selection_clear(xev.selection as xproto::Atom);
+    }
+
     fn configure_notify<F>(&self, xev: &XConfigureEvent, mut callback: F)
     where
         F: FnMut(&ActiveEventLoop, Event),
@@ -733,9 +1034,8 @@ impl EventProcessor {
 
         // NOTE: Ensure that the lock is dropped before handling the resized and
         // sending the event back to user.
-        let hittest = {
+        {
             let mut shared_state_lock = window.shared_state_lock();
-            let hittest = shared_state_lock.cursor_hittest;
 
             // This is a hack to ensure that the DPI adjusted resize is actually
             // applied on all WMs. KWin doesn't need this, but Xfwm does. The hack
@@ -753,13 +1053,6 @@ impl EventProcessor {
                     window.request_surface_size_physical(adjusted_size.0, adjusted_size.1);
                 }
             }
-
-            hittest
-        };
-
-        // Reload hittest.
-        if hittest.unwrap_or(false) {
-            let _ = window.set_cursor_hittest(true);
         }
 
         if resized {
@@ -827,6 +1120,8 @@ impl EventProcessor {
     where
         F: FnMut(&ActiveEventLoop, Event),
     {
+        self.clipboard_property_notify(xev);
+
         let atoms = self.target.x_connection().atoms();
         let atom = xev.atom as xproto::Atom;
 
@@ -837,6 +1132,15 @@ impl EventProcessor {
         }
     }
 
+    fn clipboard_property_notify(&mut self, xev: &XPropertyEvent) {
+        self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
+        self.clipboard.handle_property_notify(
+            xev.window as xproto::Window,
+            xev.atom as xproto::Atom,
+            xev.state == xlib::PropertyDelete,
+        );
+    }
+
     fn visibility_notify<F>(&self, xev: &XVisibilityEvent, mut callback: F)
     where
         F: FnMut(&ActiveEventLoop, Event),
@@ -1007,20 +1311,48 @@ impl EventProcessor {
 
         let mask = self.xkb_mod_mask_from_core(state);
         xkb_state.update_modifiers(mask, 0, 0, 0, 0, Self::core_keyboard_group(state));
-        let mods: ModifiersState = xkb_state.modifiers().into();
+        let mods: Modifiers = xkb_state.modifiers().into();
 
-        let event =
-            Event::WindowEvent { window_id, event: WindowEvent::ModifiersChanged(mods.into()) };
+        let event = Event::WindowEvent { window_id, event: WindowEvent::ModifiersChanged(mods) };
 
         callback(&self.target, event);
     }
 
+    /// Dismiss the popups grabbing the input, when pressing outside of the windows of the
+    /// application.
+    fn dismiss_popups<F>(&self, root_x: f64, root_y: f64, callback: &mut F)
+    where
+        F: FnMut(&ActiveEventLoop, Event),
+    {
+        let windows: Vec<Arc<UnownedWindow>> =
+            self.target.windows.borrow().values().filter_map(Weak::upgrade).collect();
+        if !windows.iter().any(|window| window.is_grabbing_popup()) {
+            return;
+        }
+
+        // Like with the grab of Wayland popups, pressing on any of our windows, including the
+        // parent of a popup, keeps the popups.
+        if windows.iter().any(|window| {
+            window.is_visible() == Some(true) && window.contains_root_point(root_x, root_y)
+        }) {
+            return;
+        }
+
+        for popup in windows.iter().filter(|window| window.is_grabbing_popup()) {
+            popup.dismiss_popup();
+            let event = WindowEvent::PopupDismissed;
+            callback(&self.target, Event::WindowEvent { window_id: popup.id(), event });
+        }
+    }
+
     fn xinput2_button_input<F>(&self, event: &XIDeviceEvent, state: ElementState, mut callback: F)
     where
         F: FnMut(&ActiveEventLoop, Event),
     {
-        let window_id = mkwid(event.event as xproto::Window);
+        let window = event.event as xproto::Window;
+        let window_id = mkwid(window);
         let device_id = Some(mkdid(event.deviceid as xinput::DeviceId));
+        let time = Duration::from_millis((event.time as xproto::Timestamp).into());
 
         // Set the timestamp.
         self.target.xconn.set_timestamp(event.time as xproto::Timestamp);
@@ -1030,8 +1362,38 @@ impl EventProcessor {
             return;
         }
 
+        if state == ElementState::Pressed {
+            self.dismiss_popups(event.root_x, event.root_y, &mut callback);
+        }
+
         let position = PhysicalPosition::new(event.event_x, event.event_y);
 
+        if let Some((tool, data)) = self.update_pen(event) {
+            let button = match event.detail as u32 {
+                xlib::Button1 => PenButton::Contact,
+                xlib::Button2 => PenButton::Barrel,
+                xlib::Button3 => PenButton::SecondaryBarrel,
+                x => PenButton::Other(x as u16),
+            };
+
+            let source_id = event.sourceid as xinput::DeviceId;
+            self.update_pen_proximity(
+                Some((event.event as xproto::Window, source_id, tool)),
+                position,
+                &mut callback,
+            );
+
+            let event = WindowEvent::PointerButton {
+                device_id: Some(mkdid(source_id)),
+                primary: true,
+                state,
+                position,
+                button: ButtonSource::Pen { tool, button, data },
+            };
+            callback(&self.target, Event::WindowEvent { window_id, event });
+            return;
+        }
+
         let event = match event.detail as u32 {
             xlib::Button1 => WindowEvent::PointerButton {
                 device_id,
@@ -1060,16 +1422,21 @@ impl EventProcessor {
             // those. In practice, even clicky scroll wheels appear to be reported by
             // evdev (and XInput2 in turn) as axis motion, so we don't otherwise
             // special-case these button presses.
-            4..=7 => WindowEvent::MouseWheel {
-                device_id,
-                delta: match event.detail {
-                    4 => MouseScrollDelta::LineDelta(0.0, 1.0),
-                    5 => MouseScrollDelta::LineDelta(0.0, -1.0),
-                    6 => MouseScrollDelta::LineDelta(1.0, 0.0),
-                    7 => MouseScrollDelta::LineDelta(-1.0, 0.0),
+            4..=7 => {
+                let (x, y) = match event.detail {
+                    4 => (0, 1),
+                    5 => (0, -1),
+                    6 => (1, 0),
+                    7 => (-1, 0),
                     _ => unreachable!(),
-                },
-                phase: TouchPhase::Moved,
+                };
+                WindowEvent::MouseWheel {
+                    device_id,
+                    delta: MouseScrollDelta::LineDelta(x as f32, y as f32),
+                    phase: TouchPhase::Moved,
+                    source: Some(ScrollSource::Wheel),
+                    value120: Some((x * 120, y * 120)),
+                }
             },
             8 => WindowEvent::PointerButton {
                 device_id,
@@ -1095,8 +1462,18 @@ impl EventProcessor {
             },
         };
 
+        let button = match event {
+            WindowEvent::PointerButton { button: ButtonSource::Mouse(button), .. } => Some(button),
+            _ => None,
+        };
+
         let event = Event::WindowEvent { window_id, event };
         callback(&self.target, event);
+
+        // The zones act after the application got the click, like native decorations.
+        if let Some(button) = button {
+            self.with_window(window, |window| window.hit_test_click(button, state, position, time));
+        }
     }
 
     fn xinput2_mouse_motion<F>(&self, event: &XIDeviceEvent, mut callback: F)
@@ -1110,14 +1487,36 @@ impl EventProcessor {
         let window = event.event as xproto::Window;
         let window_id = mkwid(window);
         let new_cursor_pos = (event.event_x, event.event_y);
+        let pen = self.update_pen(event);
 
         let cursor_moved = self.with_window(window, |window| {
             let mut shared_state_lock = window.shared_state_lock();
             util::maybe_change(&mut shared_state_lock.cursor_pos, new_cursor_pos)
         });
 
+        // The axes of a pen may change without moving it.
+        if let Some((tool, data)) = pen {
+            if cursor_moved.is_none() {
+                return;
+            }
+
+            let source_id = event.sourceid as xinput::DeviceId;
+            let position = PhysicalPosition::new(event.event_x, event.event_y);
+            self.update_pen_proximity(Some((window, source_id, tool)), position, &mut callback);
+
+            let event = WindowEvent::PointerMoved {
+                device_id: Some(mkdid(source_id)),
+                primary: true,
+                position,
+                source: PointerSource::Pen { tool, data },
+            };
+            callback(&self.target, Event::WindowEvent { window_id, event });
+            return;
+        }
+
         if cursor_moved == Some(true) {
             let position = PhysicalPosition::new(event.event_x, event.event_y);
+            self.update_pen_proximity(None, position, &mut callback);
 
             let event = Event::WindowEvent {
                 window_id,
@@ -1158,14 +1557,28 @@ impl EventProcessor {
                 let delta = (x - info.position) / info.increment;
                 info.position = x;
                 // X11 vertical scroll coordinates are opposite to winit's
-                let delta = match info.orientation {
+                let (delta, value120) = match info.orientation {
                     ScrollOrientation::Horizontal => {
-                        MouseScrollDelta::LineDelta(-delta as f32, 0.0)
+                        (MouseScrollDelta::LineDelta(-delta as f32, 0.0), (-delta, 0.0))
+                    },
+                    ScrollOrientation::Vertical => {
+                        (MouseScrollDelta::LineDelta(0.0, -delta as f32), (0.0, -delta))
                     },
-                    ScrollOrientation::Vertical => MouseScrollDelta::LineDelta(0.0, -delta as f32),
                 };
 
-                let event = WindowEvent::MouseWheel { device_id, delta, phase: TouchPhase::Moved };
+                // The increment of the valuator is a detent of the wheel.
+                let source = physical_device.scroll_source;
+                let value120 = (source == ScrollSource::Wheel).then(|| {
+                    ((value120.0 * 120.0).round() as i32, (value120.1 * 120.0).round() as i32)
+                });
+
+                let event = WindowEvent::MouseWheel {
+                    device_id,
+                    delta,
+                    phase: TouchPhase::Moved,
+                    source: Some(source),
+                    value120,
+                };
                 events.push(Event::WindowEvent { window_id, event });
             }
 
@@ -1177,6 +1590,53 @@ impl EventProcessor {
         }
     }
 
+    /// Update the axes of the pen which is the source of `event`, if any.
+    fn update_pen(&self, event: &XIDeviceEvent) -> Option<(PenTool, PenData)> {
+        let mut devices = self.devices.borrow_mut();
+        let pen = devices.get_mut(&mkdid(event.sourceid as xinput::DeviceId))?.pen.as_mut()?;
+        for (number, value) in valuator_values(&event.valuators) {
+            pen.update(number, value);
+        }
+        Some((pen.tool, pen.data))
+    }
+
+    /// Report the pen entering or leaving proximity of a window, once its events start or stop.
+    fn update_pen_proximity<F>(
+        &self,
+        pen: Option<(xproto::Window, xinput::DeviceId, PenTool)>,
+        position: PhysicalPosition<f64>,
+        callback: &mut F,
+    ) where
+        F: FnMut(&ActiveEventLoop, Event),
+    {
+        let previous = self.pen_proximity.replace(pen);
+        if previous == pen {
+            return;
+        }
+
+        if let Some((window, device, tool)) =
+            previous.filter(|&(window, ..)| self.window_exists(window))
+        {
+            let event = WindowEvent::PointerLeft {
+                device_id: Some(mkdid(device)),
+                primary: true,
+                position: None,
+                kind: PointerKind::Pen(tool),
+            };
+            callback(&self.target, Event::WindowEvent { window_id: mkwid(window), event });
+        }
+
+        if let Some((window, device, tool)) = pen {
+            let event = WindowEvent::PointerEntered {
+                device_id: Some(mkdid(device)),
+                primary: true,
+                position,
+                kind: PointerKind::Pen(tool),
+            };
+            callback(&self.target, Event::WindowEvent { window_id: mkwid(window), event });
+        }
+    }
+
     fn xinput2_mouse_enter<F>(&self, event: &XIEnterEvent, mut callback: F)
     where
         F: FnMut(&ActiveEventLoop, Event),
@@ -1231,6 +1691,11 @@ impl EventProcessor {
         // Set the timestamp.
         self.target.xconn.set_timestamp(event.time as xproto::Timestamp);
 
+        let position = PhysicalPosition::new(event.event_x, event.event_y);
+        if self.pen_proximity.get().is_some_and(|(pen_window, ..)| pen_window == window) {
+            self.update_pen_proximity(None, position, &mut callback);
+        }
+
         // Leave, FocusIn, and FocusOut can be received by a window that's already
         // been destroyed, which the user presumably doesn't want to deal with.
         if self.window_exists(window) {
@@ -1239,7 +1704,7 @@ impl EventProcessor {
                 event: WindowEvent::PointerLeft {
                     device_id: Some(mkdid(event.deviceid as xinput::DeviceId)),
                     primary: true,
-                    position: Some(PhysicalPosition::new(event.event_x, event.event_y)),
+                    position: Some(position),
                     kind: PointerKind::Mouse,
                 },
             };
@@ -1457,6 +1922,80 @@ impl EventProcessor {
         }
     }
 
+    fn xinput2_gesture_pinch<F>(&self, xev: &ffi::XIGesturePinchEvent, mut callback: F)
+    where
+        F: FnMut(&ActiveEventLoop, Event),
+    {
+        // Set the timestamp.
+        self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
+
+        let window = xev.event as xproto::Window;
+        if !self.window_exists(window) {
+            return;
+        }
+
+        let (phase, scale) = match xev.evtype {
+            ffi::XI_GesturePinchBegin => (TouchPhase::Started, 1.0),
+            ffi::XI_GesturePinchUpdate => (TouchPhase::Moved, xev.scale),
+            _ if xev.flags & ffi::XIGesturePinchEventCancelled != 0 => {
+                (TouchPhase::Cancelled, self.pinch_scale.get())
+            },
+            _ => (TouchPhase::Ended, self.pinch_scale.get()),
+        };
+        let delta = scale - self.pinch_scale.replace(scale);
+        if phase != TouchPhase::Moved {
+            self.pinch_scale.set(1.0);
+        }
+
+        // The gestures are reported for the touchpad rather than the master pointer.
+        let window_id = mkwid(window);
+        let device_id = Some(mkdid(xev.sourceid as xinput::DeviceId));
+        let event = Event::WindowEvent {
+            window_id,
+            event: WindowEvent::PinchGesture { device_id, delta, phase },
+        };
+        callback(&self.target, event);
+
+        // XInput 2 rotates clockwise, while the event is counterclockwise. Like on Wayland, only
+        // updates carry a change, so the gesture ends without moving.
+        let delta = if phase == TouchPhase::Moved { -xev.delta_angle as f32 } else { 0. };
+        let event = Event::WindowEvent {
+            window_id,
+            event: WindowEvent::RotationGesture { device_id, delta, phase },
+        };
+        callback(&self.target, event);
+    }
+
+    fn xinput2_gesture_swipe<F>(&self, xev: &ffi::XIGestureSwipeEvent, mut callback: F)
+    where
+        F: FnMut(&ActiveEventLoop, Event),
+    {
+        // Set the timestamp.
+        self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
+
+        let window = xev.event as xproto::Window;
+        if !self.window_exists(window) {
+            return;
+        }
+
+        let phase = match xev.evtype {
+            ffi::XI_GestureSwipeBegin => TouchPhase::Started,
+            ffi::XI_GestureSwipeUpdate => TouchPhase::Moved,
+            _ if xev.flags & ffi::XIGestureSwipeEventCancelled != 0 => TouchPhase::Cancelled,
+            _ => TouchPhase::Ended,
+        };
+
+        let event = Event::WindowEvent {
+            window_id: mkwid(window),
+            event: WindowEvent::PanGesture {
+                device_id: Some(mkdid(xev.sourceid as xinput::DeviceId)),
+                delta: PhysicalPosition::new(xev.delta_x as f32, xev.delta_y as f32),
+                phase,
+            },
+        };
+        callback(&self.target, event);
+    }
+
     fn xinput2_raw_button_input<F>(&self, xev: &XIRawEvent, state: ElementState, mut callback: F)
     where
         F: FnMut(&ActiveEventLoop, Event),
@@ -1464,13 +2003,35 @@ impl EventProcessor {
         // Set the timestamp.
         self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
 
-        if xev.flags & xinput2::XIPointerEmulated == 0 {
+        if xev.flags & xinput2::XIPointerEmulated != 0 {
+            return;
+        }
+
+        // Pads report their buttons after the scroll buttons 4 to 7 of the core protocol.
+        if self
+            .devices
+            .borrow()
+            .get(&mkdid(xev.sourceid as xinput::DeviceId))
+            .is_some_and(|device| device.pad.is_some())
+        {
+            let button = match xev.detail as u32 {
+                button @ 1..=3 => button - 1,
+                button @ 8.. => button - 5,
+                _ => return,
+            };
             let event = Event::DeviceEvent {
-                device_id: Some(mkdid(xev.deviceid as xinput::DeviceId)),
-                event: DeviceEvent::Button { state, button: xev.detail as u32 },
+                device_id: Some(mkdid(xev.sourceid as xinput::DeviceId)),
+                event: DeviceEvent::PadButton { button, group: 0, mode: 0, state },
             };
             callback(&self.target, event);
+            return;
         }
+
+        let event = Event::DeviceEvent {
+            device_id: Some(mkdid(xev.deviceid as xinput::DeviceId)),
+            event: DeviceEvent::Button { state, button: xev.detail as u32 },
+        };
+        callback(&self.target, event);
     }
 
     fn xinput2_raw_mouse_motion<F>(&self, xev: &XIRawEvent, mut callback: F)
@@ -1480,6 +2041,20 @@ impl EventProcessor {
         // Set the timestamp.
         self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
 
+        // The rings and strips of pads aren't pointer axes.
+        let source_id = mkdid(xev.sourceid as xinput::DeviceId);
+        let pad_events = self.devices.borrow().get(&source_id).and_then(|device| {
+            let pad = device.pad.as_ref()?;
+            let values = valuator_values(&xev.valuators).into_iter();
+            Some(values.filter_map(|(number, value)| pad.event(number, value)).collect::<Vec<_>>())
+        });
+        if let Some(pad_events) = pad_events {
+            for event in pad_events {
+                callback(&self.target, Event::DeviceEvent { device_id: Some(source_id), event });
+            }
+            return;
+        }
+
         let did = Some(mkdid(xev.deviceid as xinput::DeviceId));
 
         let mask =
@@ -1545,16 +2120,25 @@ impl EventProcessor {
         });
     }
 
-    fn xinput2_hierarchy_changed(&mut self, xev: &XIHierarchyEvent) {
+    fn xinput2_hierarchy_changed<F>(&mut self, xev: &XIHierarchyEvent, mut callback: F)
+    where
+        F: FnMut(&ActiveEventLoop, Event),
+    {
         // Set the timestamp.
         self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
         let infos = unsafe { slice::from_raw_parts(xev.info, xev.num_info as usize) };
         for info in infos {
+            let device_id = mkdid(info.deviceid as xinput::DeviceId);
             if 0 != info.flags & (xinput2::XISlaveAdded | xinput2::XIMasterAdded) {
                 self.init_device(info.deviceid as xinput::DeviceId);
+                let event =
+                    Event::DeviceEvent { device_id: Some(device_id), event: DeviceEvent::Added };
+                callback(&self.target, event);
             } else if 0 != info.flags & (xinput2::XISlaveRemoved | xinput2::XIMasterRemoved) {
-                let mut devices = self.devices.borrow_mut();
-                devices.remove(&mkdid(info.deviceid as xinput::DeviceId));
+                self.devices.borrow_mut().remove(&device_id);
+                let event =
+                    Event::DeviceEvent { device_id: Some(device_id), event: DeviceEvent::Removed };
+                callback(&self.target, event);
             }
         }
     }
@@ -1582,6 +2166,8 @@ impl EventProcessor {
                     let xcb = self.target.xconn.xcb_connection().get_raw_xcb_connection();
                     self.xkb_context.set_keymap_from_x11(xcb);
                     self.xmodmap.reload_from_x_connection(&self.target.xconn);
+                    *self.target.keyboard_layouts.borrow_mut() = self.xkb_context.layouts();
+                    self.target.key_lookup_xkb_context.take();
 
                     let window_id = match self.active_window.map(super::mkwid) {
                         Some(window_id) => window_id,
@@ -1598,6 +2184,8 @@ impl EventProcessor {
                 let xcb = self.target.xconn.xcb_connection().get_raw_xcb_connection();
                 self.xkb_context.set_keymap_from_x11(xcb);
                 self.xmodmap.reload_from_x_connection(&self.target.xconn);
+                *self.target.keyboard_layouts.borrow_mut() = self.xkb_context.layouts();
+                self.target.key_lookup_xkb_context.take();
                 let window_id = match self.active_window.map(super::mkwid) {
                     Some(window_id) => window_id,
                     None => return,
@@ -1615,6 +2203,7 @@ impl EventProcessor {
                 self.target.xconn.set_timestamp(xev.time as xproto::Timestamp);
 
                 if let Some(state) = self.xkb_context.state_mut() {
+                    let layout = state.active_layout();
                     state.update_modifiers(
                         xev.base_mods,
                         xev.latched_mods,
@@ -1623,6 +2212,7 @@ impl EventProcessor {
                         xev.latched_group as u32,
                         xev.locked_group as u32,
                     );
+                    let layout_changed = state.active_layout() != layout;
 
                     let window_id = match self.active_window.map(super::mkwid) {
                         Some(window_id) => window_id,
@@ -1631,6 +2221,25 @@ impl EventProcessor {
 
                     let mods = state.modifiers().into();
                     self.send_modifiers(window_id, mods, true, &mut callback);
+                    if layout_changed {
+                        self.send_keyboard_layout(window_id, &mut callback);
+                    }
+                }
+            },
+            xlib::XkbControlsNotify => {
+                // Xlib doesn't describe the event, so the controls are queried again to find out
+                // whether the key repeat changed.
+                let Ok(key_repeat_info) = self.target.xconn.key_repeat_info() else {
+                    return;
+                };
+
+                if self.key_repeat_info.replace(key_repeat_info) != Some(key_repeat_info) {
+                    let device_id = mkdid(self.xkb_context.core_keyboard_id as xinput::DeviceId);
+                    let event = Event::DeviceEvent {
+                        device_id: Some(device_id),
+                        event: DeviceEvent::KeyRepeatInfoChanged(key_repeat_info),
+                    };
+                    callback(&self.target, event);
                 }
             },
             _ => {},
@@ -1647,6 +2256,7 @@ impl EventProcessor {
         F: FnMut(&ActiveEventLoop, Event),
     {
         if let Some(state) = self.xkb_context.state_mut() {
+            let layout = state.active_layout();
             state.update_modifiers(
                 mods.base as u32,
                 mods.latched as u32,
@@ -1655,6 +2265,7 @@ impl EventProcessor {
                 group.latched as u32,
                 group.locked as u32,
             );
+            let layout_changed = state.active_layout() != layout;
 
             // NOTE: we use active window since generally sub windows don't have keyboard input,
             // and winit assumes that unfocused window doesn't have modifiers.
@@ -1665,6 +2276,21 @@ impl EventProcessor {
 
             let mods = state.modifiers();
             self.send_modifiers(window_id, mods.into(), force, &mut callback);
+            if layout_changed {
+                self.send_keyboard_layout(window_id, &mut callback);
+            }
+        }
+    }
+
+    /// Send the active keyboard layout to the window after the user switched it.
+    fn send_keyboard_layout<F: FnMut(&ActiveEventLoop, Event)>(
+        &self,
+        window_id: crate::window::WindowId,
+        callback: &mut F,
+    ) {
+        if let Some(layout) = self.xkb_context.active_layout() {
+            let event = WindowEvent::KeyboardLayoutChanged(layout);
+            callback(&self.target, Event::WindowEvent { window_id, event });
         }
     }
 
@@ -1782,7 +2408,7 @@ impl EventProcessor {
     fn send_modifiers<F: FnMut(&ActiveEventLoop, Event)>(
         &self,
         window_id: crate::window::WindowId,
-        modifiers: ModifiersState,
+        modifiers: Modifiers,
         force: bool,
         callback: &mut F,
     ) {
@@ -1791,7 +2417,7 @@ impl EventProcessor {
         if self.modifiers.replace(modifiers) != modifiers || force {
             let event = Event::WindowEvent {
                 window_id,
-                event: WindowEvent::ModifiersChanged(self.modifiers.get().into()),
+                event: WindowEvent::ModifiersChanged(self.modifiers.get()),
             };
             callback(&self.target, event);
         }
@@ -1873,6 +2499,32 @@ impl EventProcessor {
     }
 }
 
+/// The values of the valuators set in `state`, along with their number.
+fn valuator_values(state: &xinput2::XIValuatorState) -> Vec<(i32, f64)> {
+    let mask = unsafe { slice::from_raw_parts(state.mask, state.mask_len as usize) };
+    let mut values = Vec::new();
+    let mut value = state.values;
+    for i in 0..state.mask_len * 8 {
+        if xinput2::XIMaskIsSet(mask, i) {
+            values.push((i, unsafe { *value }));
+            value = unsafe { value.offset(1) };
+        }
+    }
+    values
+}
+
+/// Whether the button released by the event was the last one pressed, ignoring the wheel.
+fn is_last_button_release(event: &XIDeviceEvent) -> bool {
+    let mask =
+        unsafe { slice::from_raw_parts(event.buttons.mask, event.buttons.mask_len as usize) };
+    // Buttons 4 to 7 are the scroll wheel.
+    let is_button = |button: i32| button != 0 && !(4..=7).contains(&button);
+    is_button(event.detail)
+        && !(0..event.buttons.mask_len * 8).any(|button| {
+            button != event.detail && is_button(button) && xinput2::XIMaskIsSet(mask, button)
+        })
+}
+
 fn is_first_touch(first: &mut Option<u32>, num: &mut u32, id: u32, phase: i32) -> bool {
     match phase {
         xinput2::XI_TouchBegin => {
@@ -1,4 +1,96 @@
+// XInput 2.4 gesture events, which aren't part of `x11_dl` yet.
+pub use gesture::*;
 pub use x11_dl::error::OpenError;
 pub use x11_dl::xinput2::*;
 pub use x11_dl::xlib::*;
 pub use x11_dl::xlib_xcb::*;
+
+#[allow(non_upper_case_globals)]
+mod gesture {
+    use std::os::raw::{c_double, c_int, c_ulong};
+
+    use x11_dl::xinput2::{XIGroupState, XIModifierState};
+    use x11_dl::xlib::{Bool, Display, Time, Window};
+
+    pub const XI_GesturePinchBegin: c_int = 27;
+    pub const XI_GesturePinchUpdate: c_int = 28;
+    pub const XI_GesturePinchEnd: c_int = 29;
+    pub const XI_GestureSwipeBegin: c_int = 30;
+    pub const XI_GestureSwipeUpdate: c_int = 31;
+    pub const XI_GestureSwipeEnd: c_int = 32;
+
+    // The masks of events above 31 don't fit in `xinput::XIEventMask`.
+    pub const XI_GesturePinchBeginMask: u64 = 1 << XI_GesturePinchBegin;
+    pub const XI_GesturePinchUpdateMask: u64 = 1 << XI_GesturePinchUpdate;
+    pub const XI_GesturePinchEndMask: u64 = 1 << XI_GesturePinchEnd;
+    pub const XI_GestureSwipeBeginMask: u64 = 1 << XI_GestureSwipeBegin;
+    pub const XI_GestureSwipeUpdateMask: u64 = 1 << XI_GestureSwipeUpdate;
+    pub const XI_GestureSwipeEndMask: u64 = 1 << XI_GestureSwipeEnd;
+
+    pub const XIGesturePinchEventCancelled: c_int = 1 << 0;
+    pub const XIGestureSwipeEventCancelled: c_int = 1 << 0;
+
+    #[derive(Debug, Clone, Copy)]
+    #[repr(C)]
+    pub struct XIGesturePinchEvent {
+        pub _type: c_int,
+        pub serial: c_ulong,
+        pub send_event: Bool,
+        pub display: *mut Display,
+        pub extension: c_int,
+        pub evtype: c_int,
+        pub time: Time,
+        pub deviceid: c_int,
+        pub sourceid: c_int,
+        /// The number of touches in the gesture.
+        pub detail: c_int,
+        pub root: Window,
+        pub event: Window,
+        pub child: Window,
+        pub root_x: c_double,
+        pub root_y: c_double,
+        pub event_x: c_double,
+        pub event_y: c_double,
+        pub delta_x: c_double,
+        pub delta_y: c_double,
+        pub delta_unaccel_x: c_double,
+        pub delta_unaccel_y: c_double,
+        /// The absolute scale since the start of the gesture.
+        pub scale: c_double,
+        /// The relative clockwise rotation in degrees.
+        pub delta_angle: c_double,
+        pub flags: c_int,
+        pub mods: XIModifierState,
+        pub group: XIGroupState,
+    }
+
+    #[derive(Debug, Clone, Copy)]
+    #[repr(C)]
+    pub struct XIGestureSwipeEvent {
+        pub _type: c_int,
+        pub serial: c_ulong,
+        pub send_event: Bool,
+        pub display: *mut Display,
+        pub extension: c_int,
+        pub evtype: c_int,
+        pub time: Time,
+        pub deviceid: c_int,
+        pub sourceid: c_int,
+        /// The number of touches in the gesture.
+        pub detail: c_int,
+        pub root: Window,
+        pub event: Window,
+        pub child: Window,
+        pub root_x: c_double,
+        pub root_y: c_double,
+        pub event_x: c_double,
+        pub event_y: c_double,
+        pub delta_x: c_double,
+        pub delta_y: c_double,
+        pub delta_unaccel_x: c_double,
+        pub delta_unaccel_y: c_double,
+        pub flags: c_int,
+        pub mods: XIModifierState,
+        pub group: XIGroupState,
+    }
+}
@@ -24,12 +24,16 @@ use x11rb::xcb_ffi::ReplyOrIdError;
 
 use crate::application::ApplicationHandler;
 use crate::error::{EventLoopError, RequestError};
-use crate::event::{DeviceId, Event, StartCause, WindowEvent};
+use crate::event::{
+    DeviceEvent, DeviceId, DeviceInfo as CoreDeviceInfo, DeviceKind, Event, Force, PenData,
+    PenTilt, PenTool, ScrollSource, StartCause, WindowEvent,
+};
 use crate::event_loop::{
     ActiveEventLoop as RootActiveEventLoop, ControlFlow, DeviceEvents,
     EventLoopProxy as CoreEventLoopProxy, EventLoopProxyProvider,
     OwnedDisplayHandle as CoreOwnedDisplayHandle,
 };
+use crate::keyboard::{KeyRepeatInfo, KeyboardLayout};
 use crate::platform::pump_events::PumpStatus;
 use crate::platform_impl::common::xkb::Context;
 use crate::platform_impl::platform::min_timeout;
@@ -42,18 +46,23 @@ use crate::window::{
 
 mod activation;
 mod atoms;
+mod clipboard;
 mod dnd;
+mod drag;
 mod event_processor;
 pub mod ffi;
 mod ime;
 mod monitor;
+mod popup;
 mod util;
 pub(crate) mod window;
 mod xdisplay;
 mod xsettings;
 
 use atoms::*;
-use dnd::{Dnd, DndState};
+use clipboard::{Clipboard, ClipboardRequest};
+use dnd::{Dnd, DndState, DropRequest};
+use drag::{DragRequest, DragSource};
 use event_processor::{EventProcessor, MAX_MOD_REPLAY_LEN};
 use ime::{Ime, ImeCreationError, ImeReceiver, ImeRequest, ImeSender};
 pub(crate) use monitor::{MonitorHandle, VideoModeHandle};
@@ -66,6 +75,9 @@ const ALL_DEVICES: u16 = 0;
 const ALL_MASTER_DEVICES: u16 = 1;
 const ICONIC_STATE: u32 = 3;
 
+/// How long to wait for the clipboard manager to save our clipboard on exit.
+const CLIPBOARD_MANAGER_TIMEOUT: Duration = Duration::from_secs(1);
+
 /// The underlying x11rb connection that we are using.
 type X11rbConnection = x11rb::xcb_ffi::XCBConnection;
 
@@ -140,8 +152,18 @@ pub struct ActiveEventLoop {
     windows: RefCell<HashMap<WindowId, Weak<UnownedWindow>>>,
     redraw_sender: WakeSender<WindowId>,
     activation_sender: WakeSender<ActivationToken>,
+    clipboard_sender: WakeSender<ClipboardRequest>,
+    drag_sender: WakeSender<DragRequest>,
+    drop_sender: WakeSender<DropRequest>,
     event_loop_proxy: CoreEventLoopProxy,
     device_events: Cell<DeviceEvents>,
+    /// Whether the server supports the touchpad gestures of XInput 2.4.
+    xi2_gestures: bool,
+    /// The layouts of the keymap, kept up to date by the event processor which owns it.
+    keyboard_layouts: RefCell<Vec<KeyboardLayout>>,
+    /// A keymap of its own to look up keys, dropped by the event processor when the keymap
+    /// changes.
+    key_lookup_xkb_context: RefCell<Option<Context>>,
 }
 
 pub struct EventLoop {
@@ -150,6 +172,9 @@ pub struct EventLoop {
     event_processor: EventProcessor,
     redraw_receiver: PeekableReceiver<WindowId>,
     activation_receiver: PeekableReceiver<ActivationToken>,
+    clipboard_receiver: PeekableReceiver<ClipboardRequest>,
+    drag_receiver: PeekableReceiver<DragRequest>,
+    drop_receiver: PeekableReceiver<DropRequest>,
 
     /// The current state of the event loop.
     state: EventLoopState,
@@ -177,6 +202,9 @@ impl EventLoop {
         let dnd = Dnd::new(Arc::clone(&xconn))
             .expect("Failed to call XInternAtoms when initializing drag and drop");
 
+        let clipboard =
+            Clipboard::new(Arc::clone(&xconn)).expect("Failed to create the clipboard window");
+
         let (ime_sender, ime_receiver) = mpsc::channel();
         let (ime_event_sender, ime_event_receiver) = mpsc::channel();
         // Input methods will open successfully without setting the locale, but it won't be
@@ -226,13 +254,14 @@ impl EventLoop {
             .expect("Failed to query XKB extension")
             .expect("X server missing XKB extension");
 
-        // Check for XInput2 support.
-        xconn
+        // Check for XInput2 support, the gesture events need XInput 2.4.
+        let xi2_version = xconn
             .xcb_connection()
-            .xinput_xi_query_version(2, 3)
+            .xinput_xi_query_version(2, 4)
             .expect("Failed to send XInput2 query version request")
             .reply()
             .expect("Error while checking for XInput2 query version reply");
+        let xi2_gestures = (xi2_version.major_version, xi2_version.minor_version) >= (2, 4);
 
         xconn.update_cached_wm_info(root);
 
@@ -270,6 +299,15 @@ impl EventLoop {
         // Create a channel for sending activation tokens.
         let (activation_token_sender, activation_token_channel) = mpsc::channel();
 
+        // Create a channel for clipboard requests.
+        let (clipboard_sender, clipboard_channel) = mpsc::channel();
+
+        // Create a channel for starting drags.
+        let (drag_sender, drag_channel) = mpsc::channel();
+
+        // Create a channel for the responses to drags over our windows.
+        let (drop_sender, drop_channel) = mpsc::channel();
+
         // Create a channel for sending user events.
         let (user_waker, user_waker_source) =
             calloop::ping::make_ping().expect("Failed to create user event loop waker.");
@@ -307,16 +345,37 @@ impl EventLoop {
                 sender: activation_token_sender, // not used again so no clone
                 waker: waker.clone(),
             },
+            clipboard_sender: WakeSender {
+                sender: clipboard_sender, // not used again so no clone
+                waker: waker.clone(),
+            },
+            drag_sender: WakeSender {
+                sender: drag_sender, // not used again so no clone
+                waker: waker.clone(),
+            },
+            drop_sender: WakeSender {
+                sender: drop_sender, // not used again so no clone
+                waker: waker.clone(),
+            },
             event_loop_proxy: event_loop_proxy.into(),
             device_events: Default::default(),
+            xi2_gestures,
+            keyboard_layouts: RefCell::new(xkb_context.layouts()),
+            key_lookup_xkb_context: Default::default(),
         };
 
         // Set initial device event filter.
         window_target.update_listen_device_events(true);
 
+        // Changes of the key repeat are reported against the initial one.
+        let key_repeat_info = window_target.xconn.key_repeat_info().ok();
+
         let event_processor = EventProcessor {
             target: window_target,
             dnd,
+            clipboard,
+            drag: None,
+            pending_drag_events: Vec::new(),
             devices: Default::default(),
             randr_event_offset,
             ime_receiver,
@@ -329,6 +388,9 @@ impl EventLoop {
             num_touch: 0,
             held_key_press: None,
             first_touch: None,
+            pen_proximity: Default::default(),
+            pinch_scale: Cell::new(1.0),
+            key_repeat_info,
             active_window: None,
             modifiers: Default::default(),
             is_composing: false,
@@ -353,7 +415,8 @@ impl EventLoop {
                 0x100, // Use the "core keyboard device"
                 xkb::EventType::NEW_KEYBOARD_NOTIFY
                     | xkb::EventType::MAP_NOTIFY
-                    | xkb::EventType::STATE_NOTIFY,
+                    | xkb::EventType::STATE_NOTIFY
+                    | xkb::EventType::CONTROLS_NOTIFY,
             )
             .unwrap();
 
@@ -365,6 +428,9 @@ impl EventLoop {
             event_processor,
             redraw_receiver: PeekableReceiver::from_recv(redraw_channel),
             activation_receiver: PeekableReceiver::from_recv(activation_token_channel),
+            clipboard_receiver: PeekableReceiver::from_recv(clipboard_channel),
+            drag_receiver: PeekableReceiver::from_recv(drag_channel),
+            drop_receiver: PeekableReceiver::from_recv(drop_channel),
             state: EventLoopState { x11_readiness: Readiness::EMPTY, proxy_wake_up: false },
         }
     }
@@ -429,6 +495,8 @@ impl EventLoop {
         if let Some(code) = self.exit_code() {
             self.loop_running = false;
 
+            self.save_clipboard();
+
             app.exiting(self.window_target());
 
             PumpStatus::Exit(code)
@@ -441,6 +509,10 @@ impl EventLoop {
         self.event_processor.poll()
             || self.state.proxy_wake_up
             || self.redraw_receiver.has_incoming()
+            || self.clipboard_receiver.has_incoming()
+            || self.drag_receiver.has_incoming()
+            || self.drop_receiver.has_incoming()
+            || self.event_processor.clipboard.has_completed()
     }
 
     fn poll_events_with_timeout<A: ApplicationHandler>(
@@ -464,7 +536,14 @@ impl EventLoop {
                 },
             };
 
-            min_timeout(control_flow_timeout, timeout)
+            // Wake up to fail the clipboard read of which the owner doesn't answer.
+            let clipboard_timeout = self
+                .event_processor
+                .clipboard
+                .next_deadline()
+                .map(|deadline| deadline.saturating_duration_since(start));
+
+            min_timeout(min_timeout(control_flow_timeout, timeout), clipboard_timeout)
         };
 
         self.state.x11_readiness = Readiness::EMPTY;
@@ -477,6 +556,8 @@ impl EventLoop {
             return;
         }
 
+        self.event_processor.clipboard.fail_expired_read(Instant::now());
+
         // NB: `StartCause::Init` is handled as a special case and doesn't need
         // to be considered here
         let cause = match self.control_flow() {
@@ -543,6 +624,25 @@ impl EventLoop {
             }
         }
 
+        // Handle clipboard and drag and drop requests.
+        while let Ok(request) = self.clipboard_receiver.try_recv() {
+            self.event_processor.clipboard.handle_request(request);
+        }
+        while let Ok(request) = self.drag_receiver.try_recv() {
+            self.event_processor.start_drag(request);
+        }
+        while let Ok(request) = self.drop_receiver.try_recv() {
+            self.event_processor.handle_drop_request(request);
+        }
+        let mut callback = |target: &ActiveEventLoop, event| {
+            if let Event::WindowEvent { window_id, event } = event {
+                app.window_event(target, window_id, event);
+            }
+        };
+        self.event_processor.drain_clipboard_events(&mut callback);
+        self.event_processor.drain_drag_events(&mut callback);
+        self.event_processor.update_drop();
+
         // Empty the user event buffer
         if mem::take(&mut self.state.proxy_wake_up) {
             app.proxy_wake_up(&self.event_processor.target);
@@ -593,6 +693,32 @@ impl EventLoop {
         }
     }
 
+    /// Hand the clipboard contents over to the clipboard manager, so they survive the exit.
+    fn save_clipboard(&mut self) {
+        if !self.event_processor.clipboard.save_to_manager() {
+            return;
+        }
+
+        let deadline = Instant::now() + CLIPBOARD_MANAGER_TIMEOUT;
+        loop {
+            self.event_processor.drain_selection_events();
+            if !self.event_processor.clipboard.is_saving() {
+                break;
+            }
+
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            if timeout.is_zero() {
+                warn!("Timed out while handing the clipboard over to the clipboard manager");
+                break;
+            }
+
+            if let Err(error) = self.event_loop.dispatch(Some(timeout), &mut self.state) {
+                warn!("Failed to poll for events: {error:?}");
+                break;
+            }
+        }
+    }
+
     fn control_flow(&self) -> ControlFlow {
         self.event_processor.target.control_flow()
     }
@@ -701,6 +827,55 @@ impl RootActiveEventLoop for ActiveEventLoop {
             .map(|inner| crate::monitor::MonitorHandle { inner })
     }
 
+    fn available_devices(&self) -> Box<dyn Iterator<Item = CoreDeviceInfo>> {
+        let devices: Vec<_> = DeviceInfo::get(&self.xconn, ffi::XIAllDevices)
+            .map(|info| info.iter().map(|info| Device::info(&self.xconn, info)).collect())
+            .unwrap_or_default();
+        Box::new(devices.into_iter())
+    }
+
+    fn device_info(&self, device_id: DeviceId) -> Option<CoreDeviceInfo> {
+        let info = DeviceInfo::get(&self.xconn, device_id.into_raw() as c_int)?;
+        info.first().map(|info| Device::info(&self.xconn, info))
+    }
+
+    fn key_repeat_info(&self) -> Option<KeyRepeatInfo> {
+        self.xconn.key_repeat_info().ok()
+    }
+
+    fn keyboard_layouts(&self) -> Box<dyn Iterator<Item = KeyboardLayout>> {
+        Box::new(self.keyboard_layouts.borrow().clone().into_iter())
+    }
+
+    fn key_for_physical_key(
+        &self,
+        physical_key: crate::keyboard::PhysicalKey,
+        modifiers: crate::keyboard::ModifiersState,
+        locked_keys: crate::keyboard::LockKeys,
+        level3: bool,
+    ) -> Option<crate::keyboard::Key> {
+        let mut xkb_context = self.key_lookup_xkb_context.borrow_mut();
+        if xkb_context.is_none() {
+            let xcb = self.xconn.xcb_connection().get_raw_xcb_connection();
+            *xkb_context = Context::from_x11_xkb(xcb).ok();
+        }
+
+        let layout = match self.xconn.keyboard_group() {
+            Ok(layout) => layout,
+            Err(err) => {
+                warn!("Failed to query the active keyboard layout: {err}");
+                return None;
+            },
+        };
+        xkb_context.as_mut()?.key_for_physical_key(
+            physical_key,
+            modifiers,
+            locked_keys,
+            level3,
+            Some(layout),
+        )
+    }
+
     fn system_theme(&self) -> Option<Theme> {
         None
     }
@@ -838,6 +1013,9 @@ pub enum X11Error {
 
     /// Could not find an ARGB32 pict format.
     NoArgb32Format,
+
+    /// The server refused to grab the input.
+    GrabFailed(xproto::GrabStatus),
 }
 
 impl fmt::Display for X11Error {
@@ -865,6 +1043,7 @@ impl fmt::Display for X11Error {
             X11Error::NoArgb32Format => {
                 f.write_str("winit only supports X11 displays with ARGB32 picture formats")
             },
+            X11Error::GrabFailed(status) => write!(f, "Failed to grab the input: {status:?}"),
         }
     }
 }
@@ -971,6 +1150,12 @@ fn mkdid(w: xinput::DeviceId) -> DeviceId {
 pub struct Device {
     _name: String,
     scroll_axes: Vec<(i32, ScrollAxis)>,
+    /// How the device scrolls, fingers on touchpads and wheels otherwise.
+    scroll_source: ScrollSource,
+    /// The axes of the device if it's the pen or eraser of a drawing tablet.
+    pen: Option<Pen>,
+    /// The rings and strips of the device if it's the pad of a drawing tablet.
+    pad: Option<Pad>,
     // For master devices, this is the paired device (pointer <-> keyboard).
     // For slave devices, this is the master.
     attachment: c_int,
@@ -989,10 +1174,163 @@ enum ScrollOrientation {
     Horizontal,
 }
 
+#[derive(Debug, Clone)]
+struct Pen {
+    tool: PenTool,
+    pressure: Option<Valuator>,
+    tilt_x: Option<Valuator>,
+    tilt_y: Option<Valuator>,
+    twist: Option<Valuator>,
+    distance: Option<Valuator>,
+    /// The state of the axes as of the latest event, valuators may only be sent when they change.
+    data: PenData,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Valuator {
+    number: i32,
+    min: f64,
+    max: f64,
+}
+
+impl Valuator {
+    fn normalize(&self, value: f64) -> f64 {
+        if self.max > self.min {
+            ((value - self.min) / (self.max - self.min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Pen {
+    /// Identify the pen by the labels of its valuators, which are set by the tablet drivers.
+    fn new(xconn: &XConnection, name: &str, info: &ffi::XIDeviceInfo) -> Option<Self> {
+        let atoms = xconn.atoms();
+        let tool =
+            if name.to_lowercase().contains("eraser") { PenTool::Eraser } else { PenTool::Pen };
+        let mut pen = Pen {
+            tool,
+            pressure: None,
+            tilt_x: None,
+            tilt_y: None,
+            twist: None,
+            distance: None,
+            data: PenData::default(),
+        };
+
+        let mut values = Vec::new();
+        for &class_ptr in Device::classes(info) {
+            let ty = unsafe { (*class_ptr)._type };
+            if ty != ffi::XIValuatorClass {
+                continue;
+            }
+
+            let info = unsafe { &*(class_ptr as *const ffi::XIValuatorClassInfo) };
+            let valuator = Some(Valuator { number: info.number, min: info.min, max: info.max });
+            let label = info.label as xproto::Atom;
+            if label == atoms[AbsPressure] {
+                pen.pressure = valuator;
+            } else if label == atoms[AbsTiltX] {
+                pen.tilt_x = valuator;
+            } else if label == atoms[AbsTiltY] {
+                pen.tilt_y = valuator;
+            } else if label == atoms[AbsRotationZ] {
+                pen.twist = valuator;
+            } else if label == atoms[AbsDistance] {
+                pen.distance = valuator;
+            } else {
+                continue;
+            }
+            values.push((info.number, info.value));
+        }
+
+        if pen.pressure.is_none() && pen.tilt_x.is_none() {
+            return None;
+        }
+
+        for (number, value) in values {
+            pen.update(number, value);
+        }
+        Some(pen)
+    }
+
+    fn update(&mut self, number: i32, value: f64) {
+        let is = |valuator: Option<Valuator>| valuator.filter(|valuator| valuator.number == number);
+        if let Some(valuator) = is(self.pressure) {
+            self.data.force = Some(Force::Normalized(valuator.normalize(value)));
+        } else if is(self.tilt_x).is_some() {
+            // The drivers report the tilt in degrees.
+            self.data.tilt.get_or_insert_with(PenTilt::default).x = value.clamp(-90.0, 90.0);
+        } else if is(self.tilt_y).is_some() {
+            self.data.tilt.get_or_insert_with(PenTilt::default).y = value.clamp(-90.0, 90.0);
+        } else if let Some(valuator) = is(self.twist) {
+            self.data.twist = Some(valuator.normalize(value) * 360.0 % 360.0);
+        } else if let Some(valuator) = is(self.distance) {
+            self.data.distance = Some(valuator.normalize(value));
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Pad {
+    rings: Vec<Valuator>,
+    strips: Vec<Valuator>,
+}
+
+impl Pad {
+    /// Identify the pad by its name, its rings and strips by the labels of its valuators.
+    fn new(xconn: &XConnection, name: &str, info: &ffi::XIDeviceInfo) -> Option<Self> {
+        if !name.split_whitespace().any(|word| word.eq_ignore_ascii_case("pad")) {
+            return None;
+        }
+
+        let atoms = xconn.atoms();
+        let mut pad = Pad::default();
+        for &class_ptr in Device::classes(info) {
+            let ty = unsafe { (*class_ptr)._type };
+            if ty != ffi::XIValuatorClass {
+                continue;
+            }
+
+            let info = unsafe { &*(class_ptr as *const ffi::XIValuatorClassInfo) };
+            let valuator = Valuator { number: info.number, min: info.min, max: info.max };
+            let label = info.label as xproto::Atom;
+            if label == atoms[AbsWheel] || label == atoms[AbsThrottle] {
+                pad.rings.push(valuator);
+            } else if label == atoms[AbsRotationX] || label == atoms[AbsRotationY] {
+                pad.strips.push(valuator);
+            }
+        }
+
+        Some(pad)
+    }
+
+    /// The event of the ring or strip with the valuator `number`.
+    fn event(&self, number: i32, value: f64) -> Option<DeviceEvent> {
+        if let Some(ring) = self.rings.iter().position(|ring| ring.number == number) {
+            let angle = self.rings[ring].normalize(value) * 360.0 % 360.0;
+            Some(DeviceEvent::PadRing { ring: ring as u32, group: 0, mode: 0, angle: Some(angle) })
+        } else if let Some(strip) = self.strips.iter().position(|strip| strip.number == number) {
+            let position = self.strips[strip].normalize(value);
+            Some(DeviceEvent::PadStrip {
+                strip: strip as u32,
+                group: 0,
+                mode: 0,
+                position: Some(position),
+            })
+        } else {
+            None
+        }
+    }
+}
+
 impl Device {
-    fn new(info: &ffi::XIDeviceInfo) -> Self {
+    fn new(xconn: &XConnection, info: &ffi::XIDeviceInfo) -> Self {
         let name = unsafe { CStr::from_ptr(info.name).to_string_lossy() };
         let mut scroll_axes = Vec::new();
+        let mut pen = None;
+        let mut pad = None;
 
         if Device::physical_device(info) {
             // Identify scroll axes
@@ -1011,10 +1349,26 @@ impl Device {
                     }));
                 }
             }
+
+            if info._use != ffi::XISlaveKeyboard {
+                pad = Pad::new(xconn, &name, info);
+                if pad.is_none() {
+                    pen = Pen::new(xconn, &name, info);
+                }
+            }
         }
 
-        let mut device =
-            Device { _name: name.into_owned(), scroll_axes, attachment: info.attachment };
+        let scroll_source =
+            if Device::touchpad(&name, info) { ScrollSource::Finger } else { ScrollSource::Wheel };
+
+        let mut device = Device {
+            _name: name.into_owned(),
+            scroll_axes,
+            scroll_source,
+            pen,
+            pad,
+            attachment: info.attachment,
+        };
         device.reset_scroll_position(info);
         device
     }
@@ -1035,6 +1389,58 @@ impl Device {
         }
     }
 
+    /// The information of the device exposed to the applications.
+    fn info(xconn: &XConnection, info: &ffi::XIDeviceInfo) -> CoreDeviceInfo {
+        let name = unsafe { CStr::from_ptr(info.name).to_string_lossy() };
+        let classes = Device::classes(info);
+        let has_class =
+            |class| classes.iter().any(|&class_ptr| unsafe { (*class_ptr)._type } == class);
+        let touch_mode = classes.iter().find_map(|&class_ptr| {
+            let ty = unsafe { (*class_ptr)._type };
+            (ty == ffi::XITouchClass)
+                .then(|| unsafe { (*(class_ptr as *const ffi::XITouchClassInfo)).mode })
+        });
+
+        let kind = if info._use == ffi::XIMasterKeyboard
+            || info._use == ffi::XISlaveKeyboard
+            || (has_class(ffi::XIKeyClass) && !has_class(ffi::XIValuatorClass))
+        {
+            DeviceKind::Keyboard
+        } else if touch_mode == Some(ffi::XIDirectTouch) {
+            DeviceKind::Touchscreen
+        } else if Device::touchpad(&name, info) {
+            DeviceKind::Touchpad
+        } else if Device::physical_device(info)
+            && (Pad::new(xconn, &name, info).is_some() || Pen::new(xconn, &name, info).is_some())
+        {
+            DeviceKind::Tablet
+        } else {
+            DeviceKind::Pointer
+        };
+
+        // Only the physical devices have ids, the master devices are virtual.
+        let vendor_product_id = Device::physical_device(info)
+            .then(|| xconn.device_product_id(info.deviceid as xinput::DeviceId).ok().flatten())
+            .flatten()
+            .map(|(vendor_id, product_id)| (vendor_id as u16, product_id as u16));
+
+        CoreDeviceInfo {
+            id: mkdid(info.deviceid as xinput::DeviceId),
+            kind,
+            name: Some(name.into_owned()),
+            vendor_product_id,
+        }
+    }
+
+    /// Whether the device is a touchpad, which X11 doesn't tell when it doesn't report touches.
+    fn touchpad(name: &str, info: &ffi::XIDeviceInfo) -> bool {
+        let dependent_touch = Device::classes(info).iter().any(|&class_ptr| unsafe {
+            (*class_ptr)._type == ffi::XITouchClass
+                && (*(class_ptr as *const ffi::XITouchClassInfo)).mode == ffi::XIDependentTouch
+        });
+        dependent_touch || name.to_lowercase().contains("touchpad")
+    }
+
     #[inline]
     fn physical_device(info: &ffi::XIDeviceInfo) -> bool {
         info._use == ffi::XISlaveKeyboard
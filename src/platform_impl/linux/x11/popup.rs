@@ -0,0 +1,264 @@
+//! Popups, placed against a rectangle of their parent and grabbing the input while shown.
+
+use std::sync::{Mutex, Weak};
+
+use x11rb::connection::Connection;
+use x11rb::protocol::xinput::{self, ConnectionExt as _};
+use x11rb::protocol::xproto::{self, ConnectionExt as _};
+
+use super::util::AaRect;
+use super::window::UnownedWindow;
+use super::{X11Error, XConnection};
+use crate::window::{PopupAdjustment, PopupAnchor, PopupAttributes};
+
+#[derive(Debug)]
+pub struct PopupState {
+    /// The window the popup is placed against.
+    pub parent: Weak<UnownedWindow>,
+
+    /// Whether the popup grabs the input while it's shown.
+    pub grabs: bool,
+
+    /// The pointer grabbed while the popup is shown, if it grabs the input.
+    pub grab: Mutex<Option<xinput::DeviceId>>,
+}
+
+/// Grab the pointer and the keyboard for the popup.
+///
+/// The events over the other windows of the application are still reported to them.
+pub fn grab_input(
+    xconn: &XConnection,
+    window: xproto::Window,
+) -> Result<xinput::DeviceId, X11Error> {
+    let conn = xconn.xcb_connection();
+    let device = conn.xinput_xi_get_client_pointer(window)?.reply()?.deviceid;
+
+    let mask = xinput::XIEventMask::MOTION
+        | xinput::XIEventMask::BUTTON_PRESS
+        | xinput::XIEventMask::BUTTON_RELEASE
+        | xinput::XIEventMask::ENTER
+        | xinput::XIEventMask::LEAVE;
+    let status = conn
+        .xinput_xi_grab_device(
+            window,
+            xconn.timestamp(),
+            x11rb::NONE,
+            device,
+            xproto::GrabMode::ASYNC,
+            xproto::GrabMode::ASYNC,
+            xinput::GrabOwner::OWNER,
+            &[mask.into()],
+        )?
+        .reply()?
+        .status;
+    if status != xproto::GrabStatus::SUCCESS {
+        return Err(X11Error::GrabFailed(status));
+    }
+
+    let status = conn
+        .grab_keyboard(
+            false,
+            window,
+            xconn.timestamp(),
+            xproto::GrabMode::ASYNC,
+            xproto::GrabMode::ASYNC,
+        )?
+        .reply()?
+        .status;
+    if status != xproto::GrabStatus::SUCCESS {
+        ungrab_input(xconn, device)?;
+        return Err(X11Error::GrabFailed(status));
+    }
+
+    Ok(device)
+}
+
+/// Release the grab of a popup.
+pub fn ungrab_input(xconn: &XConnection, device: xinput::DeviceId) -> Result<(), X11Error> {
+    let conn = xconn.xcb_connection();
+    conn.xinput_xi_ungrab_device(xconn.timestamp(), device)?;
+    conn.ungrab_keyboard(xconn.timestamp())?;
+    conn.flush()?;
+    Ok(())
+}
+
+/// Place a popup of the given size against the anchor rectangle, within the bounds.
+///
+/// All coordinates are in physical pixels, relative to the root window.
+pub fn place(
+    attributes: &PopupAttributes,
+    anchor_rect: &AaRect,
+    size: (u32, u32),
+    bounds: &AaRect,
+) -> ((i32, i32), (u32, u32)) {
+    let (anchor_x, anchor_y) = sides(attributes.anchor);
+    let (gravity_x, gravity_y) = sides(attributes.gravity);
+    let adjustment = attributes.adjustment;
+
+    let (x, width) = place_axis(
+        Axis {
+            anchor_start: anchor_rect.position().0,
+            anchor_length: anchor_rect.size().0,
+            bounds_start: bounds.position().0,
+            bounds_length: bounds.size().0,
+        },
+        (anchor_x, gravity_x),
+        size.0 as i64,
+        (
+            adjustment.contains(PopupAdjustment::FLIP_X),
+            adjustment.contains(PopupAdjustment::SLIDE_X),
+            adjustment.contains(PopupAdjustment::RESIZE_X),
+        ),
+    );
+    let (y, height) = place_axis(
+        Axis {
+            anchor_start: anchor_rect.position().1,
+            anchor_length: anchor_rect.size().1,
+            bounds_start: bounds.position().1,
+            bounds_length: bounds.size().1,
+        },
+        (anchor_y, gravity_y),
+        size.1 as i64,
+        (
+            adjustment.contains(PopupAdjustment::FLIP_Y),
+            adjustment.contains(PopupAdjustment::SLIDE_Y),
+            adjustment.contains(PopupAdjustment::RESIZE_Y),
+        ),
+    );
+
+    ((x as i32, y as i32), (width as u32, height as u32))
+}
+
+/// The side of a rectangle along an axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Start,
+    Center,
+    End,
+}
+
+impl Side {
+    fn flip(self) -> Self {
+        match self {
+            Side::Start => Side::End,
+            Side::Center => Side::Center,
+            Side::End => Side::Start,
+        }
+    }
+}
+
+/// The horizontal and vertical sides of an anchor.
+fn sides(anchor: PopupAnchor) -> (Side, Side) {
+    match anchor {
+        PopupAnchor::Center => (Side::Center, Side::Center),
+        PopupAnchor::Top => (Side::Center, Side::Start),
+        PopupAnchor::Bottom => (Side::Center, Side::End),
+        PopupAnchor::Left => (Side::Start, Side::Center),
+        PopupAnchor::Right => (Side::End, Side::Center),
+        PopupAnchor::TopLeft => (Side::Start, Side::Start),
+        PopupAnchor::TopRight => (Side::End, Side::Start),
+        PopupAnchor::BottomLeft => (Side::Start, Side::End),
+        PopupAnchor::BottomRight => (Side::End, Side::End),
+    }
+}
+
+/// The anchor rectangle and the bounds along an axis.
+struct Axis {
+    anchor_start: i64,
+    anchor_length: i64,
+    bounds_start: i64,
+    bounds_length: i64,
+}
+
+/// Place the popup along an axis, returning its start and length.
+///
+/// Like `xdg_positioner`, the popup is flipped first, then slid and resized into the bounds.
+fn place_axis(
+    axis: Axis,
+    (anchor, gravity): (Side, Side),
+    length: i64,
+    (flip, slide, resize): (bool, bool, bool),
+) -> (i64, i64) {
+    let bounds_end = axis.bounds_start + axis.bounds_length;
+    let start_at = |anchor: Side, gravity: Side| {
+        let point = match anchor {
+            Side::Start => axis.anchor_start,
+            Side::Center => axis.anchor_start + axis.anchor_length / 2,
+            Side::End => axis.anchor_start + axis.anchor_length,
+        };
+        match gravity {
+            Side::Start => point - length,
+            Side::Center => point - length / 2,
+            Side::End => point,
+        }
+    };
+    let fits = |start: i64| start >= axis.bounds_start && start + length <= bounds_end;
+
+    let mut start = start_at(anchor, gravity);
+    if fits(start) {
+        return (start, length);
+    }
+
+    if flip {
+        let flipped = start_at(anchor.flip(), gravity.flip());
+        if fits(flipped) {
+            return (flipped, length);
+        }
+    }
+
+    let mut length = length;
+    if slide {
+        start = start.min(bounds_end - length).max(axis.bounds_start);
+    }
+
+    if resize {
+        let end = (start + length).min(bounds_end);
+        start = start.max(axis.bounds_start);
+        if end > start {
+            length = end - start;
+        }
+    }
+
+    (start, length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dpi::{PhysicalPosition, PhysicalSize};
+    use crate::window::{Rect, WindowId};
+
+    fn attributes() -> PopupAttributes {
+        let rect = Rect::new(PhysicalPosition::new(0, 0), PhysicalSize::new(1, 1));
+        PopupAttributes::new(WindowId::from_raw(0), rect)
+    }
+
+    fn place_at(attributes: &PopupAttributes, x: i32, y: i32) -> ((i32, i32), (u32, u32)) {
+        let anchor_rect = AaRect::new((x, y), (100, 20));
+        let bounds = AaRect::new((0, 0), (1000, 500));
+        place(attributes, &anchor_rect, (200, 100), &bounds)
+    }
+
+    #[test]
+    fn below_anchor() {
+        assert_eq!(place_at(&attributes(), 50, 50), ((50, 70), (200, 100)));
+    }
+
+    #[test]
+    fn flip_then_slide() {
+        // Flipped above the anchor rectangle, and slid to the left where flipping isn't enough.
+        assert_eq!(place_at(&attributes(), 950, 450), ((800, 350), (200, 100)));
+    }
+
+    #[test]
+    fn resize() {
+        let attributes = attributes().with_adjustment(PopupAdjustment::RESIZE_Y);
+        assert_eq!(place_at(&attributes, 50, 450), ((50, 470), (200, 30)));
+    }
+
+    #[test]
+    fn centered() {
+        let attributes = attributes().with_anchor(PopupAnchor::Center, PopupAnchor::Center);
+        assert_eq!(place_at(&attributes, 100, 100), ((50, 60), (200, 100)));
+    }
+}
@@ -19,16 +19,21 @@ impl XConnection {
         window: xproto::Window,
         cursor: Option<CursorIcon>,
     ) -> Result<(), X11Error> {
-        let cursor = {
-            let mut cache = self.cursor_cache.lock().unwrap_or_else(|e| e.into_inner());
+        let cursor = self.cached_cursor(cursor)?;
+        self.update_cursor(window, cursor)
+    }
 
-            match cache.entry(cursor) {
-                Entry::Occupied(o) => *o.get(),
-                Entry::Vacant(v) => *v.insert(self.get_cursor(cursor)?),
-            }
-        };
+    /// Get the cursor for the icon, loading it if it's not cached yet.
+    pub(crate) fn cached_cursor(
+        &self,
+        cursor: Option<CursorIcon>,
+    ) -> Result<xproto::Cursor, X11Error> {
+        let mut cache = self.cursor_cache.lock().unwrap_or_else(|e| e.into_inner());
 
-        self.update_cursor(window, cursor)
+        match cache.entry(cursor) {
+            Entry::Occupied(o) => Ok(*o.get()),
+            Entry::Vacant(v) => Ok(*v.insert(self.get_cursor(cursor)?)),
+        }
     }
 
     pub(crate) fn set_custom_cursor(
@@ -219,6 +224,10 @@ impl CustomCursor {
 
         Ok(Self { inner: Arc::new(CustomCursorInner { xconn: event_loop.xconn.clone(), cursor }) })
     }
+
+    pub(crate) fn cursor(&self) -> xproto::Cursor {
+        self.inner.cursor
+    }
 }
 
 #[derive(Debug)]
@@ -18,6 +18,14 @@ impl AaRect {
         AaRect { x, y, width, height }
     }
 
+    pub fn position(&self) -> (i64, i64) {
+        (self.x, self.y)
+    }
+
+    pub fn size(&self) -> (i64, i64) {
+        (self.width, self.height)
+    }
+
     pub fn contains_point(&self, x: i64, y: i64) -> bool {
         x >= self.x && x <= self.x + self.width && y >= self.y && y <= self.y + self.height
     }
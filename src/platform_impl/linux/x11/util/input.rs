@@ -1,9 +1,12 @@
+use std::num::NonZeroU32;
+use std::time::Duration;
 use std::{slice, str};
 
 use x11rb::protocol::xinput::{self, ConnectionExt as _};
-use x11rb::protocol::xkb;
+use x11rb::protocol::xkb::{self, ConnectionExt as _};
 
 use super::*;
+use crate::keyboard::KeyRepeatInfo;
 
 pub const VIRTUAL_CORE_POINTER: u16 = 2;
 
@@ -27,6 +30,19 @@ impl XConnection {
             .map_err(Into::into)
     }
 
+    /// Like [`Self::select_xinput_events`], with a mask that may not fit in 32 bits.
+    pub fn select_xinput_events_wide(
+        &self,
+        window: xproto::Window,
+        device_id: u16,
+        mask: u64,
+    ) -> Result<VoidCookie<'_>, X11Error> {
+        let mask = vec![(mask as u32).into(), ((mask >> 32) as u32).into()];
+        self.xcb_connection()
+            .xinput_xi_select_events(window, &[xinput::EventMask { deviceid: device_id, mask }])
+            .map_err(Into::into)
+    }
+
     pub fn select_xkb_events(
         &self,
         device_id: xkb::DeviceSpec,
@@ -56,6 +72,41 @@ impl XConnection {
             .map_err(Into::into)
     }
 
+    /// How held keys repeat, from the XKB controls of the core keyboard.
+    pub fn key_repeat_info(&self) -> Result<KeyRepeatInfo, X11Error> {
+        let reply =
+            self.xcb_connection().xkb_get_controls(xkb::ID::USE_CORE_KBD.into())?.reply()?;
+        let enabled = reply.enabled_controls.contains(xkb::BoolCtrl::REPEAT_KEYS);
+        Ok(key_repeat_info(enabled, reply.repeat_interval, reply.repeat_delay))
+    }
+
+    /// The index of the active layout of the core keyboard.
+    pub fn keyboard_group(&self) -> Result<u32, X11Error> {
+        let reply = self.xcb_connection().xkb_get_state(xkb::ID::USE_CORE_KBD.into())?.reply()?;
+        Ok(reply.group.into())
+    }
+
+    /// The vendor and product ids of an input device, set by the input drivers.
+    pub fn device_product_id(&self, device_id: u16) -> Result<Option<(u32, u32)>, X11Error> {
+        let atoms = self.atoms();
+        let reply = self
+            .xcb_connection()
+            .xinput_xi_get_property(
+                device_id,
+                false,
+                atoms[DeviceProductId],
+                xproto::AtomEnum::INTEGER.into(),
+                0,
+                2,
+            )?
+            .reply()?;
+
+        Ok(match reply.items {
+            xinput::XIGetPropertyItems::Data32(ids) if ids.len() == 2 => Some((ids[0], ids[1])),
+            _ => None,
+        })
+    }
+
     fn lookup_utf8_inner(
         &self,
         ic: ffi::XIC,
@@ -104,3 +155,35 @@ impl XConnection {
         str::from_utf8(bytes).unwrap_or("").to_string()
     }
 }
+
+/// The key repeat from the XKB controls, which have the interval between repeats and the delay in
+/// milliseconds.
+fn key_repeat_info(enabled: bool, interval: u16, delay: u16) -> KeyRepeatInfo {
+    if !enabled {
+        return KeyRepeatInfo::Disabled;
+    }
+
+    let rate = 1000 / u32::from(interval).max(1);
+    KeyRepeatInfo::Repeat {
+        rate: NonZeroU32::new(rate).unwrap_or(NonZeroU32::MIN),
+        delay: Duration::from_millis(delay.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_repeat_rate() {
+        let repeat = |rate, delay| KeyRepeatInfo::Repeat {
+            rate: NonZeroU32::new(rate).unwrap(),
+            delay: Duration::from_millis(delay),
+        };
+        assert_eq!(key_repeat_info(true, 40, 660), repeat(25, 660));
+        assert_eq!(key_repeat_info(true, 0, 500), repeat(1000, 500));
+        // Intervals over a second still repeat.
+        assert_eq!(key_repeat_info(true, 2000, 500), repeat(1, 500));
+        assert_eq!(key_repeat_info(false, 40, 660), KeyRepeatInfo::Disabled);
+    }
+}
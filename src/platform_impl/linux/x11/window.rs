@@ -4,7 +4,8 @@ use std::num::NonZeroU32;
 use std::ops::Deref;
 use std::os::raw::*;
 use std::path::Path;
-use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::time::Duration;
 use std::{cmp, env};
 
 use tracing::{debug, info, warn};
@@ -13,19 +14,29 @@ use x11rb::properties::{WmHints, WmSizeHints, WmSizeHintsSpecification};
 use x11rb::protocol::shape::SK;
 use x11rb::protocol::sync::{ConnectionExt as _, Int64};
 use x11rb::protocol::xfixes::{ConnectionExt, RegionWrapper};
+use x11rb::protocol::xinput::ConnectionExt as _;
 use x11rb::protocol::xproto::{self, ConnectionExt as _, Rectangle};
 use x11rb::protocol::{randr, xinput};
 
+use super::clipboard::ClipboardRequest;
+use super::dnd::DropRequest;
+use super::drag::DragRequest;
+use super::event_processor::KEYCODE_OFFSET;
+use super::popup::{self, PopupState};
 use super::util::{self, SelectedCursor};
 use super::{
     ffi, ActiveEventLoop, CookieResultExt, ImeRequest, ImeSender, VoidCookie, XConnection,
 };
+use crate::clipboard::ClipboardData;
 use crate::cursor::{Cursor, CustomCursor as RootCustomCursor};
+use crate::dnd::{DragActions, DragData};
 use crate::dpi::{PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size};
 use crate::error::{NotSupportedError, RequestError};
-use crate::event::{Event, SurfaceSizeWriter, WindowEvent};
+use crate::event::{DeviceId, ElementState, Event, MouseButton, SurfaceSizeWriter, WindowEvent};
 use crate::event_loop::AsyncRequestSerial;
+use crate::keyboard::PhysicalKey;
 use crate::platform::x11::WindowType;
+use crate::platform_impl::common::hit_test::{HitTestAction, HitTestZones};
 use crate::platform_impl::x11::atoms::*;
 use crate::platform_impl::x11::{
     xinput_fp1616_to_float, MonitorHandle as X11MonitorHandle, WakeSender, X11Error,
@@ -35,8 +46,9 @@ use crate::platform_impl::{
     VideoModeHandle as PlatformVideoModeHandle,
 };
 use crate::window::{
-    CursorGrabMode, ImePurpose, ResizeDirection, Theme, UserAttentionType, Window as CoreWindow,
-    WindowAttributes, WindowButtons, WindowId, WindowLevel,
+    CursorGrabMode, HitTestZone, ImePurpose, PopupKind, Rect, ResizeDirection, Theme,
+    UserAttentionType, Window as CoreWindow, WindowAttributes, WindowButtons, WindowId,
+    WindowLevel,
 };
 
 pub(crate) struct Window(Arc<UnownedWindow>);
@@ -198,6 +210,14 @@ impl CoreWindow for Window {
         self.0.set_window_level(level);
     }
 
+    fn set_transient_for(&self, parent: Option<&dyn CoreWindow>) -> Result<(), RequestError> {
+        self.0.set_transient_for(parent)
+    }
+
+    fn set_modal(&self, modal: bool) -> Result<(), RequestError> {
+        self.0.set_modal(modal)
+    }
+
     fn set_window_icon(&self, window_icon: Option<crate::window::Icon>) {
         self.0.set_window_icon(window_icon.map(|inner| inner.inner))
     }
@@ -222,6 +242,14 @@ impl CoreWindow for Window {
         self.0.has_focus()
     }
 
+    fn pressed_keys(&self) -> Box<dyn Iterator<Item = PhysicalKey>> {
+        Box::new(self.0.pressed_keys().into_iter())
+    }
+
+    fn pressed_buttons(&self) -> Box<dyn Iterator<Item = MouseButton>> {
+        Box::new(self.0.pressed_buttons().into_iter())
+    }
+
     fn request_user_attention(&self, request_type: Option<UserAttentionType>) {
         self.0.request_user_attention(request_type);
     }
@@ -242,10 +270,57 @@ impl CoreWindow for Window {
         self.0.title()
     }
 
+    fn set_clipboard(&self, data: Vec<ClipboardData>) -> Result<(), RequestError> {
+        self.0.set_clipboard(data)
+    }
+
+    fn request_clipboard(&self, mime_types: &[&str]) -> Result<AsyncRequestSerial, RequestError> {
+        self.0.request_clipboard(mime_types)
+    }
+
+    fn set_primary_selection(&self, data: Vec<ClipboardData>) -> Result<(), RequestError> {
+        self.0.set_primary_selection(data)
+    }
+
+    fn request_primary_selection(
+        &self,
+        mime_types: &[&str],
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        self.0.request_primary_selection(mime_types)
+    }
+
+    fn start_drag(
+        &self,
+        data: DragData,
+        icon: Option<RootCustomCursor>,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        self.0.start_drag(data, icon)
+    }
+
+    fn accept_drop(&self, mime_type: &str, action: DragActions) -> Result<(), RequestError> {
+        self.0.accept_drop(mime_type, action)
+    }
+
+    fn reject_drop(&self) -> Result<(), RequestError> {
+        self.0.reject_drop()
+    }
+
+    fn request_drop_data(&self, mime_types: &[&str]) -> Result<AsyncRequestSerial, RequestError> {
+        self.0.request_drop_data(mime_types)
+    }
+
     fn set_cursor(&self, cursor: Cursor) {
         self.0.set_cursor(cursor);
     }
 
+    fn pointer_position(&self) -> Option<PhysicalPosition<f64>> {
+        self.0.pointer_position()
+    }
+
+    fn global_pointer_position(&self) -> Result<PhysicalPosition<f64>, RequestError> {
+        self.0.global_pointer_position()
+    }
+
     fn set_cursor_position(&self, position: Position) -> Result<(), RequestError> {
         self.0.set_cursor_position(position)
     }
@@ -254,6 +329,18 @@ impl CoreWindow for Window {
         self.0.set_cursor_grab(mode)
     }
 
+    fn set_cursor_confine_region(&self, region: Option<&[Rect]>) -> Result<(), RequestError> {
+        self.0.set_cursor_confine_region(region)
+    }
+
+    fn set_pointer_capture(
+        &self,
+        device_id: Option<DeviceId>,
+        capture: bool,
+    ) -> Result<(), RequestError> {
+        self.0.set_pointer_capture(device_id, capture)
+    }
+
     fn set_cursor_visible(&self, visible: bool) {
         self.0.set_cursor_visible(visible);
     }
@@ -270,10 +357,19 @@ impl CoreWindow for Window {
         self.0.show_window_menu(position);
     }
 
+    fn set_hit_test_zones(&self, zones: &[(Rect, HitTestZone)]) -> Result<(), RequestError> {
+        self.0.set_hit_test_zones(zones);
+        Ok(())
+    }
+
     fn set_cursor_hittest(&self, hittest: bool) -> Result<(), RequestError> {
         self.0.set_cursor_hittest(hittest)
     }
 
+    fn set_input_region(&self, region: Option<&[Rect]>) -> Result<(), RequestError> {
+        self.0.set_input_region(region)
+    }
+
     fn current_monitor(&self) -> Option<crate::monitor::MonitorHandle> {
         self.0
             .current_monitor()
@@ -331,6 +427,8 @@ impl Drop for Window {
             window.set_fullscreen(None);
         }
 
+        window.release_popup_grab();
+
         if let Ok(c) =
             xconn.xcb_connection().destroy_window(window.id().into_raw() as xproto::Window)
         {
@@ -364,8 +462,9 @@ pub struct SharedState {
     pub base_size: Option<Size>,
     pub visibility: Visibility,
     pub has_focus: bool,
-    // Use `Option` to not apply hittest logic when it was never requested.
-    pub cursor_hittest: Option<bool>,
+    pub hit_test_zones: HitTestZones,
+    /// The region of the window receiving input, set again when the scale factor changes.
+    pub input_region: Option<Vec<Rect>>,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -403,7 +502,8 @@ impl SharedState {
             surface_resize_increments: None,
             base_size: None,
             has_focus: false,
-            cursor_hittest: None,
+            hit_test_zones: HitTestZones::default(),
+            input_region: None,
         })
     }
 }
@@ -422,12 +522,23 @@ pub struct UnownedWindow {
     sync_counter_id: Option<NonZeroU32>, // never changes
     selected_cursor: Mutex<SelectedCursor>,
     cursor_grabbed_mode: Mutex<CursorGrabMode>,
+    /// The child window the cursor is confined to, when confined to a region.
+    cursor_confine_window: Mutex<Option<xproto::Window>>,
+    /// The pointers captured with `set_pointer_capture`.
+    pointer_captures: Mutex<Vec<xinput::DeviceId>>,
     #[allow(clippy::mutex_atomic)]
     cursor_visible: Mutex<bool>,
     ime_sender: Mutex<ImeSender>,
     pub shared_state: Mutex<SharedState>,
     redraw_sender: WakeSender<WindowId>,
     activation_sender: WakeSender<super::ActivationToken>,
+    clipboard_sender: WakeSender<ClipboardRequest>,
+    drag_sender: WakeSender<DragRequest>,
+    drop_sender: WakeSender<DropRequest>,
+    /// The state of the popup, if the window is one.
+    popup: Option<PopupState>,
+    /// Whether the window has the dialog type while it's transient, as no other was requested.
+    dialog_when_transient: bool, // never changes
 }
 macro_rules! leap {
     ($e:expr) => {
@@ -484,7 +595,19 @@ impl UnownedWindow {
                 })
                 .unwrap_or_else(|| monitors.swap_remove(0))
         };
-        let scale_factor = guessed_monitor.scale_factor();
+        // Popups are placed against a window of the event loop, and shown at its scale.
+        let popup_parent = match window_attrs.popup.as_ref() {
+            Some(popup) => Some(
+                event_loop.windows.borrow().get(&popup.parent).and_then(Weak::upgrade).ok_or_else(
+                    || NotSupportedError::new("the parent of the popup isn't a window"),
+                )?,
+            ),
+            None => None,
+        };
+        let scale_factor = match popup_parent.as_ref() {
+            Some(parent) => parent.scale_factor(),
+            None => guessed_monitor.scale_factor(),
+        };
 
         info!("Guessed window scale factor: {}", scale_factor);
 
@@ -493,10 +616,10 @@ impl UnownedWindow {
         let min_surface_size: Option<(u32, u32)> =
             window_attrs.min_surface_size.map(|size| size.to_physical::<u32>(scale_factor).into());
 
-        let position =
+        let mut position =
             window_attrs.position.map(|position| position.to_physical::<i32>(scale_factor));
 
-        let dimensions = {
+        let mut dimensions = {
             // x11 only applies constraints when the window is actively resized
             // by the user, so we have to manually apply the initial constraints
             let mut dimensions: (u32, u32) = window_attrs
@@ -517,6 +640,30 @@ impl UnownedWindow {
             dimensions
         };
 
+        if let (Some(popup), Some(parent)) = (window_attrs.popup.as_ref(), popup_parent.as_ref()) {
+            let parent_position = leap!(xconn.translate_coords(parent.xwindow, parent.root));
+            let (anchor_position, anchor_size) = popup.anchor_rect.to_physical(scale_factor);
+            let anchor_rect = util::AaRect::new(
+                (
+                    i32::from(parent_position.dst_x) + anchor_position.x,
+                    i32::from(parent_position.dst_y) + anchor_position.y,
+                ),
+                anchor_size.into(),
+            );
+
+            // Keep the popup on the monitor of the anchor rectangle.
+            let (anchor_x, anchor_y) = anchor_rect.position();
+            let bounds = leap!(xconn.available_monitors())
+                .into_iter()
+                .find(|monitor| monitor.rect.contains_point(anchor_x, anchor_y))
+                .map_or_else(|| guessed_monitor.rect.clone(), |monitor| monitor.rect);
+
+            let (popup_position, popup_size) =
+                popup::place(popup, &anchor_rect, dimensions, &bounds);
+            position = Some(popup_position.into());
+            dimensions = popup_size;
+        }
+
         // An iterator over the visuals matching screen id combined with their depths.
         let mut all_visuals = screen
             .allowed_depths
@@ -570,7 +717,9 @@ impl UnownedWindow {
 
             aux = aux.event_mask(event_mask).border_pixel(0);
 
-            if window_attrs.platform_specific.x11.override_redirect {
+            // Popups are placed by us rather than by the window manager.
+            if window_attrs.platform_specific.x11.override_redirect || window_attrs.popup.is_some()
+            {
                 aux = aux.override_redirect(true as u32);
             }
 
@@ -643,11 +792,23 @@ impl UnownedWindow {
             sync_counter_id: None,
             selected_cursor: Default::default(),
             cursor_grabbed_mode: Mutex::new(CursorGrabMode::None),
+            cursor_confine_window: Mutex::new(None),
+            pointer_captures: Mutex::new(Vec::new()),
             cursor_visible: Mutex::new(true),
+            dialog_when_transient: window_attrs.popup.is_none()
+                && window_attrs.platform_specific.x11.x11_window_types == [WindowType::Normal],
             ime_sender: Mutex::new(event_loop.ime_sender.clone()),
             shared_state: SharedState::new(guessed_monitor, &window_attrs),
             redraw_sender: event_loop.redraw_sender.clone(),
             activation_sender: event_loop.activation_sender.clone(),
+            clipboard_sender: event_loop.clipboard_sender.clone(),
+            drag_sender: event_loop.drag_sender.clone(),
+            drop_sender: event_loop.drop_sender.clone(),
+            popup: popup_parent.as_ref().map(|parent| PopupState {
+                parent: Arc::downgrade(parent),
+                grabs: window_attrs.popup.as_ref().is_some_and(|popup| popup.grab),
+                grab: Mutex::new(None),
+            }),
         };
 
         // Title must be set before mapping. Some tiling window managers (i.e. i3) use the window
@@ -713,8 +874,39 @@ impl UnownedWindow {
                 flusher.ignore_error()
             }
 
-            leap!(window.set_window_types(window_attrs.platform_specific.x11.x11_window_types))
+            let transient_for = match popup_parent.as_ref() {
+                Some(parent) => Some(parent.xwindow),
+                None => {
+                    window_attrs.transient_for.map(|parent| parent.into_raw() as xproto::Window)
+                },
+            };
+            let window_types = match window_attrs.popup.as_ref().map(|popup| popup.kind) {
+                Some(PopupKind::Menu) => vec![WindowType::PopupMenu],
+                Some(PopupKind::DropdownMenu) => vec![WindowType::DropdownMenu],
+                Some(PopupKind::Tooltip) => vec![WindowType::Tooltip],
+                // Dialogs are only given their type when no other was requested.
+                None if transient_for.is_some() && window.dialog_when_transient => {
+                    vec![WindowType::Dialog]
+                },
+                None => window_attrs.platform_specific.x11.x11_window_types,
+            };
+            leap!(window.set_window_types(window_types)).ignore_error();
+
+            if transient_for.is_some() {
+                leap!(window.set_transient_for_inner(transient_for)).ignore_error();
+            }
+
+            // The state of a window is set by the client itself until it's mapped.
+            if window_attrs.modal {
+                leap!(xconn.change_property(
+                    window.xwindow,
+                    atoms[_NET_WM_STATE],
+                    xproto::Atom::from(xproto::AtomEnum::ATOM),
+                    xproto::PropMode::APPEND,
+                    &[atoms[_NET_WM_STATE_MODAL]],
+                ))
                 .ignore_error();
+            }
 
             // Set size hints.
             let mut min_surface_size =
@@ -820,7 +1012,16 @@ impl UnownedWindow {
                 | xinput::XIEventMask::TOUCH_BEGIN
                 | xinput::XIEventMask::TOUCH_UPDATE
                 | xinput::XIEventMask::TOUCH_END;
-            leap!(xconn.select_xinput_events(window.xwindow, super::ALL_MASTER_DEVICES, mask))
+            let mut mask = u64::from(u32::from(mask));
+            if event_loop.xi2_gestures {
+                mask |= ffi::XI_GesturePinchBeginMask
+                    | ffi::XI_GesturePinchUpdateMask
+                    | ffi::XI_GesturePinchEndMask
+                    | ffi::XI_GestureSwipeBeginMask
+                    | ffi::XI_GestureSwipeUpdateMask
+                    | ffi::XI_GestureSwipeEndMask;
+            }
+            leap!(xconn.select_xinput_events_wide(window.xwindow, super::ALL_MASTER_DEVICES, mask))
                 .ignore_error();
 
             // Set visibility (map window)
@@ -831,6 +1032,9 @@ impl UnownedWindow {
                     &xproto::ConfigureWindowAux::new().stack_mode(xproto::StackMode::ABOVE)
                 ))
                 .ignore_error();
+
+                // Grab the input once the popup is viewable.
+                window.grab_popup_input();
             }
 
             // Attempt to make keyboard input repeat detectable
@@ -1312,6 +1516,52 @@ impl UnownedWindow {
         self.set_netwm(maximized.into(), (horz_atom, vert_atom, 0, 0))
     }
 
+    fn set_transient_for_inner(
+        &self,
+        parent: Option<xproto::Window>,
+    ) -> Result<VoidCookie<'_>, X11Error> {
+        let property = xproto::Atom::from(xproto::AtomEnum::WM_TRANSIENT_FOR);
+        match parent {
+            Some(parent) => self.xconn.change_property(
+                self.xwindow,
+                property,
+                xproto::Atom::from(xproto::AtomEnum::WINDOW),
+                xproto::PropMode::REPLACE,
+                &[parent],
+            ),
+            None => self
+                .xconn
+                .xcb_connection()
+                .delete_property(self.xwindow, property)
+                .map_err(Into::into),
+        }
+    }
+
+    #[inline]
+    pub fn set_transient_for(&self, parent: Option<&dyn CoreWindow>) -> Result<(), RequestError> {
+        let parent = parent.map(|parent| parent.id().into_raw() as xproto::Window);
+        self.set_transient_for_inner(parent).map_err(|err| os_error!(err))?.ignore_error();
+
+        // Like at creation, the window is a dialog only while it's transient.
+        if self.dialog_when_transient {
+            let window_type =
+                if parent.is_some() { WindowType::Dialog } else { WindowType::Normal };
+            self.set_window_types(vec![window_type]).map_err(|err| os_error!(err))?.ignore_error();
+        }
+        self.xconn.flush_requests().map_err(|err| os_error!(X11Error::from(err)))?;
+        Ok(())
+    }
+
+    #[inline]
+    pub fn set_modal(&self, modal: bool) -> Result<(), RequestError> {
+        let atoms = self.xconn.atoms();
+        self.set_netwm(modal.into(), (atoms[_NET_WM_STATE_MODAL], 0, 0, 0))
+            .map_err(|err| os_error!(err))?
+            .ignore_error();
+        self.xconn.flush_requests().map_err(|err| os_error!(X11Error::from(err)))?;
+        Ok(())
+    }
+
     #[inline]
     pub fn set_maximized(&self, maximized: bool) {
         self.set_maximized_inner(maximized)
@@ -1465,7 +1715,9 @@ impl UnownedWindow {
                 .expect_then_ignore_error("Failed to call `xcb_configure_window`");
             self.xconn.flush_requests().expect("Failed to call XMapRaised");
             shared_state.visibility = Visibility::YesWait;
+            self.grab_popup_input();
         } else {
+            self.release_popup_grab();
             self.xconn
                 .xcb_connection()
                 .unmap_window(self.xwindow)
@@ -1512,6 +1764,68 @@ impl UnownedWindow {
         }
     }
 
+    /// Grab the input for the popup while it's shown, if it was created with a grab.
+    fn grab_popup_input(&self) {
+        let Some(popup) = self.popup.as_ref().filter(|popup| popup.grabs) else {
+            return;
+        };
+        let mut grab = popup.grab.lock().unwrap();
+        if grab.is_none() {
+            match popup::grab_input(&self.xconn, self.xwindow) {
+                Ok(device) => *grab = Some(device),
+                Err(err) => warn!("Failed to grab the input for the popup: {err}"),
+            }
+        }
+    }
+
+    /// Release the grab of the popup, giving it back to its parent popup if that one grabs.
+    fn release_popup_grab(&self) {
+        let Some(popup) = self.popup.as_ref() else {
+            return;
+        };
+        let Some(device) = popup.grab.lock().unwrap().take() else {
+            return;
+        };
+        if let Err(err) = popup::ungrab_input(&self.xconn, device) {
+            warn!("Failed to release the grab of the popup: {err}");
+        }
+
+        let Some(parent) = popup.parent.upgrade() else {
+            return;
+        };
+        let Some(parent_popup) = parent.popup.as_ref() else {
+            return;
+        };
+        let mut parent_grab = parent_popup.grab.lock().unwrap();
+        if parent_grab.is_some() {
+            match popup::grab_input(&self.xconn, parent.xwindow) {
+                Ok(device) => *parent_grab = Some(device),
+                Err(err) => {
+                    warn!("Failed to grab the input for the popup again: {err}");
+                    *parent_grab = None;
+                },
+            }
+        }
+    }
+
+    /// Whether the window is a popup grabbing the input.
+    pub(super) fn is_grabbing_popup(&self) -> bool {
+        self.popup.as_ref().is_some_and(|popup| popup.grab.lock().unwrap().is_some())
+    }
+
+    /// Whether the position relative to the root window is over the surface.
+    pub(super) fn contains_root_point(&self, x: f64, y: f64) -> bool {
+        let (left, top) = self.inner_position_physical();
+        let (width, height) = self.surface_size_physical();
+        let (x, y) = (x - left as f64, y - top as f64);
+        x >= 0. && y >= 0. && x < width as f64 && y < height as f64
+    }
+
+    /// Hide the popup, which releases its grab, as a click outside of it dismissed it.
+    pub(super) fn dismiss_popup(&self) {
+        self.set_visible(false);
+    }
+
     fn inner_position_physical(&self) -> (i32, i32) {
         // This should be okay to unwrap since the only error XTranslateCoordinates can return
         // is BadWindow, and if the window handle is bad we have bigger problems.
@@ -1605,10 +1919,6 @@ impl UnownedWindow {
             )
             .expect_then_ignore_error("Failed to call `xcb_configure_window`");
         self.xconn.flush_requests().expect("Failed to call XResizeWindow");
-        // cursor_hittest needs to be reapplied after each window resize.
-        if self.shared_state_lock().cursor_hittest.unwrap_or(false) {
-            let _ = self.set_cursor_hittest(true);
-        }
     }
 
     #[inline]
@@ -1728,6 +2038,11 @@ impl UnownedWindow {
         })
         .expect("Failed to update normal hints");
 
+        // Rectangles in physical pixels cover another part of the window at the new scale.
+        if let Some(region) = shared_state.input_region.as_deref() {
+            let _ = self.apply_input_region(Some(region), new_scale_factor);
+        }
+
         let new_width = (width as f64 * scale_factor).round() as u32;
         let new_height = (height as f64 * scale_factor).round() as u32;
 
@@ -1844,54 +2159,7 @@ impl UnownedWindow {
                 .xconn
                 .flush_requests()
                 .map_err(|err| RequestError::Os(os_error!(X11Error::Xlib(err)))),
-            CursorGrabMode::Confined => {
-                let result = {
-                    self.xconn
-                        .xcb_connection()
-                        .grab_pointer(
-                            true as _,
-                            self.xwindow,
-                            xproto::EventMask::BUTTON_PRESS
-                                | xproto::EventMask::BUTTON_RELEASE
-                                | xproto::EventMask::ENTER_WINDOW
-                                | xproto::EventMask::LEAVE_WINDOW
-                                | xproto::EventMask::POINTER_MOTION
-                                | xproto::EventMask::POINTER_MOTION_HINT
-                                | xproto::EventMask::BUTTON1_MOTION
-                                | xproto::EventMask::BUTTON2_MOTION
-                                | xproto::EventMask::BUTTON3_MOTION
-                                | xproto::EventMask::BUTTON4_MOTION
-                                | xproto::EventMask::BUTTON5_MOTION
-                                | xproto::EventMask::KEYMAP_STATE,
-                            xproto::GrabMode::ASYNC,
-                            xproto::GrabMode::ASYNC,
-                            self.xwindow,
-                            0u32,
-                            x11rb::CURRENT_TIME,
-                        )
-                        .expect("Failed to call `grab_pointer`")
-                        .reply()
-                        .expect("Failed to receive reply from `grab_pointer`")
-                };
-
-                match result.status {
-                    xproto::GrabStatus::SUCCESS => Ok(()),
-                    xproto::GrabStatus::ALREADY_GRABBED => {
-                        Err("Cursor could not be confined: already confined by another client")
-                    },
-                    xproto::GrabStatus::INVALID_TIME => {
-                        Err("Cursor could not be confined: invalid time")
-                    },
-                    xproto::GrabStatus::NOT_VIEWABLE => {
-                        Err("Cursor could not be confined: confine location not viewable")
-                    },
-                    xproto::GrabStatus::FROZEN => {
-                        Err("Cursor could not be confined: frozen by another client")
-                    },
-                    _ => unreachable!(),
-                }
-                .map_err(|err| RequestError::Os(os_error!(err)))
-            },
+            CursorGrabMode::Confined => self.confine_pointer(),
             CursorGrabMode::Locked => {
                 return Err(
                     NotSupportedError::new("locked cursor is not implemented on X11").into()
@@ -1906,6 +2174,227 @@ impl UnownedWindow {
         result
     }
 
+    /// Grab the pointer, confining it to the window or the region set by the user.
+    fn confine_pointer(&self) -> Result<(), RequestError> {
+        let confine_to = self.cursor_confine_window.lock().unwrap().unwrap_or(self.xwindow);
+        let result = {
+            self.xconn
+                .xcb_connection()
+                .grab_pointer(
+                    true as _,
+                    self.xwindow,
+                    xproto::EventMask::BUTTON_PRESS
+                        | xproto::EventMask::BUTTON_RELEASE
+                        | xproto::EventMask::ENTER_WINDOW
+                        | xproto::EventMask::LEAVE_WINDOW
+                        | xproto::EventMask::POINTER_MOTION
+                        | xproto::EventMask::POINTER_MOTION_HINT
+                        | xproto::EventMask::BUTTON1_MOTION
+                        | xproto::EventMask::BUTTON2_MOTION
+                        | xproto::EventMask::BUTTON3_MOTION
+                        | xproto::EventMask::BUTTON4_MOTION
+                        | xproto::EventMask::BUTTON5_MOTION
+                        | xproto::EventMask::KEYMAP_STATE,
+                    xproto::GrabMode::ASYNC,
+                    xproto::GrabMode::ASYNC,
+                    confine_to,
+                    0u32,
+                    x11rb::CURRENT_TIME,
+                )
+                .expect("Failed to call `grab_pointer`")
+                .reply()
+                .expect("Failed to receive reply from `grab_pointer`")
+        };
+
+        match result.status {
+            xproto::GrabStatus::SUCCESS => Ok(()),
+            xproto::GrabStatus::ALREADY_GRABBED => {
+                Err("Cursor could not be confined: already confined by another client")
+            },
+            xproto::GrabStatus::INVALID_TIME => Err("Cursor could not be confined: invalid time"),
+            xproto::GrabStatus::NOT_VIEWABLE => {
+                Err("Cursor could not be confined: confine location not viewable")
+            },
+            xproto::GrabStatus::FROZEN => {
+                Err("Cursor could not be confined: frozen by another client")
+            },
+            _ => unreachable!(),
+        }
+        .map_err(|err| RequestError::Os(os_error!(err)))
+    }
+
+    /// Set the region of the window [`CursorGrabMode::Confined`] confines the cursor to.
+    ///
+    /// The pointer is confined to a child window with its shape set to the region, while its input
+    /// shape is emptied to let the input through to the window.
+    pub fn set_cursor_confine_region(&self, region: Option<&[Rect]>) -> Result<(), RequestError> {
+        let grabbed_lock = self.cursor_grabbed_mode.lock().unwrap();
+        let mut confine_window = self.cursor_confine_window.lock().unwrap();
+
+        let Some(region) = region else {
+            if let Some(window) = confine_window.take() {
+                self.xconn
+                    .xcb_connection()
+                    .destroy_window(window)
+                    .map_err(|err| os_error!(X11Error::from(err)))?
+                    .ignore_error();
+            }
+            drop(confine_window);
+            return self.reload_cursor_confine_region(*grabbed_lock);
+        };
+
+        let scale_factor = self.scale_factor();
+        let rectangles: Vec<Rectangle> =
+            region.iter().map(|rect| to_x11_rectangle(*rect, scale_factor)).collect();
+
+        // The child window covers the region from the top-left of the window, which its shape
+        // clips to the region.
+        let width = rectangles.iter().map(|rect| (rect.x as i32 + rect.width as i32).max(1));
+        let height = rectangles.iter().map(|rect| (rect.y as i32 + rect.height as i32).max(1));
+        let (width, height) = (width.max().unwrap_or(1) as u32, height.max().unwrap_or(1) as u32);
+        let conn = self.xconn.xcb_connection();
+        let window = match *confine_window {
+            Some(window) => window,
+            None => {
+                let window = conn.generate_id().map_err(|err| os_error!(X11Error::from(err)))?;
+                conn.create_window(
+                    x11rb::COPY_DEPTH_FROM_PARENT,
+                    window,
+                    self.xwindow,
+                    0,
+                    0,
+                    1,
+                    1,
+                    0,
+                    xproto::WindowClass::INPUT_ONLY,
+                    x11rb::COPY_FROM_PARENT,
+                    &xproto::CreateWindowAux::new(),
+                )
+                .map_err(|err| os_error!(X11Error::from(err)))?
+                .check()
+                .map_err(|err| os_error!(X11Error::from(err)))?;
+                *confine_window = Some(window);
+                window
+            },
+        };
+
+        let empty = RegionWrapper::create_region(conn, &[])
+            .map_err(|err| os_error!(X11Error::from(err)))?;
+        let shape = RegionWrapper::create_region(conn, &rectangles)
+            .map_err(|err| os_error!(X11Error::from(err)))?;
+        conn.configure_window(
+            window,
+            &xproto::ConfigureWindowAux::new()
+                .width(width)
+                .height(height)
+                .stack_mode(xproto::StackMode::ABOVE),
+        )
+        .map_err(|err| os_error!(X11Error::from(err)))?
+        .ignore_error();
+        conn.xfixes_set_window_shape_region(window, SK::INPUT, 0, 0, empty.region())
+            .map_err(|err| os_error!(X11Error::from(err)))?
+            .ignore_error();
+        conn.xfixes_set_window_shape_region(window, SK::BOUNDING, 0, 0, shape.region())
+            .map_err(|err| os_error!(X11Error::from(err)))?
+            .ignore_error();
+        conn.map_window(window).map_err(|err| os_error!(X11Error::from(err)))?.ignore_error();
+
+        drop(confine_window);
+        self.reload_cursor_confine_region(*grabbed_lock)
+    }
+
+    /// Grab the pointer again when the region it's confined to changed.
+    fn reload_cursor_confine_region(&self, mode: CursorGrabMode) -> Result<(), RequestError> {
+        if mode == CursorGrabMode::Confined {
+            self.confine_pointer()
+        } else {
+            self.xconn.flush_requests().map_err(|err| os_error!(X11Error::Xlib(err)).into())
+        }
+    }
+
+    /// Grab the pointer to keep receiving its events while it's outside of the window.
+    pub fn set_pointer_capture(
+        &self,
+        device_id: Option<DeviceId>,
+        capture: bool,
+    ) -> Result<(), RequestError> {
+        let conn = self.xconn.xcb_connection();
+        let device = match device_id {
+            Some(device_id) => device_id.into_raw() as xinput::DeviceId,
+            None => {
+                conn.xinput_xi_get_client_pointer(self.xwindow)
+                    .map_err(|err| os_error!(X11Error::from(err)))?
+                    .reply()
+                    .map_err(|err| os_error!(X11Error::from(err)))?
+                    .deviceid
+            },
+        };
+
+        let mut captures = self.pointer_captures.lock().unwrap();
+        if !capture {
+            if captures.contains(&device) {
+                drop(captures);
+                self.release_pointer_capture(device);
+            }
+            return Ok(());
+        }
+
+        // Like on other platforms, the capture only lasts while a button is held.
+        let pointer =
+            self.xconn.query_pointer(self.xwindow, device).map_err(|err| os_error!(err))?;
+        if buttons_from_mask(&pointer.buttons).is_empty() {
+            return Err(RequestError::Ignored);
+        }
+
+        // Unlike the implicit grab of a pressed button, the events are reported to the window
+        // even while the pointer is over another one of ours.
+        let mask = xinput::XIEventMask::MOTION
+            | xinput::XIEventMask::BUTTON_PRESS
+            | xinput::XIEventMask::BUTTON_RELEASE
+            | xinput::XIEventMask::ENTER
+            | xinput::XIEventMask::LEAVE;
+        let status = conn
+            .xinput_xi_grab_device(
+                self.xwindow,
+                self.xconn.timestamp(),
+                x11rb::NONE,
+                device,
+                xproto::GrabMode::ASYNC,
+                xproto::GrabMode::ASYNC,
+                xinput::GrabOwner::NO_OWNER,
+                &[mask.into()],
+            )
+            .map_err(|err| os_error!(X11Error::from(err)))?
+            .reply()
+            .map_err(|err| os_error!(X11Error::from(err)))?
+            .status;
+        if status != xproto::GrabStatus::SUCCESS {
+            return Err(os_error!(format!("Failed to capture the pointer: {status:?}")).into());
+        }
+
+        if !captures.contains(&device) {
+            captures.push(device);
+        }
+        Ok(())
+    }
+
+    /// Release the capture of the pointer, once its last button is released.
+    pub(super) fn release_pointer_capture(&self, device: xinput::DeviceId) {
+        let mut captures = self.pointer_captures.lock().unwrap();
+        let Some(index) = captures.iter().position(|&captured| captured == device) else {
+            return;
+        };
+        captures.remove(index);
+
+        match self.xconn.xcb_connection().xinput_xi_ungrab_device(self.xconn.timestamp(), device) {
+            Ok(cookie) => cookie.ignore_error(),
+            Err(err) => warn!("Failed to release the pointer capture: {err}"),
+        }
+        if let Err(err) = self.xconn.flush_requests() {
+            warn!("Failed to release the pointer capture: {err}");
+        }
+    }
+
     #[inline]
     pub fn set_cursor_visible(&self, visible: bool) {
         #[allow(clippy::mutex_atomic)]
@@ -1937,6 +2426,41 @@ impl UnownedWindow {
         self.shared_state_lock().last_monitor.scale_factor
     }
 
+    pub fn pointer_position(&self) -> Option<PhysicalPosition<f64>> {
+        let pointer = match self.xconn.query_pointer(self.xwindow, util::VIRTUAL_CORE_POINTER) {
+            Ok(pointer) => pointer,
+            Err(err) => {
+                warn!("Failed to query the pointer position: {err}");
+                return None;
+            },
+        };
+
+        // The pointer is on another screen.
+        if !pointer.same_screen {
+            return None;
+        }
+
+        let x = xinput_fp1616_to_float(pointer.win_x);
+        let y = xinput_fp1616_to_float(pointer.win_y);
+        Some(PhysicalPosition::new(x, y))
+    }
+
+    pub fn global_pointer_position(&self) -> Result<PhysicalPosition<f64>, RequestError> {
+        let pointer = self
+            .xconn
+            .query_pointer(self.xwindow, util::VIRTUAL_CORE_POINTER)
+            .map_err(|err| os_error!(err))?;
+
+        // The position would be on the root window of another screen.
+        if !pointer.same_screen {
+            return Err(RequestError::Ignored);
+        }
+
+        let x = xinput_fp1616_to_float(pointer.root_x);
+        let y = xinput_fp1616_to_float(pointer.root_y);
+        Ok(PhysicalPosition::new(x, y))
+    }
+
     pub fn set_cursor_position_physical(&self, x: i32, y: i32) -> Result<(), RequestError> {
         self.xconn
             .xcb_connection()
@@ -1954,23 +2478,46 @@ impl UnownedWindow {
 
     #[inline]
     pub fn set_cursor_hittest(&self, hittest: bool) -> Result<(), RequestError> {
-        let mut rectangles: Vec<Rectangle> = Vec::new();
-        if hittest {
-            let size = self.surface_size();
-            rectangles.push(Rectangle {
-                x: 0,
-                y: 0,
-                width: size.width as u16,
-                height: size.height as u16,
-            })
-        }
-        let region = RegionWrapper::create_region(self.xconn.xcb_connection(), &rectangles)
-            .map_err(|_e| RequestError::Ignored)?;
-        self.xconn
-            .xcb_connection()
-            .xfixes_set_window_shape_region(self.xwindow, SK::INPUT, 0, 0, region.region())
-            .map_err(|_e| RequestError::Ignored)?;
-        self.shared_state_lock().cursor_hittest = Some(hittest);
+        self.set_input_region(if hittest { None } else { Some(&[]) })
+    }
+
+    pub fn set_input_region(&self, region: Option<&[Rect]>) -> Result<(), RequestError> {
+        let mut shared_state = self.shared_state_lock();
+        shared_state.input_region = region.map(<[Rect]>::to_vec);
+        let scale_factor = shared_state.last_monitor.scale_factor;
+        drop(shared_state);
+        self.apply_input_region(region, scale_factor)
+    }
+
+    /// Set the input shape of the window to the region at the given scale.
+    fn apply_input_region(
+        &self,
+        region: Option<&[Rect]>,
+        scale_factor: f64,
+    ) -> Result<(), RequestError> {
+        let conn = self.xconn.xcb_connection();
+        let region = match region {
+            Some(region) => {
+                let rectangles: Vec<Rectangle> =
+                    region.iter().map(|rect| to_x11_rectangle(*rect, scale_factor)).collect();
+                Some(
+                    RegionWrapper::create_region(conn, &rectangles)
+                        .map_err(|_e| RequestError::Ignored)?,
+                )
+            },
+            None => None,
+        };
+
+        // Without a region, the input shape is reset to the bounds of the window.
+        conn.xfixes_set_window_shape_region(
+            self.xwindow,
+            SK::INPUT,
+            0,
+            0,
+            region.as_ref().map_or(x11rb::NONE, RegionWrapper::region),
+        )
+        .map_err(|_e| RequestError::Ignored)?;
+        self.xconn.flush_requests().map_err(|err| os_error!(X11Error::Xlib(err)))?;
         Ok(())
     }
 
@@ -1982,6 +2529,42 @@ impl UnownedWindow {
     #[inline]
     pub fn show_window_menu(&self, _position: Position) {}
 
+    pub fn set_hit_test_zones(&self, zones: &[(Rect, HitTestZone)]) {
+        self.shared_state_lock().hit_test_zones.set(zones);
+    }
+
+    /// Perform the action of a click in the hit-test zones of the window.
+    pub(super) fn hit_test_click(
+        &self,
+        button: MouseButton,
+        state: ElementState,
+        position: PhysicalPosition<f64>,
+        time: Duration,
+    ) {
+        let scale_factor = self.scale_factor();
+        let action = self.shared_state_lock().hit_test_zones.button(
+            button,
+            state,
+            position,
+            scale_factor,
+            time,
+        );
+        let result = match action {
+            Some(HitTestAction::Move) => self.drag_window(),
+            Some(HitTestAction::Resize(direction)) => self.drag_resize_window(direction),
+            Some(HitTestAction::ToggleMaximized) => {
+                self.set_maximized(!self.is_maximized());
+                Ok(())
+            },
+            // The window menu isn't supported.
+            Some(HitTestAction::ShowMenu) | None => Ok(()),
+        };
+
+        if let Err(err) = result {
+            warn!("Failed to perform the action of the hit-test zone: {err}");
+        }
+    }
+
     /// Resizes the window while it is being dragged.
     pub fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), RequestError> {
         self.drag_initiate(match direction {
@@ -2153,6 +2736,96 @@ impl UnownedWindow {
         Ok(serial)
     }
 
+    pub fn set_clipboard(&self, data: Vec<ClipboardData>) -> Result<(), RequestError> {
+        self.set_selection(self.xconn.atoms()[CLIPBOARD], data)
+    }
+
+    pub fn request_clipboard(
+        &self,
+        mime_types: &[&str],
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        self.request_selection(self.xconn.atoms()[CLIPBOARD], mime_types)
+    }
+
+    pub fn set_primary_selection(&self, data: Vec<ClipboardData>) -> Result<(), RequestError> {
+        self.set_selection(self.xconn.atoms()[PRIMARY], data)
+    }
+
+    pub fn request_primary_selection(
+        &self,
+        mime_types: &[&str],
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        self.request_selection(self.xconn.atoms()[PRIMARY], mime_types)
+    }
+
+    pub fn start_drag(
+        &self,
+        data: DragData,
+        icon: Option<RootCustomCursor>,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        let icon = match icon {
+            Some(RootCustomCursor { inner: PlatformCustomCursor::X(cursor) }) => Some(cursor),
+            #[cfg(wayland_platform)]
+            Some(RootCustomCursor { inner: PlatformCustomCursor::Wayland(_) }) => {
+                tracing::error!("passed a Wayland cursor to X11 backend");
+                None
+            },
+            None => None,
+        };
+
+        let serial = AsyncRequestSerial::get();
+        self.drag_sender.send(DragRequest { window: self.xwindow, serial, data, icon });
+        Ok(serial)
+    }
+
+    pub fn accept_drop(&self, mime_type: &str, action: DragActions) -> Result<(), RequestError> {
+        let mime_type = mime_type.to_owned();
+        self.drop_sender.send(DropRequest::Accept { window: self.xwindow, mime_type, action });
+        Ok(())
+    }
+
+    pub fn reject_drop(&self) -> Result<(), RequestError> {
+        self.drop_sender.send(DropRequest::Reject { window: self.xwindow });
+        Ok(())
+    }
+
+    pub fn request_drop_data(
+        &self,
+        mime_types: &[&str],
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        let serial = AsyncRequestSerial::get();
+        self.drop_sender.send(DropRequest::Read {
+            window: self.xwindow,
+            serial,
+            mime_types: mime_types.iter().map(|mime_type| mime_type.to_string()).collect(),
+        });
+        Ok(serial)
+    }
+
+    fn set_selection(
+        &self,
+        selection: xproto::Atom,
+        data: Vec<ClipboardData>,
+    ) -> Result<(), RequestError> {
+        self.clipboard_sender.send(ClipboardRequest::Set { selection, data });
+        Ok(())
+    }
+
+    fn request_selection(
+        &self,
+        selection: xproto::Atom,
+        mime_types: &[&str],
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        let serial = AsyncRequestSerial::get();
+        self.clipboard_sender.send(ClipboardRequest::Read {
+            window_id: self.id(),
+            serial,
+            selection,
+            mime_types: mime_types.iter().map(|mime_type| mime_type.to_string()).collect(),
+        });
+        Ok(serial)
+    }
+
     #[inline]
     pub fn id(&self) -> WindowId {
         WindowId::from_raw(self.xwindow as _)
@@ -2205,11 +2878,56 @@ impl UnownedWindow {
         self.shared_state_lock().has_focus
     }
 
+    pub fn pressed_keys(&self) -> Vec<PhysicalKey> {
+        if !self.has_focus() {
+            return Vec::new();
+        }
+
+        self.xconn
+            .query_keymap()
+            .into_iter()
+            .filter(|keycode| *keycode >= KEYCODE_OFFSET)
+            .map(|keycode| common::xkb::raw_keycode_to_physicalkey(keycode.into()))
+            .collect()
+    }
+
+    pub fn pressed_buttons(&self) -> Vec<MouseButton> {
+        let pointer = match self.xconn.query_pointer(self.xwindow, util::VIRTUAL_CORE_POINTER) {
+            Ok(pointer) => pointer,
+            Err(err) => {
+                warn!("Failed to query the pressed buttons: {err}");
+                return Vec::new();
+            },
+        };
+
+        buttons_from_mask(&pointer.buttons)
+    }
+
     pub fn title(&self) -> String {
         String::new()
     }
 }
 
+/// The mouse buttons of a button mask, which has a bit per button.
+fn buttons_from_mask(mask: &[u32]) -> Vec<MouseButton> {
+    let buttons = mask.iter().enumerate().flat_map(|(index, mask)| {
+        (0..32).filter(move |bit| mask & (1 << bit) != 0).map(move |bit| index * 32 + bit)
+    });
+
+    buttons
+        .filter_map(|button| match button {
+            1 => Some(MouseButton::Left),
+            2 => Some(MouseButton::Middle),
+            3 => Some(MouseButton::Right),
+            // Button 0 doesn't exist, and 4 to 7 are the scroll wheel.
+            0 | 4..=7 => None,
+            8 => Some(MouseButton::Back),
+            9 => Some(MouseButton::Forward),
+            button => Some(MouseButton::Other(button as u16)),
+        })
+        .collect()
+}
+
 /// Cast a dimension value into a hinted dimension for `WmSizeHints`, clamping if too large.
 fn cast_dimension_to_hint(val: u32) -> i32 {
     val.try_into().unwrap_or(i32::MAX)
@@ -2228,3 +2946,30 @@ fn cast_size_to_hint(size: Size, scale_factor: f64) -> (i32, i32) {
         Size::Logical(size) => size.to_physical::<i32>(scale_factor).into(),
     }
 }
+
+/// The rectangle in physical pixels, as used by the shapes of windows.
+fn to_x11_rectangle(rect: Rect, scale_factor: f64) -> Rectangle {
+    let (position, size) = rect.to_physical(scale_factor);
+    Rectangle {
+        x: position.x as i16,
+        y: position.y as i16,
+        width: size.width as u16,
+        height: size.height as u16,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buttons_mask() {
+        assert_eq!(buttons_from_mask(&[]), []);
+        // Left, right, and the wheel which isn't held.
+        assert_eq!(buttons_from_mask(&[0b1_1010]), [MouseButton::Left, MouseButton::Right]);
+        assert_eq!(buttons_from_mask(&[1 << 9, 1 << 1]), [
+            MouseButton::Forward,
+            MouseButton::Other(33)
+        ]);
+    }
+}
@@ -271,7 +271,7 @@ impl EventState {
         pressed_mods
             .set(ModifiersKeys::RSUPER, self.keyboard.contains(KeyboardModifierState::RSUPER));
 
-        Modifiers { state, pressed_mods }
+        Modifiers { state, pressed_mods, ..Default::default() }
     }
 }
 
@@ -432,6 +432,8 @@ impl EventLoop {
                     device_id: None,
                     delta: event::MouseScrollDelta::LineDelta(x as f32, y as f32),
                     phase: event::TouchPhase::Moved,
+                    source: None,
+                    value120: None,
                 });
             },
             EventOption::Quit(QuitEvent {}) => {
@@ -724,6 +726,32 @@ impl RootActiveEventLoop for ActiveEventLoop {
         Some(crate::monitor::MonitorHandle { inner: MonitorHandle })
     }
 
+    fn available_devices(&self) -> Box<dyn Iterator<Item = crate::event::DeviceInfo>> {
+        Box::new(std::iter::empty())
+    }
+
+    fn device_info(&self, _device_id: crate::event::DeviceId) -> Option<crate::event::DeviceInfo> {
+        None
+    }
+
+    fn key_repeat_info(&self) -> Option<crate::keyboard::KeyRepeatInfo> {
+        None
+    }
+
+    fn keyboard_layouts(&self) -> Box<dyn Iterator<Item = crate::keyboard::KeyboardLayout>> {
+        Box::new(std::iter::empty())
+    }
+
+    fn key_for_physical_key(
+        &self,
+        _physical_key: crate::keyboard::PhysicalKey,
+        _modifiers: crate::keyboard::ModifiersState,
+        _locked_keys: crate::keyboard::LockKeys,
+        _level3: bool,
+    ) -> Option<crate::keyboard::Key> {
+        None
+    }
+
     fn listen_device_events(&self, _allowed: DeviceEvents) {}
 
     fn set_control_flow(&self, control_flow: ControlFlow) {
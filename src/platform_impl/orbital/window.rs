@@ -3,9 +3,12 @@ use std::sync::{Arc, Mutex};
 
 use super::event_loop::EventLoopProxy;
 use super::{ActiveEventLoop, MonitorHandle, RedoxSocket, WindowProperties};
-use crate::cursor::Cursor;
+use crate::clipboard::ClipboardData;
+use crate::cursor::{Cursor, CustomCursor};
+use crate::dnd::{DragActions, DragData};
 use crate::dpi::{PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size};
 use crate::error::{NotSupportedError, RequestError};
+use crate::event_loop::AsyncRequestSerial;
 use crate::monitor::MonitorHandle as CoreMonitorHandle;
 use crate::window::{self, Fullscreen, ImePurpose, Window as CoreWindow, WindowId};
 
@@ -257,6 +260,45 @@ impl CoreWindow for Window {
         properties.title.to_string()
     }
 
+    fn set_clipboard(&self, _data: Vec<ClipboardData>) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("set_clipboard is not supported").into())
+    }
+
+    fn request_clipboard(&self, _mime_types: &[&str]) -> Result<AsyncRequestSerial, RequestError> {
+        Err(NotSupportedError::new("request_clipboard is not supported").into())
+    }
+
+    fn set_primary_selection(&self, _data: Vec<ClipboardData>) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("set_primary_selection is not supported").into())
+    }
+
+    fn request_primary_selection(
+        &self,
+        _mime_types: &[&str],
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        Err(NotSupportedError::new("request_primary_selection is not supported").into())
+    }
+
+    fn start_drag(
+        &self,
+        _data: DragData,
+        _icon: Option<CustomCursor>,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        Err(NotSupportedError::new("start_drag is not supported").into())
+    }
+
+    fn accept_drop(&self, _mime_type: &str, _action: DragActions) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("accept_drop is not supported").into())
+    }
+
+    fn reject_drop(&self) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("reject_drop is not supported").into())
+    }
+
+    fn request_drop_data(&self, _mime_types: &[&str]) -> Result<AsyncRequestSerial, RequestError> {
+        Err(NotSupportedError::new("request_drop_data is not supported").into())
+    }
+
     #[inline]
     fn set_title(&self, title: &str) {
         self.window_socket.write(format!("T,{title}").as_bytes()).expect("failed to set title");
@@ -348,6 +390,14 @@ impl CoreWindow for Window {
         }
     }
 
+    fn set_transient_for(&self, _parent: Option<&dyn CoreWindow>) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_transient_for is not supported").into())
+    }
+
+    fn set_modal(&self, _modal: bool) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_modal is not supported").into())
+    }
+
     #[inline]
     fn set_window_icon(&self, _window_icon: Option<crate::icon::Icon>) {}
 
@@ -374,6 +424,16 @@ impl CoreWindow for Window {
         Err(NotSupportedError::new("set_cursor_position is not supported").into())
     }
 
+    fn pointer_position(&self) -> Option<crate::dpi::PhysicalPosition<f64>> {
+        None
+    }
+
+    fn global_pointer_position(
+        &self,
+    ) -> Result<crate::dpi::PhysicalPosition<f64>, crate::error::RequestError> {
+        Err(crate::error::NotSupportedError::new("global_pointer_position is not supported").into())
+    }
+
     #[inline]
     fn set_cursor_grab(&self, mode: window::CursorGrabMode) -> Result<(), RequestError> {
         let (grab, relative) = match mode {
@@ -390,6 +450,22 @@ impl CoreWindow for Window {
         Ok(())
     }
 
+    fn set_cursor_confine_region(
+        &self,
+        _region: Option<&[crate::window::Rect]>,
+    ) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_cursor_confine_region is not supported")
+            .into())
+    }
+
+    fn set_pointer_capture(
+        &self,
+        _device_id: Option<crate::event::DeviceId>,
+        _capture: bool,
+    ) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_pointer_capture is not supported").into())
+    }
+
     #[inline]
     fn set_cursor_visible(&self, visible: bool) {
         let _ = self.window_socket.write(format!("M,C,{}", if visible { 1 } else { 0 }).as_bytes());
@@ -427,6 +503,20 @@ impl CoreWindow for Window {
         Err(NotSupportedError::new("set_cursor_hittest is not supported").into())
     }
 
+    fn set_hit_test_zones(
+        &self,
+        _zones: &[(crate::window::Rect, crate::window::HitTestZone)],
+    ) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_hit_test_zones is not supported").into())
+    }
+
+    fn set_input_region(
+        &self,
+        _region: Option<&[crate::window::Rect]>,
+    ) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_input_region is not supported").into())
+    }
+
     #[inline]
     fn set_enabled_buttons(&self, _buttons: window::WindowButtons) {}
 
@@ -445,6 +535,14 @@ impl CoreWindow for Window {
         false
     }
 
+    fn pressed_keys(&self) -> Box<dyn Iterator<Item = crate::keyboard::PhysicalKey>> {
+        Box::new(std::iter::empty())
+    }
+
+    fn pressed_buttons(&self) -> Box<dyn Iterator<Item = crate::event::MouseButton>> {
+        Box::new(std::iter::empty())
+    }
+
     #[inline]
     fn set_theme(&self, _theme: Option<window::Theme>) {}
 
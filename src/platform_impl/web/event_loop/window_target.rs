@@ -11,12 +11,11 @@ use super::super::{lock, KeyEventExtra};
 use super::runner::EventWrapper;
 use super::{backend, runner};
 use crate::error::{NotSupportedError, RequestError};
-use crate::event::{ElementState, Event, KeyEvent, TouchPhase, WindowEvent};
+use crate::event::{ElementState, Event, KeyEvent, Modifiers, TouchPhase, WindowEvent};
 use crate::event_loop::{
     ActiveEventLoop as RootActiveEventLoop, ControlFlow, DeviceEvents,
     EventLoopProxy as RootEventLoopProxy, OwnedDisplayHandle as CoreOwnedDisplayHandle,
 };
-use crate::keyboard::ModifiersState;
 use crate::monitor::MonitorHandle as RootMonitorHandle;
 use crate::platform::web::{CustomCursorFuture, PollStrategy, WaitUntilStrategy};
 use crate::platform_impl::platform::cursor::CustomCursor;
@@ -25,14 +24,14 @@ use crate::platform_impl::Window;
 use crate::window::{CustomCursor as RootCustomCursor, CustomCursorSource, Theme, WindowId};
 
 #[derive(Default)]
-struct ModifiersShared(Rc<Cell<ModifiersState>>);
+struct ModifiersShared(Rc<Cell<Modifiers>>);
 
 impl ModifiersShared {
-    fn set(&self, new: ModifiersState) {
+    fn set(&self, new: Modifiers) {
         self.0.set(new)
     }
 
-    fn get(&self) -> ModifiersState {
+    fn get(&self) -> Modifiers {
         self.0.get()
     }
 }
@@ -82,11 +81,11 @@ impl ActiveEventLoop {
         canvas.on_blur(move || {
             has_focus.set(false);
 
-            let clear_modifiers = (!modifiers.get().is_empty()).then(|| {
-                modifiers.set(ModifiersState::empty());
+            let clear_modifiers = (modifiers.get() != Modifiers::default()).then(|| {
+                modifiers.set(Modifiers::default());
                 Event::WindowEvent {
                     window_id,
-                    event: WindowEvent::ModifiersChanged(ModifiersState::empty().into()),
+                    event: WindowEvent::ModifiersChanged(Modifiers::default()),
                 }
             });
 
@@ -132,7 +131,7 @@ impl ActiveEventLoop {
                     modifiers.set(active_modifiers);
                     Event::WindowEvent {
                         window_id,
-                        event: WindowEvent::ModifiersChanged(active_modifiers.into()),
+                        event: WindowEvent::ModifiersChanged(active_modifiers),
                     }
                 });
 
@@ -166,7 +165,7 @@ impl ActiveEventLoop {
                     modifiers.set(active_modifiers);
                     Event::WindowEvent {
                         window_id,
-                        event: WindowEvent::ModifiersChanged(active_modifiers.into()),
+                        event: WindowEvent::ModifiersChanged(active_modifiers),
                     }
                 });
 
@@ -203,7 +202,7 @@ impl ActiveEventLoop {
                     modifiers.set(active_modifiers);
                     Event::WindowEvent {
                         window_id,
-                        event: WindowEvent::ModifiersChanged(active_modifiers.into()),
+                        event: WindowEvent::ModifiersChanged(active_modifiers),
                     }
                 });
 
@@ -229,7 +228,7 @@ impl ActiveEventLoop {
                     modifiers.set(active_modifiers);
                     Event::WindowEvent {
                         window_id,
-                        event: WindowEvent::ModifiersChanged(active_modifiers.into()),
+                        event: WindowEvent::ModifiersChanged(active_modifiers),
                     }
                 });
 
@@ -255,9 +254,7 @@ impl ActiveEventLoop {
                                     modifiers.set(active_modifiers);
                                     Event::WindowEvent {
                                         window_id,
-                                        event: WindowEvent::ModifiersChanged(
-                                            active_modifiers.into(),
-                                        ),
+                                        event: WindowEvent::ModifiersChanged(active_modifiers),
                                     }
                                 });
 
@@ -285,7 +282,7 @@ impl ActiveEventLoop {
                             modifiers.set(active_modifiers);
                             Event::WindowEvent {
                                 window_id,
-                                event: WindowEvent::ModifiersChanged(active_modifiers.into()),
+                                event: WindowEvent::ModifiersChanged(active_modifiers),
                             }
                         });
 
@@ -312,7 +309,7 @@ impl ActiveEventLoop {
                     modifiers.set(active_modifiers);
                     Event::WindowEvent {
                         window_id,
-                        event: WindowEvent::ModifiersChanged(active_modifiers.into()),
+                        event: WindowEvent::ModifiersChanged(active_modifiers),
                     }
                 });
 
@@ -340,7 +337,7 @@ impl ActiveEventLoop {
                         modifiers.set(active_modifiers);
                         Event::WindowEvent {
                             window_id,
-                            event: WindowEvent::ModifiersChanged(active_modifiers.into()),
+                            event: WindowEvent::ModifiersChanged(active_modifiers),
                         }
                     });
 
@@ -365,7 +362,7 @@ impl ActiveEventLoop {
                     modifiers.set(active_modifiers);
                     Event::WindowEvent {
                         window_id,
-                        event: WindowEvent::ModifiersChanged(active_modifiers.into()),
+                        event: WindowEvent::ModifiersChanged(active_modifiers),
                     }
                 });
 
@@ -376,6 +373,8 @@ impl ActiveEventLoop {
                         device_id: None,
                         delta,
                         phase: TouchPhase::Moved,
+                        source: None,
+                        value120: None,
                     },
                 },
             )));
@@ -517,6 +516,32 @@ impl RootActiveEventLoop for ActiveEventLoop {
         self.runner.monitor().primary_monitor().map(|inner| RootMonitorHandle { inner })
     }
 
+    fn available_devices(&self) -> Box<dyn Iterator<Item = crate::event::DeviceInfo>> {
+        Box::new(std::iter::empty())
+    }
+
+    fn device_info(&self, _device_id: crate::event::DeviceId) -> Option<crate::event::DeviceInfo> {
+        None
+    }
+
+    fn key_repeat_info(&self) -> Option<crate::keyboard::KeyRepeatInfo> {
+        None
+    }
+
+    fn keyboard_layouts(&self) -> Box<dyn Iterator<Item = crate::keyboard::KeyboardLayout>> {
+        Box::new(std::iter::empty())
+    }
+
+    fn key_for_physical_key(
+        &self,
+        _physical_key: crate::keyboard::PhysicalKey,
+        _modifiers: crate::keyboard::ModifiersState,
+        _locked_keys: crate::keyboard::LockKeys,
+        _level3: bool,
+    ) -> Option<crate::keyboard::Key> {
+        None
+    }
+
     fn listen_device_events(&self, allowed: DeviceEvents) {
         self.runner.listen_device_events(allowed)
     }
@@ -7,8 +7,11 @@ use wasm_bindgen::{JsCast, JsValue};
 use web_sys::{KeyboardEvent, MouseEvent, Navigator, PointerEvent, WheelEvent};
 
 use super::Engine;
-use crate::event::{FingerId, MouseButton, MouseScrollDelta, PointerKind};
-use crate::keyboard::{Key, KeyLocation, ModifiersState, NamedKey, PhysicalKey};
+use crate::event::{
+    FingerId, Force, Modifiers, MouseButton, MouseScrollDelta, PenButton, PenData, PenTilt,
+    PenTool, PointerKind,
+};
+use crate::keyboard::{Key, KeyLocation, LockKeys, ModifiersState, NamedKey, PhysicalKey};
 
 bitflags::bitflags! {
     // https://www.w3.org/TR/pointerevents3/#the-buttons-property
@@ -19,6 +22,7 @@ bitflags::bitflags! {
         const MIDDLE  = 0b00100;
         const BACK    = 0b01000;
         const FORWARD = 0b10000;
+        const ERASER  = 0b100000;
     }
 }
 
@@ -164,10 +168,40 @@ pub fn pointer_type(event: &PointerEvent, pointer_id: i32) -> PointerKind {
     match event.pointer_type().as_str() {
         "mouse" => PointerKind::Mouse,
         "touch" => PointerKind::Touch(FingerId::from_raw(pointer_id as usize)),
+        "pen" => PointerKind::Pen(pen_tool(event)),
         _ => PointerKind::Unknown,
     }
 }
 
+fn pen_tool(event: &PointerEvent) -> PenTool {
+    // https://www.w3.org/TR/pointerevents3/#the-button-property
+    if mouse_buttons(event).contains(ButtonsState::ERASER) || event.button() == 5 {
+        PenTool::Eraser
+    } else {
+        PenTool::Pen
+    }
+}
+
+pub fn pen_button(button: MouseButton) -> PenButton {
+    match button {
+        // The eraser touching the surface is reported as its own button.
+        MouseButton::Left | MouseButton::Other(5) => PenButton::Contact,
+        MouseButton::Right => PenButton::Barrel,
+        MouseButton::Middle => PenButton::SecondaryBarrel,
+        button => PenButton::Other(button.to_id()),
+    }
+}
+
+pub fn pen_data(event: &PointerEvent) -> PenData {
+    // Browsers report `0` for the axes the hardware doesn't support.
+    PenData {
+        force: Some(Force::Normalized(event.pressure().into())),
+        tilt: Some(PenTilt { x: event.tilt_x().into(), y: event.tilt_y().into() }),
+        twist: Some(event.twist().into()),
+        distance: None,
+    }
+}
+
 pub fn key_code(event: &KeyboardEvent) -> PhysicalKey {
     let code = event.code();
     PhysicalKey::from_key_code_attribute_value(&code)
@@ -203,7 +237,7 @@ pub fn key_location(event: &KeyboardEvent) -> KeyLocation {
     }
 }
 
-pub fn keyboard_modifiers(event: &KeyboardEvent) -> ModifiersState {
+pub fn keyboard_modifiers(event: &KeyboardEvent) -> Modifiers {
     let mut state = ModifiersState::empty();
 
     if event.shift_key() {
@@ -219,10 +253,20 @@ pub fn keyboard_modifiers(event: &KeyboardEvent) -> ModifiersState {
         state |= ModifiersState::SUPER;
     }
 
-    state
+    let locked_keys = lock_keys(|key| event.get_modifier_state(key));
+    Modifiers { state, locked_keys, ..Default::default() }
+}
+
+/// The lock keys which are on, `getModifierState()` doesn't tell whether they are latched.
+fn lock_keys(get_modifier_state: impl Fn(&str) -> bool) -> LockKeys {
+    let mut lock_keys = LockKeys::empty();
+    lock_keys.set(LockKeys::CAPS_LOCK, get_modifier_state("CapsLock"));
+    lock_keys.set(LockKeys::NUM_LOCK, get_modifier_state("NumLock"));
+    lock_keys.set(LockKeys::SCROLL_LOCK, get_modifier_state("ScrollLock"));
+    lock_keys
 }
 
-pub fn mouse_modifiers(event: &MouseEvent) -> ModifiersState {
+pub fn mouse_modifiers(event: &MouseEvent) -> Modifiers {
     let mut state = ModifiersState::empty();
 
     if event.shift_key() {
@@ -238,7 +282,8 @@ pub fn mouse_modifiers(event: &MouseEvent) -> ModifiersState {
         state |= ModifiersState::SUPER;
     }
 
-    state
+    let locked_keys = lock_keys(|key| event.get_modifier_state(key));
+    Modifiers { state, locked_keys, ..Default::default() }
 }
 
 pub fn pointer_move_event(event: PointerEvent) -> impl Iterator<Item = PointerEvent> {
@@ -7,8 +7,9 @@ use super::canvas::Common;
 use super::event;
 use super::event_handle::EventListenerHandle;
 use crate::dpi::PhysicalPosition;
-use crate::event::{ButtonSource, DeviceId, ElementState, Force, PointerKind, PointerSource};
-use crate::keyboard::ModifiersState;
+use crate::event::{
+    ButtonSource, DeviceId, ElementState, Force, Modifiers, PointerKind, PointerSource,
+};
 use crate::platform_impl::web::event::mkdid;
 
 #[allow(dead_code)]
@@ -35,8 +36,7 @@ impl PointerHandler {
 
     pub fn on_pointer_leave<F>(&mut self, canvas_common: &Common, mut handler: F)
     where
-        F: 'static
-            + FnMut(ModifiersState, Option<DeviceId>, bool, PhysicalPosition<f64>, PointerKind),
+        F: 'static + FnMut(Modifiers, Option<DeviceId>, bool, PhysicalPosition<f64>, PointerKind),
     {
         let window = canvas_common.window.clone();
         self.on_cursor_leave =
@@ -53,8 +53,7 @@ impl PointerHandler {
 
     pub fn on_pointer_enter<F>(&mut self, canvas_common: &Common, mut handler: F)
     where
-        F: 'static
-            + FnMut(ModifiersState, Option<DeviceId>, bool, PhysicalPosition<f64>, PointerKind),
+        F: 'static + FnMut(Modifiers, Option<DeviceId>, bool, PhysicalPosition<f64>, PointerKind),
     {
         let window = canvas_common.window.clone();
         self.on_cursor_enter =
@@ -71,8 +70,7 @@ impl PointerHandler {
 
     pub fn on_pointer_release<C>(&mut self, canvas_common: &Common, mut handler: C)
     where
-        C: 'static
-            + FnMut(ModifiersState, Option<DeviceId>, bool, PhysicalPosition<f64>, ButtonSource),
+        C: 'static + FnMut(Modifiers, Option<DeviceId>, bool, PhysicalPosition<f64>, ButtonSource),
     {
         let window = canvas_common.window.clone();
         self.on_pointer_release =
@@ -89,6 +87,11 @@ impl PointerHandler {
                         finger_id,
                         force: Some(Force::Normalized(event.pressure().into())),
                     },
+                    PointerKind::Pen(tool) => ButtonSource::Pen {
+                        tool,
+                        button: event::pen_button(button),
+                        data: event::pen_data(&event),
+                    },
                     PointerKind::Unknown => ButtonSource::Unknown(button.to_id()),
                 };
 
@@ -108,8 +111,7 @@ impl PointerHandler {
         mut handler: C,
         prevent_default: Rc<Cell<bool>>,
     ) where
-        C: 'static
-            + FnMut(ModifiersState, Option<DeviceId>, bool, PhysicalPosition<f64>, ButtonSource),
+        C: 'static + FnMut(Modifiers, Option<DeviceId>, bool, PhysicalPosition<f64>, ButtonSource),
     {
         let window = canvas_common.window.clone();
         let canvas = canvas_common.raw().clone();
@@ -142,6 +144,11 @@ impl PointerHandler {
                         finger_id,
                         force: Some(Force::Normalized(event.pressure().into())),
                     },
+                    PointerKind::Pen(tool) => ButtonSource::Pen {
+                        tool,
+                        button: event::pen_button(button),
+                        data: event::pen_data(&event),
+                    },
                     PointerKind::Unknown => ButtonSource::Unknown(button.to_id()),
                 };
 
@@ -165,13 +172,11 @@ impl PointerHandler {
         C: 'static
             + FnMut(
                 Option<DeviceId>,
-                &mut dyn Iterator<
-                    Item = (ModifiersState, bool, PhysicalPosition<f64>, PointerSource),
-                >,
+                &mut dyn Iterator<Item = (Modifiers, bool, PhysicalPosition<f64>, PointerSource)>,
             ),
         B: 'static
             + FnMut(
-                ModifiersState,
+                Modifiers,
                 Option<DeviceId>,
                 bool,
                 PhysicalPosition<f64>,
@@ -217,6 +222,11 @@ impl PointerHandler {
                                 force: Some(Force::Normalized(event.pressure().into())),
                             }
                         },
+                        PointerKind::Pen(tool) => ButtonSource::Pen {
+                            tool,
+                            button: event::pen_button(button),
+                            data: event::pen_data(&event),
+                        },
                         PointerKind::Unknown => todo!(),
                     };
 
@@ -248,6 +258,9 @@ impl PointerHandler {
                                     finger_id,
                                     force: Some(Force::Normalized(event.pressure().into())),
                                 },
+                                PointerKind::Pen(tool) => {
+                                    PointerSource::Pen { tool, data: event::pen_data(&event) }
+                                },
                                 PointerKind::Unknown => PointerSource::Unknown,
                             },
                         )
@@ -9,14 +9,17 @@ use super::main_thread::{MainThreadMarker, MainThreadSafe};
 use super::monitor::MonitorHandler;
 use super::r#async::Dispatcher;
 use super::{backend, lock, ActiveEventLoop};
+use crate::clipboard::ClipboardData;
+use crate::dnd::{DragActions, DragData};
 use crate::dpi::{LogicalInsets, PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size};
 use crate::error::{NotSupportedError, RequestError};
+use crate::event_loop::AsyncRequestSerial;
 use crate::icon::Icon;
 use crate::monitor::MonitorHandle as RootMonitorHandle;
 use crate::window::{
-    Cursor, CursorGrabMode, Fullscreen as RootFullscreen, ImePurpose, ResizeDirection, Theme,
-    UserAttentionType, Window as RootWindow, WindowAttributes, WindowButtons, WindowId,
-    WindowLevel,
+    Cursor, CursorGrabMode, CustomCursor, Fullscreen as RootFullscreen, ImePurpose,
+    ResizeDirection, Theme, UserAttentionType, Window as RootWindow, WindowAttributes,
+    WindowButtons, WindowId, WindowLevel,
 };
 
 pub struct Window {
@@ -295,6 +298,14 @@ impl RootWindow for Window {
         // Intentionally a no-op, no window ordering
     }
 
+    fn set_transient_for(&self, _parent: Option<&dyn RootWindow>) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_transient_for is not supported").into())
+    }
+
+    fn set_modal(&self, _modal: bool) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_modal is not supported").into())
+    }
+
     fn set_window_icon(&self, _: Option<Icon>) {
         // Currently an intentional no-op
     }
@@ -321,6 +332,14 @@ impl RootWindow for Window {
         self.inner.queue(|inner| inner.canvas.has_focus.get())
     }
 
+    fn pressed_keys(&self) -> Box<dyn Iterator<Item = crate::keyboard::PhysicalKey>> {
+        Box::new(std::iter::empty())
+    }
+
+    fn pressed_buttons(&self) -> Box<dyn Iterator<Item = crate::event::MouseButton>> {
+        Box::new(std::iter::empty())
+    }
+
     fn request_user_attention(&self, _: Option<UserAttentionType>) {
         // Currently an intentional no-op
     }
@@ -345,6 +364,45 @@ impl RootWindow for Window {
         String::new()
     }
 
+    fn set_clipboard(&self, _data: Vec<ClipboardData>) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("set_clipboard is not supported").into())
+    }
+
+    fn request_clipboard(&self, _mime_types: &[&str]) -> Result<AsyncRequestSerial, RequestError> {
+        Err(NotSupportedError::new("request_clipboard is not supported").into())
+    }
+
+    fn set_primary_selection(&self, _data: Vec<ClipboardData>) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("set_primary_selection is not supported").into())
+    }
+
+    fn request_primary_selection(
+        &self,
+        _mime_types: &[&str],
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        Err(NotSupportedError::new("request_primary_selection is not supported").into())
+    }
+
+    fn start_drag(
+        &self,
+        _data: DragData,
+        _icon: Option<CustomCursor>,
+    ) -> Result<AsyncRequestSerial, RequestError> {
+        Err(NotSupportedError::new("start_drag is not supported").into())
+    }
+
+    fn accept_drop(&self, _mime_type: &str, _action: DragActions) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("accept_drop is not supported").into())
+    }
+
+    fn reject_drop(&self) -> Result<(), RequestError> {
+        Err(NotSupportedError::new("reject_drop is not supported").into())
+    }
+
+    fn request_drop_data(&self, _mime_types: &[&str]) -> Result<AsyncRequestSerial, RequestError> {
+        Err(NotSupportedError::new("request_drop_data is not supported").into())
+    }
+
     fn set_cursor(&self, cursor: Cursor) {
         self.inner.dispatch(move |inner| inner.canvas.cursor.set_cursor(cursor))
     }
@@ -353,6 +411,14 @@ impl RootWindow for Window {
         Err(NotSupportedError::new("set_cursor_position is not supported").into())
     }
 
+    fn pointer_position(&self) -> Option<crate::dpi::PhysicalPosition<f64>> {
+        None
+    }
+
+    fn global_pointer_position(&self) -> Result<crate::dpi::PhysicalPosition<f64>, RequestError> {
+        Err(NotSupportedError::new("global_pointer_position is not supported").into())
+    }
+
     fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), RequestError> {
         Ok(self.inner.queue(|inner| {
             match mode {
@@ -371,6 +437,46 @@ impl RootWindow for Window {
         })?)
     }
 
+    fn set_cursor_confine_region(
+        &self,
+        _region: Option<&[crate::window::Rect]>,
+    ) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_cursor_confine_region is not supported")
+            .into())
+    }
+
+    fn set_pointer_capture(
+        &self,
+        device_id: Option<crate::event::DeviceId>,
+        capture: bool,
+    ) -> Result<(), RequestError> {
+        let Some(device_id) = device_id else {
+            return Err(RequestError::Ignored);
+        };
+
+        let pointer_id = device_id.into_raw() as i32;
+        self.inner.queue(|inner| -> Result<(), RequestError> {
+            let canvas = inner.canvas.raw();
+            if !capture {
+                canvas
+                    .release_pointer_capture(pointer_id)
+                    .map_err(|_| os_error!("Failed to release the pointer capture"))?;
+                return Ok(());
+            }
+
+            canvas
+                .set_pointer_capture(pointer_id)
+                .map_err(|_| os_error!("Failed to set the pointer capture"))?;
+
+            // The browser only captures pointers with a pressed button.
+            if canvas.has_pointer_capture(pointer_id) {
+                Ok(())
+            } else {
+                Err(RequestError::Ignored)
+            }
+        })
+    }
+
     fn set_cursor_visible(&self, visible: bool) {
         self.inner.dispatch(move |inner| inner.canvas.cursor.set_cursor_visible(visible))
     }
@@ -389,6 +495,20 @@ impl RootWindow for Window {
         Err(NotSupportedError::new("set_cursor_hittest is not supported").into())
     }
 
+    fn set_hit_test_zones(
+        &self,
+        _zones: &[(crate::window::Rect, crate::window::HitTestZone)],
+    ) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_hit_test_zones is not supported").into())
+    }
+
+    fn set_input_region(
+        &self,
+        _region: Option<&[crate::window::Rect]>,
+    ) -> Result<(), RequestError> {
+        Err(crate::error::NotSupportedError::new("set_input_region is not supported").into())
+    }
+
     fn current_monitor(&self) -> Option<RootMonitorHandle> {
         Some(self.inner.queue(|inner| inner.monitor.current_monitor()).into())
     }
@@ -492,6 +492,32 @@ impl RootActiveEventLoop for ActiveEventLoop {
         Some(RootMonitorHandle { inner: monitor::primary_monitor() })
     }
 
+    fn available_devices(&self) -> Box<dyn Iterator<Item = crate::event::DeviceInfo>> {
+        Box::new(std::iter::empty())
+    }
+
+    fn device_info(&self, _device_id: crate::event::DeviceId) -> Option<crate::event::DeviceInfo> {
+        None
+    }
+
+    fn key_repeat_info(&self) -> Option<crate::keyboard::KeyRepeatInfo> {
+        None
+    }
+
+    fn keyboard_layouts(&self) -> Box<dyn Iterator<Item = crate::keyboard::KeyboardLayout>> {
+        Box::new(std::iter::empty())
+    }
+
+    fn key_for_physical_key(
+        &self,
+        _physical_key: crate::keyboard::PhysicalKey,
+        _modifiers: crate::keyboard::ModifiersState,
+        _locked_keys: crate::keyboard::LockKeys,
+        _level3: bool,
+    ) -> Option<crate::keyboard::Key> {
+        None
+    }
+
     fn exiting(&self) -> bool {
         self.runner_shared.exit_code().is_some()
     }
@@ -1707,6 +1733,8 @@ unsafe fn public_window_callback_inner(
                     device_id: None,
                     delta: LineDelta(0.0, value),
                     phase: TouchPhase::Moved,
+                    source: None,
+                    value120: None,
                 },
             });
 
@@ -1727,6 +1755,8 @@ unsafe fn public_window_callback_inner(
                     device_id: None,
                     delta: LineDelta(value, 0.0),
                     phase: TouchPhase::Moved,
+                    source: None,
+                    value120: None,
                 },
             });
 
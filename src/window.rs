@@ -6,10 +6,17 @@ pub use cursor_icon::{CursorIcon, ParseError as CursorIconParseError};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+use crate::clipboard::ClipboardData;
 pub use crate::cursor::{BadImage, Cursor, CustomCursor, CustomCursorSource, MAX_CURSOR_SIZE};
-use crate::dpi::{PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size};
+use crate::dnd::{DragActions, DragData};
+use crate::dpi::{
+    LogicalPosition, LogicalSize, PhysicalInsets, PhysicalPosition, PhysicalSize, Position, Size,
+};
 use crate::error::RequestError;
+use crate::event::{DeviceId, MouseButton};
+use crate::event_loop::AsyncRequestSerial;
 pub use crate::icon::{BadIcon, Icon};
+use crate::keyboard::PhysicalKey;
 use crate::monitor::{MonitorHandle, VideoModeHandle};
 use crate::platform_impl::PlatformSpecificWindowAttributes;
 use crate::utils::AsAny;
@@ -68,6 +75,9 @@ pub struct WindowAttributes {
     pub active: bool,
     pub cursor: Cursor,
     pub(crate) parent_window: Option<SendSyncRawWindowHandle>,
+    pub popup: Option<PopupAttributes>,
+    pub transient_for: Option<WindowId>,
+    pub modal: bool,
     pub fullscreen: Option<Fullscreen>,
     // Platform-specific configuration.
     #[allow(dead_code)]
@@ -98,6 +108,9 @@ impl Default for WindowAttributes {
             content_protected: false,
             cursor: Cursor::default(),
             parent_window: None,
+            popup: None,
+            transient_for: None,
+            modal: false,
             active: true,
             platform_specific: Default::default(),
         }
@@ -388,6 +401,68 @@ impl WindowAttributes {
         self
     }
 
+    /// Build a popup, such as a menu or a tooltip, placed relative to its parent window.
+    ///
+    /// The position of the window is ignored, the popup is placed against the anchor rectangle
+    /// instead and kept on the monitor as described by the [`PopupAttributes`]. Its surface
+    /// size is the size of the popup. Decorations, maximizing and fullscreen don't apply to
+    /// popups.
+    ///
+    /// The popup must be dropped before its parent window. When it's dismissed by the system,
+    /// [`WindowEvent::PopupDismissed`] is delivered.
+    ///
+    /// The default is `None`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Uses `xdg_popup`, the compositor places the popup. The parent must be a
+    ///   regular window or another popup.
+    /// - **X11:** Uses an override-redirect window, with pointer and keyboard grabs.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported, a regular window is
+    ///   created.
+    ///
+    /// [`WindowEvent::PopupDismissed`]: crate::event::WindowEvent::PopupDismissed
+    #[inline]
+    pub fn with_popup(mut self, popup: Option<PopupAttributes>) -> Self {
+        self.popup = popup;
+        self
+    }
+
+    /// Build a dialog of another window, such as a file chooser or a preferences window.
+    ///
+    /// The default is `None`.
+    ///
+    /// See [`Window::set_transient_for`] for details.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** The window gets the dialog window type, unless
+    #[cfg_attr(
+        any(x11_platform, docsrs),
+        doc = "  [`WindowAttributesExtX11::with_x11_window_type`][crate::platform::x11::WindowAttributesExtX11::with_x11_window_type]"
+    )]
+    #[cfg_attr(
+        not(any(x11_platform, docsrs)),
+        doc = "  `WindowAttributesExtX11::with_x11_window_type`"
+    )]
+    ///   is used.
+    #[inline]
+    pub fn with_transient_for(mut self, parent: Option<WindowId>) -> Self {
+        self.transient_for = parent;
+        self
+    }
+
+    /// Sets whether the dialog is modal, blocking the interaction with its parent.
+    ///
+    /// The default is `false`.
+    ///
+    /// See [`Window::set_modal`] for details.
+    #[inline]
+    pub fn with_modal(mut self, modal: bool) -> Self {
+        self.modal = modal;
+        self
+    }
+
     /// Build window with parent window.
     ///
     /// The default is `None`.
@@ -402,7 +477,15 @@ impl WindowAttributes {
     ///   to the client area of its parent window. For more information, see
     ///   <https://docs.microsoft.com/en-us/windows/win32/winmsg/window-features#child-windows>
     /// - **X11**: A child window is confined to the client area of its parent window.
-    /// - **Android / iOS / Wayland / Web:** Unsupported.
+    /// - **Wayland:** A child window is a subsurface of its parent window, positioned relative to
+    ///   it. See
+    #[cfg_attr(
+        any(wayland_platform, docsrs),
+        doc = "  [`WindowExtWayland`][crate::platform::wayland::WindowExtWayland]"
+    )]
+    #[cfg_attr(not(any(wayland_platform, docsrs)), doc = "  `WindowExtWayland`")]
+    ///   for its presentation and stacking order.
+    /// - **Android / iOS / Web:** Unsupported.
     #[inline]
     pub unsafe fn with_parent_window(
         mut self,
@@ -1011,6 +1094,35 @@ pub trait Window: AsAny + Send + Sync {
     /// See [`WindowLevel`] for details.
     fn set_window_level(&self, level: WindowLevel);
 
+    /// Makes the window a dialog of `parent`, or a regular window again with `None`.
+    ///
+    /// The system keeps dialogs above their parent, and usually minimizes and restores them
+    /// along with it.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    /// - **X11:** Sets `WM_TRANSIENT_FOR`, along with the dialog window type unless another type
+    ///   was requested at creation.
+    /// - **Wayland:** Uses `xdg_toplevel.set_parent`, popups can't be dialogs.
+    fn set_transient_for(&self, parent: Option<&dyn Window>) -> Result<(), RequestError>;
+
+    /// Sets whether the dialog is modal, blocking the interaction with its parent.
+    ///
+    /// This is a hint for the system to present the dialog as modal, the parent still gets its
+    /// input events. Modality only applies to windows having a parent, see
+    /// [`Window::set_transient_for`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    /// - **X11:** Sets `_NET_WM_STATE_MODAL`.
+    /// - **Wayland:** Uses `xdg_dialog_v1`, returns an [`RequestError::NotSupported`] if the
+    ///   compositor doesn't support it.
+    fn set_modal(&self, modal: bool) -> Result<(), RequestError>;
+
     /// Sets the window icon.
     ///
     /// On Windows and X11, this is typically the small icon in the top-left
@@ -1120,6 +1232,29 @@ pub trait Window: AsAny + Send + Sync {
     /// [`WindowEvent::Focused`]: crate::event::WindowEvent::Focused
     fn has_focus(&self) -> bool;
 
+    /// Returns the keys which are pressed while the window has keyboard focus.
+    ///
+    /// Keys pressed before the window gained focus aren't reported with
+    /// [`WindowEvent::KeyboardInput`], this lets the state of the keyboard be rebuilt when it
+    /// does. Returns nothing while the window doesn't have focus.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported, no keys are returned.
+    ///
+    /// [`WindowEvent::KeyboardInput`]: crate::event::WindowEvent::KeyboardInput
+    fn pressed_keys(&self) -> Box<dyn Iterator<Item = PhysicalKey>>;
+
+    /// Returns the mouse buttons which are pressed while the pointer is over the window.
+    ///
+    /// Returns nothing while the pointer isn't over the window.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported, no buttons are returned.
+    /// - **X11:** The buttons are returned wherever the pointer is.
+    fn pressed_buttons(&self) -> Box<dyn Iterator<Item = MouseButton>>;
+
     /// Requests user attention to the window, this has no effect if the application
     /// is already focused. How requesting for user attention manifests is platform dependent,
     /// see [`UserAttentionType`] for details.
@@ -1176,6 +1311,140 @@ pub trait Window: AsAny + Send + Sync {
     /// - **iOS / Android / x11 / Wayland / Web:** Unsupported. Always returns an empty string.
     fn title(&self) -> String;
 
+    /// Replaces the clipboard contents with `data`.
+    ///
+    /// Each entry is a different representation of the same content, identified by its MIME
+    /// type. The data is kept by winit and handed out to other applications on request until
+    /// the clipboard is taken over by someone else.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Owns the `CLIPBOARD` selection. Text is additionally offered as `UTF8_STRING` and
+    ///   `STRING`. The contents are handed to the clipboard manager when the event loop exits.
+    /// - **Wayland:** The compositor only accepts the selection in response to user input, the
+    ///   request is ignored when the application hasn't received any keyboard or pointer button
+    ///   input yet.
+    /// - **iOS / Android / macOS / Orbital / Web / Windows:** Always returns
+    ///   [`RequestError::NotSupported`].
+    fn set_clipboard(&self, data: Vec<ClipboardData>) -> Result<(), RequestError>;
+
+    /// Requests the clipboard contents.
+    ///
+    /// `mime_types` are tried in order of preference, the first one offered by the clipboard
+    /// owner is transferred. The result is delivered with [`WindowEvent::ClipboardDone`] carrying
+    /// the returned serial.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **iOS / Android / macOS / Orbital / Web / Windows:** Always returns
+    ///   [`RequestError::NotSupported`].
+    ///
+    /// [`WindowEvent::ClipboardDone`]: crate::event::WindowEvent::ClipboardDone
+    fn request_clipboard(&self, mime_types: &[&str]) -> Result<AsyncRequestSerial, RequestError>;
+
+    /// Replaces the contents of the primary selection with `data`.
+    ///
+    /// The primary selection usually holds the most recently selected text and is pasted with
+    /// the middle mouse button. It works like [`Window::set_clipboard`], but is independent of
+    /// the clipboard.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Owns the `PRIMARY` selection.
+    /// - **Wayland:** Requires `zwp_primary_selection_device_manager_v1`, and the same user input
+    ///   as [`Window::set_clipboard`].
+    /// - **iOS / Android / macOS / Orbital / Web / Windows:** Always returns
+    ///   [`RequestError::NotSupported`].
+    fn set_primary_selection(&self, data: Vec<ClipboardData>) -> Result<(), RequestError>;
+
+    /// Requests the contents of the primary selection.
+    ///
+    /// Works like [`Window::request_clipboard`], the result is delivered with
+    /// [`WindowEvent::ClipboardDone`] carrying the returned serial.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Requires `zwp_primary_selection_device_manager_v1`.
+    /// - **iOS / Android / macOS / Orbital / Web / Windows:** Always returns
+    ///   [`RequestError::NotSupported`].
+    ///
+    /// [`WindowEvent::ClipboardDone`]: crate::event::WindowEvent::ClipboardDone
+    fn request_primary_selection(
+        &self,
+        mime_types: &[&str],
+    ) -> Result<AsyncRequestSerial, RequestError>;
+
+    /// Starts dragging `data` out of the window.
+    ///
+    /// Must be called while a pointer button is held, usually in response to
+    /// [`WindowEvent::PointerButton`] or a following [`WindowEvent::PointerMoved`]. The drag
+    /// follows the pointer until the button is released, its progress is reported with
+    /// [`WindowEvent::DragSource`] carrying the returned serial. `icon` is shown under the
+    /// pointer during the drag, with its hotspot at the pointer position.
+    ///
+    /// Starting a new drag cancels the current one.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Acts as an XDND source. The icon replaces the cursor during the drag.
+    /// - **Wayland:** Requires a pointer button press on the window, the drag is cancelled
+    ///   otherwise. [`DragActions::LINK`] is not supported.
+    /// - **iOS / Android / macOS / Orbital / Web / Windows:** Always returns
+    ///   [`RequestError::NotSupported`].
+    ///
+    /// [`WindowEvent::PointerButton`]: crate::event::WindowEvent::PointerButton
+    /// [`WindowEvent::PointerMoved`]: crate::event::WindowEvent::PointerMoved
+    /// [`WindowEvent::DragSource`]: crate::event::WindowEvent::DragSource
+    /// [`DragActions::LINK`]: crate::dnd::DragActions::LINK
+    fn start_drag(
+        &self,
+        data: DragData,
+        icon: Option<CustomCursor>,
+    ) -> Result<AsyncRequestSerial, RequestError>;
+
+    /// Accepts the drag over the window.
+    ///
+    /// The data will be transferred as `mime_type`, which should be one of the MIME types from
+    /// [`WindowEvent::DragEntered`], and `action` is performed on drop. It should contain a single
+    /// action allowed by the source. The response is kept until the drag leaves the window, so
+    /// it's usually updated in response to [`WindowEvent::DragEntered`] and
+    /// [`WindowEvent::DragMoved`] as the pointer moves over different parts of the window.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **iOS / Android / macOS / Orbital / Web / Windows:** Always returns
+    ///   [`RequestError::NotSupported`].
+    /// - **Wayland:** [`DragActions::LINK`] is not supported.
+    ///
+    /// [`WindowEvent::DragEntered`]: crate::event::WindowEvent::DragEntered
+    /// [`WindowEvent::DragMoved`]: crate::event::WindowEvent::DragMoved
+    fn accept_drop(&self, mime_type: &str, action: DragActions) -> Result<(), RequestError>;
+
+    /// Rejects the drag over the window, see [`Window::accept_drop`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **iOS / Android / macOS / Orbital / Web / Windows:** Always returns
+    ///   [`RequestError::NotSupported`].
+    fn reject_drop(&self) -> Result<(), RequestError>;
+
+    /// Requests the data of the drag over the window.
+    ///
+    /// Works like [`Window::request_clipboard`], the result is delivered with
+    /// [`WindowEvent::ClipboardDone`] carrying the returned serial. Must be called while the drag
+    /// is over the window or in response to [`WindowEvent::DragDropped`], the result is
+    /// [`ClipboardError::Empty`] otherwise.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **iOS / Android / macOS / Orbital / Web / Windows:** Always returns
+    ///   [`RequestError::NotSupported`].
+    ///
+    /// [`WindowEvent::ClipboardDone`]: crate::event::WindowEvent::ClipboardDone
+    /// [`WindowEvent::DragDropped`]: crate::event::WindowEvent::DragDropped
+    /// [`ClipboardError::Empty`]: crate::clipboard::ClipboardError::Empty
+    fn request_drop_data(&self, mime_types: &[&str]) -> Result<AsyncRequestSerial, RequestError>;
+
     /// Modifies the cursor icon of the window.
     ///
     /// ## Platform-specific
@@ -1185,6 +1454,33 @@ pub trait Window: AsAny + Send + Sync {
     ///   cursor is shown.
     fn set_cursor(&self, cursor: Cursor);
 
+    /// Returns the position of the pointer relative to the top-left of the surface.
+    ///
+    /// Unlike tracking [`WindowEvent::PointerMoved`], this is known before the pointer moves.
+    /// Returns `None` when the position isn't known, like while the pointer isn't over the window.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported, always returns `None`.
+    /// - **X11:** The position is returned wherever the pointer is, with [`Window::outer_position`]
+    ///   and [`Window::surface_position`] it gives the position on the desktop.
+    /// - **Wayland:** The position is only known while the pointer is over the surface.
+    ///
+    /// [`WindowEvent::PointerMoved`]: crate::event::WindowEvent::PointerMoved
+    fn pointer_position(&self) -> Option<PhysicalPosition<f64>>;
+
+    /// Returns the position of the pointer relative to the top-left of the desktop.
+    ///
+    /// This is the position [`Window::pointer_position`] has on the desktop, wherever the pointer
+    /// is.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Wayland / Web / Windows:** Always returns
+    ///   [`RequestError::NotSupported`].
+    /// - **X11:** Returns [`RequestError::Ignored`] if the pointer is on another screen.
+    fn global_pointer_position(&self) -> Result<PhysicalPosition<f64>, RequestError>;
+
     /// Changes the position of the cursor in window coordinates.
     ///
     /// ```no_run
@@ -1222,6 +1518,58 @@ pub trait Window: AsAny + Send + Sync {
     /// ```
     fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), RequestError>;
 
+    /// Sets the region of the surface [`CursorGrabMode::Confined`] confines the cursor to.
+    ///
+    /// The region is the union of the given rectangles, `None` confines the cursor to the whole
+    /// surface again. When the cursor is already confined, the new region takes effect right away.
+    ///
+    /// ```no_run
+    /// # use winit::dpi::{LogicalPosition, LogicalSize};
+    /// # use winit::window::{CursorGrabMode, Rect, Window};
+    /// # fn scope(window: &dyn Window) {
+    /// // Keep the cursor in the viewport below the toolbar.
+    /// let viewport = Rect::new(LogicalPosition::new(0.0, 48.0), LogicalSize::new(800.0, 552.0));
+    /// window.set_cursor_confine_region(Some(&[viewport])).unwrap();
+    /// window.set_cursor_grab(CursorGrabMode::Confined).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    /// - **Wayland:** Requires `zwp_pointer_constraints`.
+    fn set_cursor_confine_region(&self, region: Option<&[Rect]>) -> Result<(), RequestError>;
+
+    /// Captures the pointer, or releases it when `capture` is `false`.
+    ///
+    /// A captured pointer keeps being reported to the window while it's outside of it, with
+    /// [`WindowEvent::PointerMoved`] positions beyond the bounds of the surface, which is what
+    /// sliders and scrollbars need while they're dragged.
+    ///
+    /// The capture only lasts while a button of the pointer is held, so it's meant to be taken
+    /// when a button is pressed. It's released along with the last button, and an
+    /// [`RequestError::Ignored`] is returned when no button is pressed.
+    ///
+    /// `device_id` is the pointer reported by [`WindowEvent::PointerButton`], `None` captures the
+    /// main pointer of the seat.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    /// - **Wayland:** The compositor already captures the pointer while one of its buttons is
+    ///   pressed, so capturing only checks that one is. The device is ignored.
+    /// - **Web:** Returns an [`RequestError::Ignored`] without a `device_id`.
+    ///
+    /// [`WindowEvent::PointerMoved`]: crate::event::WindowEvent::PointerMoved
+    /// [`WindowEvent::PointerButton`]: crate::event::WindowEvent::PointerButton
+    fn set_pointer_capture(
+        &self,
+        device_id: Option<DeviceId>,
+        capture: bool,
+    ) -> Result<(), RequestError>;
+
     /// Modifies the cursor's visibility.
     ///
     /// If `false`, this will hide the cursor. If `true`, this will show the cursor.
@@ -1271,6 +1619,49 @@ pub trait Window: AsAny + Send + Sync {
     /// [window menu]: https://en.wikipedia.org/wiki/Common_menus_in_Microsoft_Windows#System_menu
     fn show_window_menu(&self, position: Position);
 
+    /// Sets the zones of the window which act like the decorations drawn by the system.
+    ///
+    /// This lets windows drawing their own decorations behave like the native ones, without
+    /// calling [`Window::drag_window`] and [`Window::drag_resize_window`] from the pointer events:
+    /// the window is moved or resized when the left button is pressed in a zone, maximized when
+    /// the caption is double-clicked and shows its [window menu] when the caption is
+    /// right-clicked. See [`HitTestZone`] for the action of each zone.
+    ///
+    /// The first zone containing the pointer applies, the rest of the window is
+    /// [`HitTestZone::Client`]. The pointer events are still delivered in all zones.
+    ///
+    /// ```no_run
+    /// # use winit::dpi::{LogicalPosition, LogicalSize};
+    /// # use winit::window::{HitTestZone, Rect, ResizeDirection, Window};
+    /// # fn scope(window: &dyn Window) {
+    /// window
+    ///     .set_hit_test_zones(&[
+    ///         (
+    ///             Rect::new(LogicalPosition::new(760.0, 0.0), LogicalSize::new(40.0, 32.0)),
+    ///             HitTestZone::MaximizeButton,
+    ///         ),
+    ///         (
+    ///             Rect::new(LogicalPosition::new(0.0, 0.0), LogicalSize::new(800.0, 32.0)),
+    ///             HitTestZone::Caption,
+    ///         ),
+    ///         (
+    ///             Rect::new(LogicalPosition::new(796.0, 0.0), LogicalSize::new(4.0, 600.0)),
+    ///             HitTestZone::Resize(ResizeDirection::East),
+    ///         ),
+    ///     ])
+    ///     .unwrap();
+    /// # }
+    /// ```
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`].
+    /// - **X11:** The window menu isn't shown.
+    ///
+    /// [window menu]: https://en.wikipedia.org/wiki/Common_menus_in_Microsoft_Windows#System_menu
+    fn set_hit_test_zones(&self, zones: &[(Rect, HitTestZone)]) -> Result<(), RequestError>;
+
     /// Modifies whether the window catches cursor events.
     ///
     /// If `true`, the window will catch the cursor events. If `false`, events are passed through
@@ -1280,8 +1671,33 @@ pub trait Window: AsAny + Send + Sync {
     /// ## Platform-specific
     ///
     /// - **iOS / Android / Web / Orbital:** Always returns an [`RequestError::NotSupported`].
+    /// - **X11 / Wayland:** The same as [`Window::set_input_region`] with no rectangles when
+    ///   `false`, and with `None` when `true`.
     fn set_cursor_hittest(&self, hittest: bool) -> Result<(), RequestError>;
 
+    /// Sets the region of the window which catches the pointer events.
+    ///
+    /// The region is the union of the given rectangles, the events outside of it are passed
+    /// through the window to the windows behind it. `None` makes the whole window catch the
+    /// events, which is the default.
+    ///
+    /// ```no_run
+    /// # use winit::dpi::{LogicalPosition, LogicalSize};
+    /// # use winit::window::{Rect, Window};
+    /// # fn scope(window: &dyn Window) {
+    /// // Only the toolbar of the overlay can be clicked.
+    /// let toolbar = Rect::new(LogicalPosition::new(0.0, 0.0), LogicalSize::new(400.0, 32.0));
+    /// window.set_input_region(Some(&[toolbar])).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`RequestError::NotSupported`], use [`Window::set_cursor_hittest`] instead.
+    /// - **Wayland:** The region takes effect with the next frame presented.
+    fn set_input_region(&self, region: Option<&[Rect]>) -> Result<(), RequestError>;
+
     /// Returns the monitor on which the window currently resides.
     ///
     /// Returns `None` if current monitor can't be detected.
@@ -1400,6 +1816,189 @@ pub enum CursorGrabMode {
     Locked,
 }
 
+/// A rectangle on the surface of a window, relative to its top-left corner.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Rect {
+    /// The position of the top-left corner of the rectangle.
+    pub position: Position,
+    pub size: Size,
+}
+
+impl Rect {
+    /// Creates a new rectangle.
+    pub fn new<P: Into<Position>, S: Into<Size>>(position: P, size: S) -> Self {
+        Self { position: position.into(), size: size.into() }
+    }
+
+    /// The position and size of the rectangle in physical pixels.
+    #[cfg_attr(not(any(x11_platform, wayland_platform)), allow(dead_code))]
+    pub(crate) fn to_physical(
+        self,
+        scale_factor: f64,
+    ) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+        (self.position.to_physical(scale_factor), self.size.to_physical(scale_factor))
+    }
+
+    /// The position and size of the rectangle in logical pixels.
+    #[cfg_attr(not(wayland_platform), allow(dead_code))]
+    pub(crate) fn to_logical(self, scale_factor: f64) -> (LogicalPosition<i32>, LogicalSize<u32>) {
+        (self.position.to_logical(scale_factor), self.size.to_logical(scale_factor))
+    }
+}
+
+/// The attributes of a popup, placed relative to its parent window.
+///
+/// Use this struct with [`WindowAttributes::with_popup`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PopupAttributes {
+    /// The window the popup belongs to, which can be another popup.
+    pub parent: WindowId,
+
+    /// The rectangle on the surface of the parent the popup is placed against, such as the
+    /// menu item opening a submenu.
+    pub anchor_rect: Rect,
+
+    /// The point of the anchor rectangle the popup is placed at.
+    pub anchor: PopupAnchor,
+
+    /// The direction the popup extends to from the anchor point.
+    pub gravity: PopupAnchor,
+
+    /// How the popup is adjusted when it doesn't fit on the monitor.
+    pub adjustment: PopupAdjustment,
+
+    /// The kind of the popup, a hint for the system.
+    pub kind: PopupKind,
+
+    /// Whether the popup grabs the pointer and the keyboard.
+    ///
+    /// The popup then gets the keyboard input, and clicks outside of the windows of the
+    /// application dismiss it along with its parent popups. This requires the popup to be
+    /// created in response to an input, like a click.
+    pub grab: bool,
+}
+
+impl PopupAttributes {
+    /// Creates the attributes of a menu opening below the anchor rectangle.
+    pub fn new(parent: WindowId, anchor_rect: Rect) -> Self {
+        Self {
+            parent,
+            anchor_rect,
+            anchor: PopupAnchor::BottomLeft,
+            gravity: PopupAnchor::BottomRight,
+            adjustment: PopupAdjustment::FLIP_X
+                | PopupAdjustment::FLIP_Y
+                | PopupAdjustment::SLIDE_X
+                | PopupAdjustment::SLIDE_Y,
+            kind: PopupKind::Menu,
+            grab: true,
+        }
+    }
+
+    /// Sets the point of the anchor rectangle the popup is placed at, and the direction it
+    /// extends to from there.
+    #[inline]
+    pub fn with_anchor(mut self, anchor: PopupAnchor, gravity: PopupAnchor) -> Self {
+        self.anchor = anchor;
+        self.gravity = gravity;
+        self
+    }
+
+    /// Sets how the popup is adjusted when it doesn't fit on the monitor.
+    #[inline]
+    pub fn with_adjustment(mut self, adjustment: PopupAdjustment) -> Self {
+        self.adjustment = adjustment;
+        self
+    }
+
+    /// Sets the kind of the popup.
+    #[inline]
+    pub fn with_kind(mut self, kind: PopupKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Sets whether the popup grabs the pointer and the keyboard.
+    #[inline]
+    pub fn with_grab(mut self, grab: bool) -> Self {
+        self.grab = grab;
+        self
+    }
+}
+
+/// A point of a rectangle, or the direction from it.
+///
+/// Used as the anchor and the gravity of [`PopupAttributes`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PopupAnchor {
+    Center,
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+bitflags::bitflags! {
+    /// How a popup is adjusted when it doesn't fit on the monitor, tried in the order flip,
+    /// slide and resize on each axis.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct PopupAdjustment: u32 {
+        /// Move the popup horizontally until it fits.
+        const SLIDE_X = 1 << 0;
+        /// Move the popup vertically until it fits.
+        const SLIDE_Y = 1 << 1;
+        /// Mirror the anchor and the gravity horizontally, if the popup fits that way.
+        const FLIP_X = 1 << 2;
+        /// Mirror the anchor and the gravity vertically, if the popup fits that way.
+        const FLIP_Y = 1 << 3;
+        /// Shrink the popup horizontally until it fits.
+        const RESIZE_X = 1 << 4;
+        /// Shrink the popup vertically until it fits.
+        const RESIZE_Y = 1 << 5;
+    }
+}
+
+/// The kind of a popup.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PopupKind {
+    /// A context menu, or a submenu.
+    #[default]
+    Menu,
+
+    /// A menu opened from a menu bar or a combo box.
+    DropdownMenu,
+
+    /// A tooltip, which usually doesn't grab the input.
+    Tooltip,
+}
+
+/// The role of a zone of a window drawing its own decorations.
+///
+/// Use this enum with [`Window::set_hit_test_zones`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum HitTestZone {
+    /// The content of the window, where the pointer only generates events.
+    Client,
+
+    /// The title bar, which moves the window when dragged, maximizes or restores it when
+    /// double-clicked and shows the window menu when right-clicked.
+    Caption,
+
+    /// An edge or a corner, which resizes the window in the direction when dragged.
+    Resize(ResizeDirection),
+
+    /// The maximize button, which maximizes or restores the window when clicked.
+    MaximizeButton,
+}
+
 /// Defines the orientation that a window resize will be performed.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]